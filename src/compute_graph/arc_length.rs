@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::ProcessingError;
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub type ArcLengthResult = Result<([Data; 2], Vec<Operation>), ProcessingError>;
+
+// Resamples a curve so that its points are evenly spaced along the curve. The output curve has
+// the same parameter as the input one, and its speed is constant. The total length of the curve
+// is exposed as a scalar.
+// The computation is split in two passes: a prefix sum of the lengths of the segments, and the
+// search of each output point among the cumulative lengths.
+pub fn create(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+) -> ArcLengthResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Arc Length node \n is missing its Geometry input ".into()))?;
+    let (curve_buffer, param) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => (buffer, param),
+        _ => return Err(ProcessingError::IncorrectInput(" the input geometry \n is not a curve ".into())),
+    };
+    let n_points = param.n_points();
+
+    // a curve has at most 256 points, so the whole prefix sum fits in a single workgroup.
+    // The scan is the simple one that doubles the offset at each step.
+    let prefix_sum_source = format!(r##"
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct LengthsBuffer {{
+    values: array<f32>;
+}};
+
+struct TotalBuffer {{
+    value: f32;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out_lengths: LengthsBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_total: TotalBuffer;
+
+var<workgroup> partial_sums: array<f32, {n_points}>;
+
+[[stage(compute), workgroup_size({n_points})]]
+fn main([[builtin(local_invocation_id)]] local_id: vec3<u32>) {{
+    let idx = local_id.x;
+    // the first point is at distance zero from the beginning of the curve
+    if (idx == 0u) {{
+        partial_sums[idx] = 0.0;
+    }} else {{
+        partial_sums[idx] = distance(in_curve.positions[idx].xyz, in_curve.positions[idx - 1u].xyz);
+    }}
+    workgroupBarrier();
+
+    for (var offset: u32 = 1u; offset < {n_points}u; offset = offset * 2u) {{
+        var addend = 0.0;
+        if (idx >= offset) {{
+            addend = partial_sums[idx - offset];
+        }}
+        workgroupBarrier();
+        partial_sums[idx] = partial_sums[idx] + addend;
+        workgroupBarrier();
+    }}
+
+    out_lengths.values[idx] = partial_sums[idx];
+    if (idx == {n_points}u - 1u) {{
+        out_total.value = partial_sums[idx];
+    }}
+}}
+"##, n_points=n_points,
+);
+
+    //println!("arc length prefix sum shader source:\n {}", &prefix_sum_source);
+
+    let lengths_buffer = util::create_storage_buffer(device, n_points * std::mem::size_of::<f32>());
+    let total_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let bind_info = vec![
+        BindInfo {
+            buffer: curve_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &lengths_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &total_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &prefix_sum_source, &bind_info);
+    let prefix_sum_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+
+    // each output point looks for the segment that contains its target length with a binary search,
+    // then it interpolates linearly between the two ends of that segment
+    let resample_source = format!(r##"
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct LengthsBuffer {{
+    values: array<f32>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(1)]] var<storage, read> in_lengths: LengthsBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out: CurveBuffer;
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    let total_length = in_lengths.values[{n_points}u - 1u];
+    let target_length = total_length * f32(idx) / f32({n_points}u - 1u);
+
+    var low: u32 = 0u;
+    var high: u32 = {n_points}u - 1u;
+    loop {{
+        if (high - low <= 1u) {{
+            break;
+        }}
+        let mid = (low + high) / 2u;
+        if (in_lengths.values[mid] <= target_length) {{
+            low = mid;
+        }} else {{
+            high = mid;
+        }}
+    }}
+
+    let segment_length = in_lengths.values[high] - in_lengths.values[low];
+    var alpha = 0.0;
+    if (segment_length > 0.0) {{
+        alpha = clamp((target_length - in_lengths.values[low]) / segment_length, 0.0, 1.0);
+    }}
+    out.positions[idx] = mix(in_curve.positions[low], in_curve.positions[high], alpha);
+}}
+"##, n_points=n_points, pps=Parameter::POINTS_PER_SEGMENT,
+);
+
+    //println!("arc length resample shader source:\n {}", &resample_source);
+
+    let output_buffer = util::create_storage_buffer(device, n_points * std::mem::size_of::<glam::Vec4>());
+    let bind_info = vec![
+        BindInfo {
+            buffer: curve_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &lengths_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &resample_source, &bind_info);
+    let resample_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param.segments, 1, 1],
+    };
+
+    let new_data = [
+        Data::Geom1D { buffer: output_buffer, param: param.clone() },
+        Data::Scalar { buffer: total_buffer },
+    ];
+
+    Ok((new_data, vec![prefix_sum_operation, resample_operation]))
+}
@@ -0,0 +1,390 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{MatcapData, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::node_graph::Colormap;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+// the buffer contains the range of values spanned by the colormap, used to draw the legend
+pub type ColormapResult = Result<(wgpu::Buffer, Vec<Operation>), ProcessingError>;
+
+const WORKGROUP_SIZE: usize = 64;
+
+// Colors the vertices of a renderable by evaluating a scalar function on each of them,
+// and mapping the value to a color. Instanced renderables (e.g. point sets) are colored per instance.
+// The function can use the coordinates x, y, z of the vertex, the parameters of the
+// rendered curve or surface and, if the geometry comes directly from a Curve or Surface node,
+// the curvature (the Gaussian curvature for surfaces).
+// The computation is split in three passes: evaluation of the function, search of the
+// range of the values and computation of the colors.
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    renderable: &MatcapData,
+    colormap: Colormap,
+    function: String,
+    expressions: Option<[String; 3]>,
+) -> ColormapResult {
+    let params: Vec<&Parameter> = match geometry_id.and_then(|data_id| data_map.get(&data_id)) {
+        Some(Data::Geom1D { param, .. }) => vec![param],
+        Some(Data::PointSet { param, .. }) => vec![param],
+        Some(Data::CurveFamily { param, .. }) => vec![param],
+        Some(Data::Geom2D { param1, param2, .. }) => vec![param1, param2],
+        _ => Vec::new(),
+    };
+    // parameters without a name cannot be used in the function
+    let names: Vec<&str> = params.iter().filter_map(|param| param.name.as_deref()).collect();
+    let named_params = names.len() == params.len();
+
+    let mut local_params = vec!["x", "y", "z"];
+    let mut params_code = String::new();
+    if named_params {
+        // the coordinates relative to the parameters are the uv coordinates of the vertices
+        for (param, (name, coord)) in params.iter().zip(names.iter().zip(["x", "y"])) {
+            local_params.push(*name);
+            params_code += &format!("    let {name} = ({begin}) + (({end}) - ({begin})) * rel.{coord};\n",
+                name=name, begin=&param.begin, end=&param.end, coord=coord);
+        }
+    }
+    // the curves of a family can also be colored by the value at which they were sampled
+    if let Some(Data::CurveFamily { curves_count, sampled_name, sampled_values, .. }) = geometry_id.and_then(|data_id| data_map.get(&data_id)) {
+        local_params.push(sampled_name.as_str());
+        params_code += &format!("    var sampled_values = array<f32, {count}>({values});\n    let {name} = sampled_values[idx / {curve_vertices}u];\n",
+            count=curves_count, values=sampled_values.join(", "), name=sampled_name, curve_vertices=renderable.vertex_count as usize / curves_count);
+    }
+    let curvature_code = match (expressions, named_params, names.as_slice()) {
+        (Some(expressions), true, &[name]) => {
+            let mut derivatives = Vec::<Vec<String>>::new();
+            for expression in expressions.iter() {
+                derivatives.push(globals.sanitize_derivatives(&[name], expression, name, 2)?);
+            }
+            local_params.push("curvature");
+            format!(r##"
+    let curve_d1 = vec3<f32>({x1}, {y1}, {z1});
+    let curve_d2 = vec3<f32>({x2}, {y2}, {z2});
+    let curvature = length(cross(curve_d1, curve_d2)) / pow(length(curve_d1), 3.0);
+"##, x1=derivatives[0][1], y1=derivatives[1][1], z1=derivatives[2][1],
+x2=derivatives[0][2], y2=derivatives[1][2], z2=derivatives[2][2],
+            )
+        },
+        (Some(expressions), true, &[a, b]) => {
+            let partials: [&[&str]; 5] = [&[a], &[b], &[a, a], &[a, b], &[b, b]];
+            let mut vectors = Vec::<String>::new();
+            for variables in partials {
+                let mut coords = Vec::<String>::new();
+                for expression in expressions.iter() {
+                    coords.push(globals.sanitize_partial_derivative(&[a, b], expression, variables)?);
+                }
+                vectors.push(format!("vec3<f32>({})", coords.join(", ")));
+            }
+            local_params.push("curvature");
+            format!(r##"
+    let surface_da = {sa};
+    let surface_db = {sb};
+    let surface_normal = normalize(cross(surface_da, surface_db));
+    let first_e = dot(surface_da, surface_da);
+    let first_f = dot(surface_da, surface_db);
+    let first_g = dot(surface_db, surface_db);
+    let second_l = dot({saa}, surface_normal);
+    let second_m = dot({sab}, surface_normal);
+    let second_n = dot({sbb}, surface_normal);
+    let curvature = (second_l * second_n - second_m * second_m) / (first_e * first_g - first_f * first_f);
+"##, sa=vectors[0], sb=vectors[1], saa=vectors[2], sab=vectors[3], sbb=vectors[4],
+            )
+        },
+        _ => String::new(),
+    };
+    let sanitized_function = globals.sanitize_expression(&local_params, &function)?;
+
+    // instanced renderables are colored per instance, using the translation of each instance
+    let (count, element_struct, read_code) = match &renderable.instance_buffer {
+        Some(_) => (
+            renderable.instance_count as usize,
+            r##"
+struct Element {
+    transform: mat4x4<f32>;
+    color: vec4<f32>;
+};
+"##,
+            format!(r##"
+    let position = elements.list[idx].transform[3];
+    let rel = vec2<f32>(f32(idx) / max(f32({count}u) - 1.0, 1.0), 0.0);
+"##, count=renderable.instance_count),
+        ),
+        None => (
+            renderable.vertex_count as usize,
+            r##"
+struct Element {
+    position: vec4<f32>;
+    normal: vec4<f32>;
+    uv_coords: vec2<f32>;
+    padding: vec2<f32>;
+    color: vec4<f32>;
+};
+"##,
+            String::from(r##"
+    let position = elements.list[idx].position;
+    let rel = elements.list[idx].uv_coords;
+"##),
+        ),
+    };
+    let elements_buffer = match &renderable.instance_buffer {
+        Some(instance_buffer) => instance_buffer,
+        None => &renderable.vertex_buffer,
+    };
+    // the shaders skip the invocations past the last element
+    let dispatch_size = (count / WORKGROUP_SIZE + 1) as u32;
+
+    // vertices that are not part of the mesh (e.g. the ones added to fill the last chunk of a model)
+    // and undefined values are marked with a huge value, and they keep the color of the material
+    let values_source = format!(r##"
+{wgsl_header}
+
+{element_struct}
+
+struct ElementsBuffer {{
+    list: array<Element>;
+}};
+
+struct ValuesBuffer {{
+    values: array<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> elements: ElementsBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out: ValuesBuffer;
+
+[[stage(compute), workgroup_size({workgroup_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    if (idx >= {count}u) {{
+        return;
+    }}
+{read_code}
+    if (position.w == 0.0) {{
+        out.values[idx] = 1e31;
+        return;
+    }}
+    let x = position.x;
+    let y = position.y;
+    let z = position.z;
+{params_code}{curvature_code}
+    let value: f32 = {function};
+    if (abs(value) < 1e30) {{
+        out.values[idx] = value;
+    }} else {{
+        out.values[idx] = 1e31;
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), element_struct=element_struct, workgroup_size=WORKGROUP_SIZE,
+count=count, read_code=read_code, params_code=params_code, curvature_code=curvature_code, function=sanitized_function,
+);
+
+    //println!("colormap values shader source:\n {}", &values_source);
+
+    let values_buffer = util::create_storage_buffer(device, count * std::mem::size_of::<f32>());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: elements_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &values_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &values_source, &bind_info);
+    let values_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [dispatch_size, 1, 1],
+    };
+
+    // the range is searched by a single invocation, just like the maximum of a Vector Field.
+    // Diverging colormaps are centered on zero, so their range is symmetric.
+    let symmetric_code = match colormap {
+        Colormap::Diverging => r##"
+    let extent = max(abs(low), abs(high));
+    low = -extent;
+    high = extent;
+"##,
+        _ => "",
+    };
+    let range_source = format!(r##"
+struct ValuesBuffer {{
+    values: array<f32>;
+}};
+
+struct RangeBuffer {{
+    low: f32;
+    high: f32;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_values: ValuesBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out: RangeBuffer;
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    var low: f32 = 1e30;
+    var high: f32 = -1e30;
+    for (var i: u32 = 0u; i < {count}u; i = i + 1u) {{
+        let value = in_values.values[i];
+        if (value < 1e30) {{
+            low = min(low, value);
+            high = max(high, value);
+        }}
+    }}
+    // no valid value at all
+    if (low > high) {{
+        low = 0.0;
+        high = 0.0;
+    }}
+{symmetric_code}
+    out.low = low;
+    out.high = high;
+}}
+"##, count=count, symmetric_code=symmetric_code,
+);
+
+    //println!("colormap range shader source:\n {}", &range_source);
+
+    let range_buffer = util::create_storage_buffer(device, 2 * std::mem::size_of::<f32>());
+    let bind_info = vec![
+        BindInfo {
+            buffer: &values_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &range_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &range_source, &bind_info);
+    let range_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+
+    let colors_source = format!(r##"
+{element_struct}
+
+struct ElementsBuffer {{
+    list: array<Element>;
+}};
+
+struct ValuesBuffer {{
+    values: array<f32>;
+}};
+
+struct RangeBuffer {{
+    low: f32;
+    high: f32;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_values: ValuesBuffer;
+[[group(0), binding(1)]] var<storage, read> in_range: RangeBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out: ElementsBuffer;
+
+{colormap_code}
+
+[[stage(compute), workgroup_size({workgroup_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    if (idx >= {count}u) {{
+        return;
+    }}
+    let value = in_values.values[idx];
+    var color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    if (value < 1e30) {{
+        let extent = in_range.high - in_range.low;
+        var t = 0.5;
+        if (extent > 0.0) {{
+            t = clamp((value - in_range.low) / extent, 0.0, 1.0);
+        }}
+        color = vec4<f32>(colormap(t), 1.0);
+    }}
+    out.list[idx].color = color;
+}}
+"##, element_struct=element_struct, colormap_code=colormap_wgsl_code(colormap), workgroup_size=WORKGROUP_SIZE,
+count=count,
+);
+
+    //println!("colormap colors shader source:\n {}", &colors_source);
+
+    let bind_info = vec![
+        BindInfo {
+            buffer: &values_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &range_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: elements_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &colors_source, &bind_info);
+    let colors_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [dispatch_size, 1, 1],
+    };
+
+    Ok((range_buffer, vec![values_operation, range_operation, colors_operation]))
+}
+
+// WGSL code of a colormap(t) function that maps values in [0, 1] to colors.
+// It must match Colormap::color(), which is used to draw the legend.
+fn colormap_wgsl_code(colormap: Colormap) -> &'static str {
+    match colormap {
+        // polynomial fit of the viridis colormap
+        Colormap::Viridis => r##"
+fn colormap(t: f32) -> vec3<f32> {
+    let c0 = vec3<f32>(0.2777273, 0.0054073, 0.3340998);
+    let c1 = vec3<f32>(0.105093, 1.404613, 1.38459);
+    let c2 = vec3<f32>(-0.3308618, 0.2148476, 0.0950952);
+    let c3 = vec3<f32>(-4.634231, -5.799101, -19.33244);
+    let c4 = vec3<f32>(6.22827, 14.17993, 56.69055);
+    let c5 = vec3<f32>(4.776385, -13.74515, -65.35303);
+    let c6 = vec3<f32>(-5.435456, 4.645853, 26.31244);
+    let color = c0 + t * (c1 + t * (c2 + t * (c3 + t * (c4 + t * (c5 + t * c6)))));
+    return clamp(color, vec3<f32>(0.0, 0.0, 0.0), vec3<f32>(1.0, 1.0, 1.0));
+}
+"##,
+        // from blue to red, going through white in the middle
+        Colormap::Diverging => r##"
+fn colormap(t: f32) -> vec3<f32> {
+    let blue = vec3<f32>(0.230, 0.299, 0.754);
+    let white = vec3<f32>(0.865, 0.865, 0.865);
+    let red = vec3<f32>(0.706, 0.016, 0.150);
+    if (t < 0.5) {
+        return mix(blue, white, 2.0 * t);
+    }
+    return mix(white, red, 2.0 * t - 1.0);
+}
+"##,
+        // the hue goes from blue to red
+        Colormap::Rainbow => r##"
+fn colormap(t: f32) -> vec3<f32> {
+    let hue = (1.0 - t) * 2.0 / 3.0;
+    let color = abs(fract(vec3<f32>(hue, hue + 2.0 / 3.0, hue + 1.0 / 3.0)) * 6.0 - 3.0) - 1.0;
+    return clamp(color, vec3<f32>(0.0, 0.0, 0.0), vec3<f32>(1.0, 1.0, 1.0));
+}
+"##,
+        Colormap::Material => r##"
+fn colormap(t: f32) -> vec3<f32> {
+    return vec3<f32>(t, t, t);
+}
+"##,
+    }
+}
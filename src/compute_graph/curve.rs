@@ -4,9 +4,11 @@ use super::Operation;
 use super::globals::Globals;
 use super::{SingleDataResult, ProcessingError};
 use super::{DataID, Data};
+use crate::node_graph::CoordinateSystem;
 use crate::util;
 use crate::shader_processing::{naga_compute_pipeline, BindInfo};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     device: &wgpu::Device,
     globals: &Globals,
@@ -15,6 +17,7 @@ pub fn create(
     fx: String,
     fy: String,
     fz: String,
+    coordinates: CoordinateSystem,
 ) -> SingleDataResult {
     //println!("new curve processing");
     let data_id = interval_id
@@ -59,9 +62,10 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     let fx = {fx};
     let fy = {fy};
     let fz = {fz};
-    output.positions[index] = vec4<f32>(fx, fy, fz, 1.0);
+    output.positions[index] = {position};
 }}
-"##, wgsl_header=globals.get_wgsl_header(), par=param_name, fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz, n_points=param.n_points()
+"##, wgsl_header=globals.get_wgsl_header(), par=param_name, fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz, n_points=param.n_points(),
+position=position_wgsl_code(coordinates),
 );
 
     //println!("shader source:\n {}", &wgsl_source);
@@ -95,3 +99,32 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
 
     Ok((new_data, operation))
 }
+
+// WGSL expression that turns the three values fx, fy and fz into a cartesian position.
+// The values are interpreted according to the coordinate system: (r, theta, z) for
+// cylindrical coordinates, (rho, theta, phi) for spherical ones, where phi is measured
+// from the positive z axis.
+pub fn position_wgsl_code(coordinates: CoordinateSystem) -> &'static str {
+    match coordinates {
+        CoordinateSystem::Cartesian
+            => "vec4<f32>(fx, fy, fz, 1.0)",
+        CoordinateSystem::Cylindrical
+            => "vec4<f32>(fx * cos(fy), fx * sin(fy), fz, 1.0)",
+        CoordinateSystem::Spherical
+            => "vec4<f32>(fx * sin(fz) * cos(fy), fx * sin(fz) * sin(fy), fx * cos(fz), 1.0)",
+    }
+}
+
+// Same as position_wgsl_code(), but it builds the cartesian expressions as text,
+// so that they can be differentiated by the parser.
+pub fn cartesian_expressions(coordinates: CoordinateSystem, [f1, f2, f3]: [String; 3]) -> [String; 3] {
+    let [f1, f2, f3] = [f1, f2, f3].map(|f| format!("({})", f));
+    match coordinates {
+        CoordinateSystem::Cartesian
+            => [f1, f2, f3],
+        CoordinateSystem::Cylindrical
+            => [format!("{}*cos{}", f1, f2), format!("{}*sin{}", f1, f2), f3],
+        CoordinateSystem::Spherical
+            => [format!("{}*sin{}*cos{}", f1, f3, f2), format!("{}*sin{}*sin{}", f1, f3, f2), format!("{}*cos{}", f1, f3)],
+    }
+}
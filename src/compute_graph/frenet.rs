@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::ProcessingError;
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub type FrameResult = Result<([Data; 4], Operation), ProcessingError>;
+pub type CurvatureResult = Result<([Data; 3], Operation), ProcessingError>;
+
+const CIRCLE_SEGMENTS: u32 = 4;
+
+// Computes the Frenet frame of a curve at the given parameter value:
+// the curve point, followed by the tangent, normal and binormal vectors.
+#[allow(clippy::too_many_arguments)]
+pub fn create_frame(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    parameter_name: String,
+    sample_value: String,
+    expressions: Option<[String; 3]>,
+) -> FrameResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Frenet Frame node \n is missing its Geometry input ".into()))?;
+    let (curve_buffer, curve_param) = get_curve(data_map, data_id)?;
+    let derivatives_code = derivatives_wgsl_code(globals, curve_param, &parameter_name, &sample_value, expressions)?;
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct VectorBuffer {{
+    direction: vec4<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_point: PointBuffer;
+[[group(0), binding(3)]] var<storage, read_write> out_tangent: VectorBuffer;
+[[group(0), binding(4)]] var<storage, read_write> out_normal: VectorBuffer;
+[[group(0), binding(5)]] var<storage, read_write> out_binormal: VectorBuffer;
+
+{derivatives_code}
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    compute_derivatives();
+    let tangent = normalize(d1);
+    let d1_cross_d2 = cross(d1, d2);
+    var binormal = vec3<f32>(0.0, 0.0, 0.0);
+    var normal = vec3<f32>(0.0, 0.0, 0.0);
+    // on straight lines and inflection points the normal is not defined
+    if (length(d1_cross_d2) > 1e-6) {{
+        binormal = normalize(d1_cross_d2);
+        normal = cross(binormal, tangent);
+    }}
+    out_point.position = vec4<f32>(position, 1.0);
+    out_tangent.direction = vec4<f32>(tangent, 0.0);
+    out_normal.direction = vec4<f32>(normal, 0.0);
+    out_binormal.direction = vec4<f32>(binormal, 0.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), derivatives_code=derivatives_code,
+);
+
+    //println!("frenet frame shader source:\n {}", &wgsl_source);
+
+    let point_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let tangent_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let normal_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let binormal_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: curve_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &point_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &tangent_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &normal_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &binormal_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+    let new_data = [
+        Data::Geom0D { buffer: point_buffer },
+        Data::Vector { buffer: tangent_buffer },
+        Data::Vector { buffer: normal_buffer },
+        Data::Vector { buffer: binormal_buffer },
+    ];
+
+    Ok((new_data, operation))
+}
+
+// Computes the curvature and the torsion of a curve at the given parameter value,
+// together with its osculating circle.
+#[allow(clippy::too_many_arguments)]
+pub fn create_curvature(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    parameter_name: String,
+    sample_value: String,
+    expressions: Option<[String; 3]>,
+) -> CurvatureResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Curvature node \n is missing its Geometry input ".into()))?;
+    let (curve_buffer, curve_param) = get_curve(data_map, data_id)?;
+    let derivatives_code = derivatives_wgsl_code(globals, curve_param, &parameter_name, &sample_value, expressions)?;
+
+    let circle_param = Parameter {
+        name: None,
+        begin: "0.0".into(),
+        end: format!("{:?}", 2.0 * std::f32::consts::PI),
+        segments: CIRCLE_SEGMENTS,
+        use_interval_as_uv: false,
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct ScalarBuffer {{
+    value: f32;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_curvature: ScalarBuffer;
+[[group(0), binding(3)]] var<storage, read_write> out_torsion: ScalarBuffer;
+[[group(0), binding(4)]] var<storage, read_write> out_circle: CurveBuffer;
+
+{derivatives_code}
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    compute_derivatives();
+    let d1_cross_d2 = cross(d1, d2);
+    let cross_length = length(d1_cross_d2);
+    var curvature = 0.0;
+    var torsion = 0.0;
+    var circle_point = position;
+    // on straight lines and inflection points the osculating circle degenerates to the curve point
+    if (cross_length > 1e-6) {{
+        let speed = length(d1);
+        curvature = cross_length / (speed * speed * speed);
+        torsion = dot(d1_cross_d2, d3) / (cross_length * cross_length);
+        let tangent = d1 / speed;
+        let normal = cross(d1_cross_d2 / cross_length, tangent);
+        let radius = 1.0 / curvature;
+        let angle = f32(global_id.x) / f32({circle_size}u - 1u) * 2.0 * 3.14159265;
+        circle_point = position + radius * (normal - cos(angle) * normal + sin(angle) * tangent);
+    }}
+    out_circle.positions[global_id.x] = vec4<f32>(circle_point, 1.0);
+    if (global_id.x == 0u) {{
+        out_curvature.value = curvature;
+        out_torsion.value = torsion;
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), derivatives_code=derivatives_code,
+pps=Parameter::POINTS_PER_SEGMENT, circle_size=circle_param.n_points(),
+);
+
+    //println!("curvature shader source:\n {}", &wgsl_source);
+
+    let curvature_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let torsion_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let circle_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * circle_param.n_points());
+
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: curve_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &curvature_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &torsion_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &circle_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [circle_param.segments, 1, 1],
+    };
+    let new_data = [
+        Data::Scalar { buffer: curvature_buffer },
+        Data::Scalar { buffer: torsion_buffer },
+        Data::Geom1D { buffer: circle_buffer, param: circle_param },
+    ];
+
+    Ok((new_data, operation))
+}
+
+fn get_curve(data_map: &BTreeMap<DataID, Data>, data_id: DataID) -> Result<(&wgpu::Buffer, &Parameter), ProcessingError> {
+    match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => Ok((buffer, param)),
+        _ => Err(ProcessingError::IncorrectInput(" the input geometry \n is not a curve ".into())),
+    }
+}
+
+// Returns the WGSL code of a compute_derivatives() function, which stores the position and
+// the first three derivatives of the curve into private variables. If the expressions that define
+// the curve are known, the derivatives are computed analytically, otherwise they are estimated
+// with finite differences on the points of the curve.
+fn derivatives_wgsl_code(
+    globals: &Globals,
+    curve_param: &Parameter,
+    parameter_name: &str,
+    sample_value: &str,
+    expressions: Option<[String; 3]>,
+) -> Result<String, ProcessingError> {
+    let sanitized_name = Globals::sanitize_variable_name(parameter_name)?;
+    let sanitized_value = globals.sanitize_expression(&[], sample_value)?;
+    match &curve_param.name {
+        Some(name) if name == &sanitized_name => (),
+        Some(_) => return Err(ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into())),
+        None => return Err(ProcessingError::IncorrectAttributes(" the curve parameter \n has no name: use a \n Name Parameters node ".into())),
+    }
+
+    let body = if let Some([fx, fy, fz]) = expressions {
+        let dx = globals.sanitize_derivatives(&[&sanitized_name], &fx, &sanitized_name, 3)?;
+        let dy = globals.sanitize_derivatives(&[&sanitized_name], &fy, &sanitized_name, 3)?;
+        let dz = globals.sanitize_derivatives(&[&sanitized_name], &fz, &sanitized_name, 3)?;
+        format!(r##"
+    let {name} = {value};
+    position = vec3<f32>({x0}, {y0}, {z0});
+    d1 = vec3<f32>({x1}, {y1}, {z1});
+    d2 = vec3<f32>({x2}, {y2}, {z2});
+    d3 = vec3<f32>({x3}, {y3}, {z3});
+"##, name=sanitized_name, value=sanitized_value,
+x0=dx[0], y0=dy[0], z0=dz[0], x1=dx[1], y1=dy[1], z1=dz[1],
+x2=dx[2], y2=dy[2], z2=dz[2], x3=dx[3], y3=dy[3], z3=dz[3],
+        )
+    } else {
+        format!(r##"
+    let size = {n_points};
+    let interval_begin: f32 = {begin};
+    let interval_end: f32 = {end};
+    let h = (interval_end - interval_begin) / f32(size - 1);
+    let value = ({value} - interval_begin) / h;
+    // the position is interpolated just like in the Sample node
+    let inf_idx = i32(clamp(floor(value), 0.0, f32(size - 1)));
+    let sup_idx = i32(clamp(ceil(value), 0.0, f32(size - 1)));
+    let alpha = fract(value);
+    position = mix(point_at(inf_idx), point_at(sup_idx), alpha);
+    // central differences need two points on each side, so the derivatives
+    // close to the ends of the curve are the ones of the nearest valid point
+    let fd_idx = clamp(i32(floor(value)), 2, size - 4);
+    let fd_alpha = clamp(value - f32(fd_idx), 0.0, 1.0);
+    d1 = mix(first_derivative(fd_idx, h), first_derivative(fd_idx + 1, h), fd_alpha);
+    d2 = mix(second_derivative(fd_idx, h), second_derivative(fd_idx + 1, h), fd_alpha);
+    d3 = mix(third_derivative(fd_idx, h), third_derivative(fd_idx + 1, h), fd_alpha);
+"##, n_points=curve_param.n_points(), begin=&curve_param.begin, end=&curve_param.end, value=sanitized_value,
+        )
+    };
+
+    Ok(format!(r##"
+var<private> position: vec3<f32>;
+var<private> d1: vec3<f32>;
+var<private> d2: vec3<f32>;
+var<private> d3: vec3<f32>;
+
+fn point_at(idx: i32) -> vec3<f32> {{
+    return in_curve.positions[idx].xyz;
+}}
+
+fn first_derivative(idx: i32, h: f32) -> vec3<f32> {{
+    return (point_at(idx + 1) - point_at(idx - 1)) / (2.0 * h);
+}}
+
+fn second_derivative(idx: i32, h: f32) -> vec3<f32> {{
+    return (point_at(idx + 1) - 2.0 * point_at(idx) + point_at(idx - 1)) / (h * h);
+}}
+
+fn third_derivative(idx: i32, h: f32) -> vec3<f32> {{
+    return (point_at(idx + 2) - 2.0 * point_at(idx + 1) + 2.0 * point_at(idx - 1) - point_at(idx - 2)) / (2.0 * h * h * h);
+}}
+
+fn compute_derivatives() {{{body}}}
+"##, body=body))
+}
@@ -3,7 +3,7 @@ use std::rc::Rc;
 
 use crate::rendering::model::MODEL_CHUNK_VERTICES;
 use super::Operation;
-use crate::rendering::StandardVertexData;
+use crate::rendering::{StandardVertexData, InstanceData};
 use super::{MatcapData, ProcessingError};
 use super::Parameter;
 use super::{DataID, Data};
@@ -36,28 +36,126 @@ pub fn create(
         Data::Geom2D {
             buffer, param1, param2,
         } => handle_2d(device, buffer, param1, param2, mask, material),
+        Data::Geom3D {
+            buffer, param1, param2, param3,
+        } => handle_3d(device, buffer, [param1, param2, param3], mask, material),
+        Data::PointSet {
+            buffer, param,
+        } => handle_point_set(device, buffer, param, thickness, material),
         Data::Prefab {
             vertex_buffer, chunks_count, index_buffer, index_count,
         } => handle_prefab(device, vertex_buffer, *chunks_count, index_buffer, *index_count, mask, material),
+        Data::Segments {
+            buffer, segments_count,
+        } => handle_segments(device, buffer, *segments_count, thickness, mask, material),
+        Data::CurveFamily {
+            buffer, param, curves_count, ..
+        } => create_tubes(device, buffer, param.n_points(), *curves_count, thickness, mask, material),
         _ => Err(ProcessingError::InternalError("Geometry render operation cannot handle the kind of data provided as input".into()))
     }
 }
 
-fn handle_0d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, thickness: usize, material_id: usize) -> MatcapResult {
+// Lines of constant u and of constant v drawn on top of a surface, each group with its own operations
+pub type LinesResult = Result<(Vec<MatcapData>, Vec<Operation>), ProcessingError>;
+
+// Draws lines_count curves of constant u and lines_count curves of constant v on a surface,
+// evenly spaced along each parameter and including the boundary ones. The curves are taken
+// from the points already computed for the surface, and turned into tubes like any other curve.
+// Geometries that are not surfaces have no u-v lines.
+pub fn create_uv_lines(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    lines_count: usize,
+    thickness: usize,
+    material: usize,
+) -> LinesResult {
+    let (buffer, param1, param2) = match geometry_id.and_then(|data_id| data_map.get(&data_id)) {
+        Some(Data::Geom2D { buffer, param1, param2 }) if lines_count > 0 => (buffer, param1, param2),
+        _ => return Ok((Vec::new(), Vec::new())),
+    };
+    let size_u = param1.n_points();
+    let size_v = param2.n_points();
+
+    let mut renderables = Vec::new();
+    let mut operations = Vec::new();
+    // lines of constant u run along v and the other way round
+    for (fixed_size, fixed_stride, line_size, line_stride) in [(size_u, 1, size_v, size_u), (size_v, size_u, size_u, 1)] {
+        let line_index = if lines_count > 1 {
+            format!("line * {}u / {}u", fixed_size - 1, lines_count - 1)
+        } else {
+            format!("{}u", (fixed_size - 1) / 2)
+        };
+
+        let wgsl_source = format!(r##"
+struct PointsBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in: PointsBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out: PointsBuffer;
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    // each line is made of the points of the surface that share the same value of one of the parameters
+    let point = global_id.x;
+    let line = global_id.y;
+    let fixed = {line_index};
+    out.positions[line * {line_size}u + point] = in.positions[fixed * {fixed_stride}u + point * {line_stride}u];
+}}
+"##, pps=Parameter::POINTS_PER_SEGMENT, line_index=line_index, line_size=line_size,
+fixed_stride=fixed_stride, line_stride=line_stride);
+
+        //println!("uv lines shader source:\n {}", &wgsl_source);
+
+        let lines_buffer = util::create_storage_buffer(device, lines_count * line_size * std::mem::size_of::<glam::Vec4>());
+        let bind_info = vec![
+            BindInfo {
+                buffer,
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+            },
+            BindInfo {
+                buffer: &lines_buffer,
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+            },
+        ];
+        let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+        operations.push(Operation {
+            bind_group,
+            pipeline: Rc::new(pipeline),
+            dim: [(line_size / Parameter::POINTS_PER_SEGMENT) as u32, lines_count as u32, 1],
+        });
+
+        let (renderable, tubes_operation) = create_tubes(device, &lines_buffer, line_size, lines_count, thickness, 0, material)?;
+        renderables.push(renderable);
+        operations.push(tubes_operation);
+    }
+
+    Ok((renderables, operations))
+}
+
+// The unit sphere used to draw points: the directions of its vertices from the center
+// (which are also their normals) and the indices of its triangles.
+fn create_icosphere(thickness: usize) -> (Vec<glam::Vec4>, Vec<u32>) {
     // Never go above a certain refinement level:
     // with a refine level of 6 we already hit the 492 points count.
     let refine_amount = std::cmp::min(thickness, 6);
-    let sphere_radius = AVAILABLE_SIZES[thickness];
 
     use hexasphere::shapes::IcoSphere;
     let sphere = IcoSphere::new(refine_amount, |_| ());
 
     let raw_points = sphere.raw_points();
 
-    let mut reference_vertices: Vec<glam::Vec4> = raw_points
+    let reference_vertices: Vec<glam::Vec4> = raw_points
         .iter()
         .map(|v| {glam::Vec4::new(v.x, v.y, v.z, 0.0)})
         .collect();
+    (reference_vertices, sphere.get_all_indices())
+}
+
+fn handle_0d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, thickness: usize, material_id: usize) -> MatcapResult {
+    let sphere_radius = AVAILABLE_SIZES[thickness];
+    let (mut reference_vertices, indices) = create_icosphere(thickness);
 
     // TODO: DRY, same code in model.rs
     let vertices_remainder = reference_vertices.len() % MODEL_CHUNK_VERTICES;
@@ -68,7 +166,6 @@ fn handle_0d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, thickness: usiz
     }
     let vertex_count = reference_vertices.len();
     let chunks_count = vertex_count / MODEL_CHUNK_VERTICES;
-    let indices = sphere.get_all_indices();
 
     use wgpu::util::DeviceExt;
     let reference_vertex_buffer = device.create_buffer_init(
@@ -81,7 +178,7 @@ fn handle_0d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, thickness: usiz
         &wgpu::util::BufferInitDescriptor {
             label: None,
             contents: bytemuck::cast_slice(&indices),
-            usage: wgpu::BufferUsages::INDEX,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
     });
 
     let wgsl_source = format!(r##"
@@ -90,6 +187,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 // input buffer will contain a single vertex, the actual point coords
@@ -127,6 +225,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     out.vertices[idx].normal = normal;
     out.vertices[idx].uv_coords = vec2<f32>(0.0, 0.0);
     out.vertices[idx].padding = vec2<f32>(0.123, 0.456);
+    out.vertices[idx].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
 }}
 "##, vertices_per_chunk=MODEL_CHUNK_VERTICES, radius=sphere_radius);
 
@@ -150,10 +249,13 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
 
     let renderable = MatcapData {
         vertex_buffer: output_buffer,
+        vertex_count: vertex_count as u32,
         index_buffer: Rc::new(index_buffer),
         index_count: indices.len() as u32,
         mask_id: 0,
         material_id,
+        instance_buffer: None,
+        instance_count: 1,
     };
     let operation = Operation {
         bind_group,
@@ -164,16 +266,123 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     Ok((renderable, operation))
 }
 
+// A point set is drawn as many copies of the same sphere, one for each point.
+// The compute shader only needs to move each copy to the position of its point.
+fn handle_point_set(device: &wgpu::Device, input_buffer: &wgpu::Buffer, param: &Parameter, thickness: usize, material_id: usize) -> MatcapResult {
+    let sphere_radius = AVAILABLE_SIZES[thickness];
+    let (reference_vertices, indices) = create_icosphere(thickness);
+    let vertices: Vec<StandardVertexData> = reference_vertices
+        .iter()
+        .map(|normal| StandardVertexData {
+            position: (sphere_radius * *normal + glam::Vec4::W).into(),
+            normal: (*normal).into(),
+            uv_coords: [0.0, 0.0],
+            _padding: [0.123, 0.456],
+            color: [0.0, 0.0, 0.0, 0.0],
+        })
+        .collect();
+
+    use wgpu::util::DeviceExt;
+    let vertex_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::MAP_READ,
+    });
+    let index_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
+    });
+
+    let wgsl_source = format!(r##"
+struct InputBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct Instance {{
+    transform: mat4x4<f32>;
+    color: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    instances: array<Instance>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in: InputBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out: OutputBuffer;
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    let position = in.positions[idx];
+    // points with w = 0 are not part of the set, their sphere is collapsed onto a single point
+    var scale = 1.0;
+    if (position.w == 0.0) {{
+        scale = 0.0;
+    }}
+    out.instances[idx].transform = mat4x4<f32>(
+        vec4<f32>(scale, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, scale, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, scale, 0.0),
+        vec4<f32>(position.xyz, 1.0),
+    );
+    // a null color means that the material is used as it is
+    out.instances[idx].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+}}
+"##, pps=Parameter::POINTS_PER_SEGMENT);
+
+    //println!("point set shader source:\n {}", &wgsl_source);
+
+    let instance_buffer = util::create_storage_buffer(device, param.n_points() * std::mem::size_of::<InstanceData>());
+    let bind_info = vec![
+        BindInfo {
+            buffer: input_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &instance_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let renderable = MatcapData {
+        vertex_buffer,
+        vertex_count: vertices.len() as u32,
+        index_buffer: Rc::new(index_buffer),
+        index_count: indices.len() as u32,
+        mask_id: 0,
+        material_id,
+        instance_buffer: Some(instance_buffer),
+        instance_count: param.n_points() as u32,
+    };
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param.segments, 1, 1],
+    };
+
+    Ok((renderable, operation))
+}
+
+fn handle_1d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, n_points: usize, thickness: usize, mask_id: usize, material_id: usize) -> MatcapResult {
+    create_tubes(device, input_buffer, n_points, 1, thickness, mask_id, material_id)
+}
+
+// Turns a list of curves into tubes. The input buffer contains the points of all the curves
+// one after the other, each curve has n_points points and is processed by its own workgroup.
 // TODO: maybe we can skip a memory barrier if we do store some extra information in the curve
 // geometry, so that we do not need to compute the entire "ref_buff". We also need to handle
 // the 90 degree curve anyway, so this code requires a bit of a rework anyway
-fn handle_1d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, n_points: usize, thickness: usize, mask_id: usize, material_id: usize) -> MatcapResult {
+fn create_tubes(device: &wgpu::Device, input_buffer: &wgpu::Buffer, n_points: usize, curves_count: usize, thickness: usize, mask_id: usize, material_id: usize) -> MatcapResult {
 
     let section_diameter = AVAILABLE_SIZES[thickness];
     let n_section_points = (thickness + 3)*2;
 
-    let (index_buffer, index_count) = create_curve_index_buffer(device, n_points, n_section_points);
-    let vertex_buffer = util::create_storage_buffer(device, n_points * n_section_points * std::mem::size_of::<StandardVertexData>());
+    let (index_buffer, index_count) = create_curve_index_buffer(device, n_points, curves_count, n_section_points);
+    let vertex_buffer = util::create_storage_buffer(device, curves_count * n_points * n_section_points * std::mem::size_of::<StandardVertexData>());
 
     let reference_vertices = create_curve_reference_points(section_diameter/2.0, n_section_points);
     use wgpu::util::DeviceExt;
@@ -190,6 +399,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 struct InputBuffer {{
@@ -217,14 +427,21 @@ var<workgroup> ref_buff: array<vec3<f32>, {dimx}>;
 
 var<workgroup> spine_alpha: array<f32, 16>;
 
+// index of the first point of the curve processed by the current workgroup
+var<private> curve_offset: i32;
+
+fn curve_point(idx: i32) -> vec4<f32> {{
+    return in.positions[curve_offset + idx];
+}}
+
 fn compute_tangent(idx: i32, size_x: i32) -> vec3<f32> {{
     var tangent: vec3<f32>;
     if (idx == 0) {{
-        tangent = (-1.5*in.positions[idx] + 2.0*in.positions[idx + 1] - 0.5*in.positions[idx + 2]).xyz;
+        tangent = (-1.5*curve_point(idx) + 2.0*curve_point(idx + 1) - 0.5*curve_point(idx + 2)).xyz;
     }} else if (idx == size_x - 1) {{
-        tangent = ( 1.5*in.positions[idx] - 2.0*in.positions[idx - 1] + 0.5*in.positions[idx - 2]).xyz;
+        tangent = ( 1.5*curve_point(idx) - 2.0*curve_point(idx - 1) + 0.5*curve_point(idx - 2)).xyz;
     }} else {{
-        tangent = (-0.5*in.positions[idx - 1] + 0.5*in.positions[idx+1]).xyz;
+        tangent = (-0.5*curve_point(idx - 1) + 0.5*curve_point(idx+1)).xyz;
     }}
 
     return normalize(tangent);
@@ -282,11 +499,12 @@ fn compute_next_spine_dr(x_i: vec3<f32>, spine_i: vec3<f32>, t_i: vec3<f32>, x_j
 }}
 
 [[stage(compute), workgroup_size({dimx})]]
-fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+fn main([[builtin(local_invocation_id)]] local_id: vec3<u32>, [[builtin(workgroup_id)]] workgroup_id: vec3<u32>) {{
     // this shader prepares the data for curve rendering.
     let x_size: i32 = {dimx};
 
-    let idx: i32 = i32(global_id.x);
+    let idx: i32 = i32(local_id.x);
+    curve_offset = i32(workgroup_id.x) * x_size;
 
     var tangent = compute_tangent(idx, x_size);
     tangent_buff[idx] = tangent;
@@ -309,7 +527,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
         // after this computation is done, we can compare the ref buffer with the spine that the next section will be using
         if (idx + 16 < x_size) {{
             let next_section_tan = compute_tangent(idx + 16, x_size);
-            let next_section_spine = compute_next_spine_dr(in.positions[idx].xyz, starting_spine, tangent, in.positions[idx+16].xyz, next_section_tan);
+            let next_section_spine = compute_next_spine_dr(curve_point(idx).xyz, starting_spine, tangent, curve_point(idx+16).xyz, next_section_tan);
             let next_default_spine = compute_default_spine(next_section_tan);
             let alpha = compute_next_section_angle(next_section_tan, next_default_spine, next_section_spine);
             spine_alpha[group_idx] = alpha;
@@ -320,7 +538,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
 
     // now all the compute threads can access the ref_buff, which contains a reference
     // vector for every frame. Each thread computes the transformed section.
-    let section_position: vec4<f32> = in.positions[idx];
+    let section_position: vec4<f32> = curve_point(idx);
     // compute the three directions for the frame: forward direction
     let frame_forward = vec4<f32>(tangent, 0.0);
     // up direction; remember that we need to rotate the spine by the sum of all previous
@@ -347,12 +565,13 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
         // the curve section is written as list of vec2 constant points, turn them into actual positions
         // or directions and multiply them by the transform matrix. Note that the new_basis
         // is orthonormal, so there is no need to compute the inverse transpose
-        let out_idx = idx * {points_per_section} + i;
+        let out_idx = (curve_offset + idx) * {points_per_section} + i;
         let section_point = vec3<f32>(0.0, ref.coords[i].x, ref.coords[i].y);
         out.vertices[out_idx].position = new_basis * vec4<f32>(section_point, 1.0);
         out.vertices[out_idx].normal = new_basis * vec4<f32>(normalize(section_point), 0.0);
         out.vertices[out_idx].uv_coords = vec2<f32>(f32(idx)/(f32(x_size) - 1.0), f32(i)/(f32({points_per_section}) - 1.0));
         out.vertices[out_idx].padding = vec2<f32>(1.123, 1.456);
+        out.vertices[out_idx].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
     }}
 }}
 "##, points_per_section=n_section_points, dimx=n_points);
@@ -379,15 +598,18 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
 
     let renderable = MatcapData {
         vertex_buffer,
+        vertex_count: (curves_count * n_points * n_section_points) as u32,
         index_buffer: Rc::new(index_buffer),
         index_count,
         mask_id,
         material_id,
+        instance_buffer: None,
+        instance_count: 1,
     };
     let operation = Operation {
         bind_group,
         pipeline: Rc::new(pipeline),
-        dim: [1, 1, 1],
+        dim: [curves_count as u32, 1, 1],
     };
 
     Ok((renderable, operation))
@@ -403,6 +625,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 struct InputBuffer {{
@@ -481,6 +704,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     out.vertices[idx].normal = vec4<f32>(normal, 0.0);
     out.vertices[idx].uv_coords = vec2<f32>(u_coord, v_coord);
     out.vertices[idx].padding = vec2<f32>(2.123, 2.456);
+    out.vertices[idx].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
 }}
 "##, pps=Parameter::POINTS_PER_SEGMENT,
 size_x=param1.n_points(), size_y=param2.n_points());
@@ -508,10 +732,204 @@ size_x=param1.n_points(), size_y=param2.n_points());
     };
     let renderable = MatcapData {
         vertex_buffer,
+        vertex_count: (param1.n_points() * param2.n_points()) as u32,
         index_buffer: Rc::new(index_buffer),
         index_count,
         mask_id,
         material_id,
+        instance_buffer: None,
+        instance_count: 1,
+    };
+
+    Ok((renderable, operation))
+}
+
+// A volume is rendered by drawing its six boundary faces. Each face is a grid over two of the
+// parameters, while the third one is fixed at the beginning or at the end of its interval.
+// Faces are laid out one after the other in the vertex buffer, in this order:
+// param3 begin, param3 end, param2 begin, param2 end, param1 begin, param1 end.
+fn handle_3d(device: &wgpu::Device, input_buffer: &wgpu::Buffer, params: [&Parameter; 3], mask_id: usize, material_id: usize) -> MatcapResult {
+    const WORKGROUP_SIZE: usize = 64;
+    let sizes = [params[0].n_points(), params[1].n_points(), params[2].n_points()];
+    // the two axes that span each pair of faces
+    let face_axes = [(0, 1), (0, 2), (1, 2)];
+
+    let flag_pattern = true;
+    let mut index_vector = Vec::<u32>::new();
+    let mut face_offsets = Vec::<usize>::new();
+    let mut vertex_count = 0;
+    for &(axis_a, axis_b) in face_axes.iter() {
+        for _side in 0..2 {
+            face_offsets.push(vertex_count);
+            index_vector.extend(create_grid_indices(sizes[axis_a], sizes[axis_b], flag_pattern, vertex_count as u32));
+            vertex_count += sizes[axis_a] * sizes[axis_b];
+        }
+    }
+    assert!(vertex_count % WORKGROUP_SIZE == 0);
+
+    use wgpu::util::DeviceExt;
+    let index_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&index_vector),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
+    });
+    let vertex_buffer = util::create_storage_buffer(device, vertex_count * std::mem::size_of::<StandardVertexData>());
+
+    let wgsl_source = format!(r##"
+struct MatcapVertex {{
+    position: vec4<f32>;
+    normal: vec4<f32>;
+    uv_coords: vec2<f32>;
+    padding: vec2<f32>;
+    color: vec4<f32>;
+}};
+
+struct InputBuffer {{
+    pos: array<vec4<f32>>;
+}};
+
+struct OutputBuffer {{
+    vertices: array<MatcapVertex>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in: InputBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out: OutputBuffer;
+
+fn volume_point(coords: vec3<u32>) -> vec3<f32> {{
+    return in.pos[coords.x + {size_x}u * (coords.y + {size_y}u * coords.z)].xyz;
+}}
+
+// picks the component of a vector selected by a step, e.g. (0, 1, 0) selects y
+fn select_component(v: vec3<u32>, step: vec3<u32>) -> u32 {{
+    let masked = v * step;
+    return masked.x + masked.y + masked.z;
+}}
+
+// tangent along one of the three parameters, computed via finite differences
+fn compute_tangent(coords: vec3<u32>, step: vec3<u32>, size: u32) -> vec3<f32> {{
+    let position = select_component(coords, step);
+    if (position == 0u) {{
+        return -1.5 * volume_point(coords) + 2.0 * volume_point(coords + step) - 0.5 * volume_point(coords + 2u * step);
+    }} else if (position == size - 1u) {{
+        return 1.5 * volume_point(coords) - 2.0 * volume_point(coords - step) + 0.5 * volume_point(coords - 2u * step);
+    }} else {{
+        return -0.5 * volume_point(coords - step) + 0.5 * volume_point(coords + step);
+    }}
+}}
+
+[[stage(compute), workgroup_size({wg_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let sizes = vec3<u32>({size_x}u, {size_y}u, {size_z}u);
+    let idx = global_id.x;
+
+    // figure out which face this vertex belongs to
+    var face = 5u;
+    var face_offset = {offset_5}u;
+    if (idx < {offset_1}u) {{
+        face = 0u;
+        face_offset = {offset_0}u;
+    }} else if (idx < {offset_2}u) {{
+        face = 1u;
+        face_offset = {offset_1}u;
+    }} else if (idx < {offset_3}u) {{
+        face = 2u;
+        face_offset = {offset_2}u;
+    }} else if (idx < {offset_4}u) {{
+        face = 3u;
+        face_offset = {offset_3}u;
+    }} else if (idx < {offset_5}u) {{
+        face = 4u;
+        face_offset = {offset_4}u;
+    }}
+
+    // the two parameters spanning the face, and the one that is fixed
+    var step_a: vec3<u32>;
+    var step_b: vec3<u32>;
+    var step_c: vec3<u32>;
+    if (face < 2u) {{
+        step_a = vec3<u32>(1u, 0u, 0u);
+        step_b = vec3<u32>(0u, 1u, 0u);
+        step_c = vec3<u32>(0u, 0u, 1u);
+    }} else if (face < 4u) {{
+        step_a = vec3<u32>(1u, 0u, 0u);
+        step_b = vec3<u32>(0u, 0u, 1u);
+        step_c = vec3<u32>(0u, 1u, 0u);
+    }} else {{
+        step_a = vec3<u32>(0u, 1u, 0u);
+        step_b = vec3<u32>(0u, 0u, 1u);
+        step_c = vec3<u32>(1u, 0u, 0u);
+    }}
+    let size_a = select_component(sizes, step_a);
+    let size_b = select_component(sizes, step_b);
+    let size_c = select_component(sizes, step_c);
+    let i = (idx - face_offset) % size_a;
+    let j = (idx - face_offset) / size_a;
+    let k = (face % 2u) * (size_c - 1u);
+    let coords = i * step_a + j * step_b + k * step_c;
+
+    let a_tangent = compute_tangent(coords, step_a, size_a);
+    let b_tangent = compute_tangent(coords, step_b, size_b);
+    let c_tangent = compute_tangent(coords, step_c, size_c);
+
+    // same criterion used for surfaces to decide when to zero out the normal vector
+    var normal = cross(a_tangent, b_tangent);
+    let len_a = length(a_tangent);
+    let len_b = length(b_tangent);
+    let len_n = length(normal);
+    if (len_n > 1e-3 * len_a * len_b) {{
+        normal = 1.0 / len_n * normal;
+    }} else {{
+        normal = vec3<f32>(0.0, 0.0, 0.0);
+    }}
+    // make the normal point outside of the volume: on the "begin" faces it must go against
+    // the direction in which the fixed parameter grows, on the "end" faces along it.
+    let outward = dot(normal, c_tangent);
+    if ((face % 2u == 0u && outward > 0.0) || (face % 2u == 1u && outward < 0.0)) {{
+        normal = -1.0 * normal;
+    }}
+
+    let u_coord = f32(i) / f32(size_a - 1u);
+    let v_coord = f32(j) / f32(size_b - 1u);
+
+    out.vertices[idx].position = vec4<f32>(volume_point(coords), 1.0);
+    out.vertices[idx].normal = vec4<f32>(normal, 0.0);
+    out.vertices[idx].uv_coords = vec2<f32>(u_coord, v_coord);
+    out.vertices[idx].padding = vec2<f32>(2.123, 2.456);
+    out.vertices[idx].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+}}
+"##, size_x=sizes[0], size_y=sizes[1], size_z=sizes[2], wg_size=WORKGROUP_SIZE,
+offset_0=face_offsets[0], offset_1=face_offsets[1], offset_2=face_offsets[2],
+offset_3=face_offsets[3], offset_4=face_offsets[4], offset_5=face_offsets[5]);
+
+    //println!("3d shader source:\n {}", &wgsl_source);
+
+    let bind_info = vec![
+        BindInfo {
+            buffer: input_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &vertex_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [(vertex_count / WORKGROUP_SIZE) as u32, 1, 1],
+    };
+    let renderable = MatcapData {
+        vertex_buffer,
+        vertex_count: vertex_count as u32,
+        index_buffer: Rc::new(index_buffer),
+        index_count: index_vector.len() as u32,
+        mask_id,
+        material_id,
+        instance_buffer: None,
+        instance_count: 1,
     };
 
     Ok((renderable, operation))
@@ -525,6 +943,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 struct VertexBuffer {{
@@ -563,10 +982,145 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     };
     let renderable = MatcapData {
         vertex_buffer: out_buffer,
+        vertex_count: (chunks_count * MODEL_CHUNK_VERTICES) as u32,
         index_buffer: Rc::clone(index_buffer),
         index_count: index_count as u32,
         mask_id,
         material_id,
+        instance_buffer: None,
+        instance_count: 1,
+    };
+
+    Ok((renderable, operation))
+}
+
+fn handle_segments(device: &wgpu::Device, input_buffer: &wgpu::Buffer, segments_count: usize, thickness: usize, mask_id: usize, material_id: usize) -> MatcapResult {
+    // Each segment is rendered as a short tube, using the same sections as the 1D curves.
+    // Since each point carries its own tangent and up direction, the sections at the shared
+    // end of two consecutive segments are identical and the tubes join seamlessly.
+    const WORKGROUP_SIZE: usize = 64;
+    let n_points = 2 * segments_count;
+
+    let section_diameter = AVAILABLE_SIZES[thickness];
+    let n_section_points = (thickness + 3)*2;
+
+    let (index_buffer, index_count) = create_segments_index_buffer(device, segments_count, n_section_points);
+    let vertex_buffer = util::create_storage_buffer(device, n_points * n_section_points * std::mem::size_of::<StandardVertexData>());
+
+    let reference_vertices = create_curve_reference_points(section_diameter/2.0, n_section_points);
+    use wgpu::util::DeviceExt;
+    let reference_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&reference_vertices),
+            usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let wgsl_source = format!(r##"
+struct MatcapVertex {{
+    position: vec4<f32>;
+    normal: vec4<f32>;
+    uv_coords: vec2<f32>;
+    padding: vec2<f32>;
+    color: vec4<f32>;
+}};
+
+struct SegmentPoint {{
+    position: vec4<f32>;
+    tangent: vec4<f32>;
+    up: vec4<f32>;
+}};
+
+struct InputBuffer {{
+    points: array<SegmentPoint>;
+}};
+
+struct ReferenceBuffer {{
+    coords: array<vec2<f32>>;
+}};
+
+struct OutputBuffer {{
+    vertices: array<MatcapVertex>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in: InputBuffer;
+[[group(0), binding(1)]] var<storage, read> ref: ReferenceBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out: OutputBuffer;
+
+[[stage(compute), workgroup_size({wg_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    if (idx >= {n_points}u) {{
+        return;
+    }}
+    let point = in.points[idx];
+
+    var new_basis: mat4x4<f32>;
+    if (dot(point.tangent.xyz, point.tangent.xyz) < 1e-12) {{
+        // unused segment: collapse the whole section into a single point
+        new_basis = mat4x4<f32>(
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            point.position,
+        );
+    }} else {{
+        let forward = normalize(point.tangent.xyz);
+        // the up direction might not be orthogonal to the tangent, e.g. after a non-uniform scaling
+        let up = normalize(point.up.xyz - forward * dot(point.up.xyz, forward));
+        let left = -1.0 * normalize(cross(forward, up));
+        new_basis = mat4x4<f32>(
+            vec4<f32>(forward, 0.0),
+            vec4<f32>(left, 0.0),
+            vec4<f32>(up, 0.0),
+            point.position,
+        );
+    }}
+
+    for (var i: i32 = 0; i < {points_per_section}; i = i + 1) {{
+        let out_idx = i32(idx) * {points_per_section} + i;
+        let section_point = vec3<f32>(0.0, ref.coords[i].x, ref.coords[i].y);
+        out.vertices[out_idx].position = new_basis * vec4<f32>(section_point, 1.0);
+        out.vertices[out_idx].normal = new_basis * vec4<f32>(normalize(section_point), 0.0);
+        out.vertices[out_idx].uv_coords = vec2<f32>(f32(idx % 2u), f32(i)/(f32({points_per_section}) - 1.0));
+        out.vertices[out_idx].padding = vec2<f32>(1.123, 1.456);
+        out.vertices[out_idx].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }}
+}}
+"##, points_per_section=n_section_points, n_points=n_points, wg_size=WORKGROUP_SIZE);
+
+    //println!("segments shader source:\n {}", &wgsl_source);
+
+    let bind_info = vec![
+        BindInfo {
+            buffer: input_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &reference_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &vertex_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let renderable = MatcapData {
+        vertex_buffer,
+        vertex_count: (n_points * n_section_points) as u32,
+        index_buffer: Rc::new(index_buffer),
+        index_count,
+        mask_id,
+        material_id,
+        instance_buffer: None,
+        instance_count: 1,
+    };
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [((n_points - 1) / WORKGROUP_SIZE + 1) as u32, 1, 1],
     };
 
     Ok((renderable, operation))
@@ -577,14 +1131,18 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
 // - create the index buffers for curve and surface rendering
 // - create the default vertices positions for each curve section
 
-fn create_curve_index_buffer(device: &wgpu::Device, x_size: usize, circle_points: usize) -> (wgpu::Buffer, u32) {
+fn create_curve_index_buffer(device: &wgpu::Device, x_size: usize, curves_count: usize, circle_points: usize) -> (wgpu::Buffer, u32) {
     assert!(circle_points > 3);
     let mut index_vector = Vec::<u32>::new();
 
-    for i in 0 .. x_size - 1 {
-        let segment = (i, i+1);
-        let mut segment_indices = create_curve_segment(segment, circle_points);
-        index_vector.append(&mut segment_indices);
+    // the curves are not connected to each other
+    for curve in 0 .. curves_count {
+        let first_section = curve * x_size;
+        for i in first_section .. first_section + x_size - 1 {
+            let segment = (i, i+1);
+            let mut segment_indices = create_curve_segment(segment, circle_points);
+            index_vector.append(&mut segment_indices);
+        }
     }
 
     // TODO: add caps
@@ -600,6 +1158,27 @@ fn create_curve_index_buffer(device: &wgpu::Device, x_size: usize, circle_points
     (index_buffer, index_vector.len() as u32)
 }
 
+fn create_segments_index_buffer(device: &wgpu::Device, segments_count: usize, circle_points: usize) -> (wgpu::Buffer, u32) {
+    assert!(circle_points > 3);
+    let mut index_vector = Vec::<u32>::new();
+
+    // unlike a curve, the segments are not connected to each other
+    for i in 0 .. segments_count {
+        let segment = (2*i, 2*i + 1);
+        let mut segment_indices = create_curve_segment(segment, circle_points);
+        index_vector.append(&mut segment_indices);
+    }
+
+    use wgpu::util::DeviceExt;
+    let index_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&index_vector),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
+    });
+    (index_buffer, index_vector.len() as u32)
+}
+
 fn create_curve_segment(segment: (usize, usize), circle_points: usize) -> Vec::<u32> {
     let mut indices = Vec::<u32>::new();
     // the variable names are a bit misleading, so here is an explanation:
@@ -632,6 +1211,19 @@ fn create_curve_reference_points(radius: f32, n_section_points: usize) -> Vec<gl
 }
 
 fn create_grid_index_buffer(device: &wgpu::Device, x_size: usize, y_size: usize, flag_pattern: bool) -> (wgpu::Buffer, u32) {
+    let index_vector = create_grid_indices(x_size, y_size, flag_pattern, 0);
+
+    use wgpu::util::DeviceExt;
+    let index_buffer = device.create_buffer_init(
+        &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&index_vector),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
+    });
+    (index_buffer, index_vector.len() as u32)
+}
+
+fn create_grid_indices(x_size: usize, y_size: usize, flag_pattern: bool, first_index: u32) -> Vec<u32> {
     // the grid has indices growing first along x, then along y
     let mut index_vector = Vec::<u32>::new();
     let num_triangles_x = x_size - 1;
@@ -639,10 +1231,10 @@ fn create_grid_index_buffer(device: &wgpu::Device, x_size: usize, y_size: usize,
     for j in 0..num_triangles_y {
         for i in 0..num_triangles_x {
             // process every quad element of the grid by producing 2 triangles
-            let bot_left_idx =  ( i  +   j   * x_size) as u32;
-            let bot_right_idx = (i+1 +   j   * x_size) as u32;
-            let top_left_idx =  ( i  + (j+1) * x_size) as u32;
-            let top_right_idx = (i+1 + (j+1) * x_size) as u32;
+            let bot_left_idx =  first_index + ( i  +   j   * x_size) as u32;
+            let bot_right_idx = first_index + (i+1 +   j   * x_size) as u32;
+            let top_left_idx =  first_index + ( i  + (j+1) * x_size) as u32;
+            let top_right_idx = first_index + (i+1 + (j+1) * x_size) as u32;
 
             // some code is shared between branches, but this makes it easier to read the code IMO
             #[allow(clippy::branches_sharing_code)]
@@ -667,14 +1259,6 @@ fn create_grid_index_buffer(device: &wgpu::Device, x_size: usize, y_size: usize,
             }
         }
     }
-
-    use wgpu::util::DeviceExt;
-    let index_buffer = device.create_buffer_init(
-        &wgpu::util::BufferInitDescriptor {
-            label: None,
-            contents: bytemuck::cast_slice(&index_vector),
-            usage: wgpu::BufferUsages::INDEX,
-    });
-    (index_buffer, index_vector.len() as u32)
+    index_vector
 }
 
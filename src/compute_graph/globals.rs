@@ -43,6 +43,18 @@ impl Globals {
         &self.buffer
     }
 
+    // the number of values stored in the buffer, constants included
+    pub fn get_values_count(&self) -> usize {
+        GLOBAL_CONSTANTS.len() + self.names.len()
+    }
+
+    // the position of a variable inside the buffer, after all the constants
+    pub fn get_variable_index(&self, name: &str) -> Option<usize> {
+        self.names.iter()
+            .position(|variable_name| variable_name == name)
+            .map(|position| GLOBAL_CONSTANTS.len() + position)
+    }
+
     pub fn sanitize_variable_name(name: &str) -> Result<String, ProcessingError> {
         let parsing_result = parse_expression(name);
         match parsing_result {
@@ -104,6 +116,31 @@ impl Globals {
         }
     }
 
+    // returns the sanitized expression followed by its first `order` derivatives
+    // with respect to `variable`, which must be one of the local parameters.
+    pub fn sanitize_derivatives(&self, local_params: &[&str], expression: &str, variable: &str, order: usize) -> Result<Vec<String>, ProcessingError> {
+        // validate all the identifiers first, the derivatives cannot introduce new ones
+        let sanitized = self.sanitize_expression(local_params, expression)?;
+        let mut ast_tree = parse_expression(expression).map_err(Self::ast_to_block_error)?;
+        let mut derivatives = vec![sanitized];
+        for _ in 0..order {
+            ast_tree = ast_tree.derivative(variable);
+            derivatives.push(ast_tree.to_string(&self.names));
+        }
+        Ok(derivatives)
+    }
+
+    // returns the sanitized partial derivative of the expression, taken once
+    // with respect to each of the `variables`, in order (e.g. ["u", "v"] for the mixed derivative).
+    pub fn sanitize_partial_derivative(&self, local_params: &[&str], expression: &str, variables: &[&str]) -> Result<String, ProcessingError> {
+        self.sanitize_expression(local_params, expression)?;
+        let mut ast_tree = parse_expression(expression).map_err(Self::ast_to_block_error)?;
+        for variable in variables {
+            ast_tree = ast_tree.derivative(variable);
+        }
+        Ok(ast_tree.to_string(&self.names))
+    }
+
     fn ast_to_block_error(error: AstError) -> ProcessingError {
         match error {
             AstError::UnreachableMatch(e) => ProcessingError::InternalError(e),
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::{DataID, Data};
+use crate::node_graph::CoordinatePlane;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+// Each cell of the grid is processed by a single invocation, the grid is made of
+// CELLS_PER_QUALITY * quality cells along each side of the domain.
+const CELLS_PER_QUALITY: usize = 16;
+const MAX_QUALITY: usize = 6;
+const WORKGROUP_SIZE: usize = 8;
+
+// The marching squares algorithm produces at most 2 segments for each cell
+const MAX_SEGMENTS_PER_CELL: usize = 2;
+
+// For each configuration of the cell corners, the pairs of edges connected by a segment.
+// Corners are numbered counter-clockwise starting from (x_min, y_min), edge i goes from
+// corner i to corner i+1. The two saddle configurations (5 and 10) assume the center of the
+// cell to be outside the curve, the shader swaps them if that is not the case.
+const SEGMENT_TABLE: [[i32; 2 * MAX_SEGMENTS_PER_CELL]; 16] = [
+    [-1, -1, -1, -1],
+    [ 3,  0, -1, -1],
+    [ 0,  1, -1, -1],
+    [ 3,  1, -1, -1],
+    [ 1,  2, -1, -1],
+    [ 3,  0,  1,  2],
+    [ 0,  2, -1, -1],
+    [ 2,  3, -1, -1],
+    [ 2,  3, -1, -1],
+    [ 0,  2, -1, -1],
+    [ 0,  1,  2,  3],
+    [ 1,  2, -1, -1],
+    [ 3,  1, -1, -1],
+    [ 0,  1, -1, -1],
+    [ 3,  0, -1, -1],
+    [-1, -1, -1, -1],
+];
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    function: String,
+    x_min: String,
+    x_max: String,
+    y_min: String,
+    y_max: String,
+    plane: CoordinatePlane,
+    center: Option<DataID>,
+    normal: Option<DataID>,
+    quality: usize,
+) -> SingleDataResult {
+    if !(1..=MAX_QUALITY).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(format!(" Implicit Curve quality \n must be an integer \n in the [1, {}] range ", MAX_QUALITY)));
+    }
+    if function.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for the implicit function ".into()));
+    }
+    let bounds = [&x_min, &x_max, &y_min, &y_max];
+    if bounds.iter().any(|bound| bound.is_empty()) {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for each side of the domain ".into()));
+    }
+
+    // The curve lies on one of the coordinate planes, unless both a point and a normal
+    // are provided: in that case it lies on the same plane a Plane node would draw.
+    let frame_buffers = match (center, normal) {
+        (None, None) => None,
+        (Some(center_id), Some(normal_id)) => {
+            let center_buffer = match data_map.get(&center_id).ok_or(ProcessingError::NoInputData)? {
+                Data::Geom0D { buffer } => buffer,
+                _ => return Err(ProcessingError::IncorrectInput(" Implicit Curve center \n is not a point ".into()))
+            };
+            let normal_buffer = match data_map.get(&normal_id).ok_or(ProcessingError::NoInputData)? {
+                Data::Vector { buffer } => buffer,
+                _ => return Err(ProcessingError::IncorrectInput(" Implicit Curve normal \n is not a vector ".into()))
+            };
+            Some((center_buffer, normal_buffer))
+        },
+        _ => return Err(ProcessingError::InputMissing(" please connect both the center \n and the normal inputs, \n or none of them ".into())),
+    };
+
+    // Sanitize all input expressions. The domain can only depend on the global variables,
+    // while the implicit function is evaluated at each point (x, y) of the grid.
+    let sanitized_function = globals.sanitize_expression(&["x", "y"], &function)?;
+    let sanitized_bounds = bounds.iter()
+        .map(|bound| globals.sanitize_expression(&[], bound))
+        .collect::<Result<Vec<String>, ProcessingError>>()?;
+
+    let frame_code = match (frame_buffers, plane) {
+        (Some(_), _) => plane_frame_wgsl_code(),
+        (None, CoordinatePlane::XY) => coordinate_frame_wgsl_code("vec3<f32>(1.0, 0.0, 0.0)", "vec3<f32>(0.0, 1.0, 0.0)"),
+        (None, CoordinatePlane::XZ) => coordinate_frame_wgsl_code("vec3<f32>(1.0, 0.0, 0.0)", "vec3<f32>(0.0, 0.0, 1.0)"),
+        (None, CoordinatePlane::YZ) => coordinate_frame_wgsl_code("vec3<f32>(0.0, 1.0, 0.0)", "vec3<f32>(0.0, 0.0, 1.0)"),
+    };
+
+    let n_cells = CELLS_PER_QUALITY * quality;
+    let segments_count = n_cells * n_cells * MAX_SEGMENTS_PER_CELL;
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct SegmentPoint {{
+    position: vec4<f32>;
+    tangent: vec4<f32>;
+    up: vec4<f32>;
+}};
+
+struct SegmentBuffer {{
+    points: array<SegmentPoint>;
+}};
+
+struct TableBuffer {{
+    edges: array<i32>;
+}};
+
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct NormalBuffer {{
+    direction: vec4<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> segment_table: TableBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_buff: SegmentBuffer;
+{frame_bindings}
+
+fn implicit_function(point: vec2<f32>) -> f32 {{
+    let x = point.x;
+    let y = point.y;
+    return {function};
+}}
+
+fn compute_tangent(point: vec2<f32>, delta: vec2<f32>) -> vec2<f32> {{
+    // the tangent is orthogonal to the gradient of the implicit function, which is
+    // computed via central differences. The orientation only depends on the point itself,
+    // so that the two segments that share an end agree on it.
+    let h = 0.05 * delta;
+    let gradient = vec2<f32>(
+        implicit_function(point + vec2<f32>(h.x, 0.0)) - implicit_function(point - vec2<f32>(h.x, 0.0)),
+        implicit_function(point + vec2<f32>(0.0, h.y)) - implicit_function(point - vec2<f32>(0.0, h.y)),
+    ) / (2.0 * h);
+    let len = length(gradient);
+    if (len > 1e-6) {{
+        return vec2<f32>(-gradient.y, gradient.x) / len;
+    }}
+    return vec2<f32>(0.0, 0.0);
+}}
+
+{frame_code}
+
+[[stage(compute), workgroup_size({wg_size}, {wg_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let n_cells = {n_cells}u;
+    let cell_index = global_id.x + n_cells * global_id.y;
+
+    let domain_min = vec2<f32>({x_min}, {y_min});
+    let domain_max = vec2<f32>({x_max}, {y_max});
+    let delta = (domain_max - domain_min) / f32(n_cells);
+
+    // evaluate the function at the corners of the cell and build the configuration index
+    var corners: array<vec2<f32>, 4>;
+    corners[0] = domain_min + delta * vec2<f32>(global_id.xy);
+    corners[1] = domain_min + delta * vec2<f32>(global_id.xy + vec2<u32>(1u, 0u));
+    corners[2] = domain_min + delta * vec2<f32>(global_id.xy + vec2<u32>(1u, 1u));
+    corners[3] = domain_min + delta * vec2<f32>(global_id.xy + vec2<u32>(0u, 1u));
+    var values: array<f32, 4>;
+    var configuration = 0u;
+    for (var i = 0u; i < 4u; i = i + 1u) {{
+        values[i] = implicit_function(corners[i]);
+        if (values[i] < 0.0) {{
+            configuration = configuration | (1u << i);
+        }}
+    }}
+    // resolve the ambiguity of the saddle configurations using the center of the cell
+    if (configuration == 5u || configuration == 10u) {{
+        let center_value = implicit_function(domain_min + delta * (vec2<f32>(global_id.xy) + vec2<f32>(0.5, 0.5)));
+        if (center_value < 0.0) {{
+            configuration = 15u - configuration;
+        }}
+    }}
+
+    // compute the intersection point of the curve with each one of the edges of the cell.
+    // Each edge is always interpolated in the same direction, so that neighbouring cells
+    // compute exactly the same point on their shared edge.
+    var edge_points: array<vec2<f32>, 4>;
+    edge_points[0] = mix(corners[0], corners[1], clamp(values[0] / (values[0] - values[1]), 0.0, 1.0));
+    edge_points[1] = mix(corners[1], corners[2], clamp(values[1] / (values[1] - values[2]), 0.0, 1.0));
+    edge_points[2] = mix(corners[3], corners[2], clamp(values[3] / (values[3] - values[2]), 0.0, 1.0));
+    edge_points[3] = mix(corners[0], corners[3], clamp(values[0] / (values[0] - values[3]), 0.0, 1.0));
+
+    let frame = embedding_frame();
+    let up = vec4<f32>(normalize(cross(frame[0].xyz, frame[1].xyz)), 0.0);
+
+    // each cell owns a fixed amount of segments. The unused ones are collapsed into
+    // a single point with no tangent, which the renderer turns into degenerate triangles.
+    let first_point = cell_index * {points_per_cell}u;
+    let first_entry = configuration * {points_per_cell}u;
+    for (var i = 0u; i < {points_per_cell}u; i = i + 1u) {{
+        let edge = segment_table.edges[first_entry + i];
+        var point: SegmentPoint;
+        if (edge < 0) {{
+            point.position = frame * vec4<f32>(domain_min, 0.0, 1.0);
+            point.tangent = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        }} else {{
+            let local_point = edge_points[edge];
+            let local_tangent = compute_tangent(local_point, delta);
+            point.position = frame * vec4<f32>(local_point, 0.0, 1.0);
+            point.tangent = frame * vec4<f32>(local_tangent, 0.0, 0.0);
+        }}
+        point.up = up;
+        out_buff.points[first_point + i] = point;
+    }}
+
+    // make sure that each segment goes in the same direction as the tangent,
+    // otherwise its tube would be rendered inside out.
+    for (var s = 0u; s < {points_per_cell}u; s = s + 2u) {{
+        let begin = out_buff.points[first_point + s];
+        let end = out_buff.points[first_point + s + 1u];
+        if (dot(end.position - begin.position, begin.tangent + end.tangent) < 0.0) {{
+            out_buff.points[first_point + s] = end;
+            out_buff.points[first_point + s + 1u] = begin;
+        }}
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), function=sanitized_function,
+x_min=sanitized_bounds[0], x_max=sanitized_bounds[1],
+y_min=sanitized_bounds[2], y_max=sanitized_bounds[3],
+frame_bindings=if frame_buffers.is_some() { FRAME_BINDINGS } else { "" },
+frame_code=frame_code, n_cells=n_cells, wg_size=WORKGROUP_SIZE,
+points_per_cell=2 * MAX_SEGMENTS_PER_CELL,
+);
+    //println!("implicit curve shader source:\n {}", &wgsl_source);
+
+    use wgpu::util::DeviceExt;
+    let table: Vec<i32> = SEGMENT_TABLE.iter().flatten().copied().collect();
+    let table_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+        label: Some("marching squares table buffer"),
+        contents: bytemuck::cast_slice(&table),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+
+    let out_buffer = util::create_storage_buffer(device, 2 * segments_count * Data::SEGMENT_POINT_SIZE);
+
+    let mut bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &table_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &out_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    if let Some((center_buffer, normal_buffer)) = frame_buffers {
+        bind_info.push(BindInfo {
+            buffer: center_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        });
+        bind_info.push(BindInfo {
+            buffer: normal_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        });
+    }
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let new_data = Data::Segments {
+        buffer: out_buffer,
+        segments_count,
+    };
+    let workgroups = (n_cells / WORKGROUP_SIZE) as u32;
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [workgroups, workgroups, 1],
+    };
+
+    Ok((new_data, operation))
+}
+
+const FRAME_BINDINGS: &str = r##"
+[[group(0), binding(3)]] var<storage, read> center: PointBuffer;
+[[group(0), binding(4)]] var<storage, read> normal: NormalBuffer;
+"##;
+
+// WGSL code for the matrix that takes a point from the (x, y) domain to the 3D scene,
+// when the curve lies on the plane spanned by two of the coordinate axes.
+fn coordinate_frame_wgsl_code(x_axis: &str, y_axis: &str) -> String {
+    format!(r##"
+fn embedding_frame() -> mat4x4<f32> {{
+    let x_axis = {x_axis};
+    let y_axis = {y_axis};
+    return mat4x4<f32>(
+        vec4<f32>(x_axis, 0.0),
+        vec4<f32>(y_axis, 0.0),
+        vec4<f32>(cross(x_axis, y_axis), 0.0),
+        vec4<f32>(0.0, 0.0, 0.0, 1.0),
+    );
+}}
+"##, x_axis=x_axis, y_axis=y_axis)
+}
+
+// WGSL code for the matrix that takes a point from the (x, y) domain to the 3D scene,
+// when the curve lies on the plane defined by a center and a normal.
+// TODO: DRY: the matrix is the same one computed in plane.rs
+fn plane_frame_wgsl_code() -> String {
+    r##"
+fn embedding_frame() -> mat4x4<f32> {
+    let direction = normalize(normal.direction);
+
+    var angle_z: f32;
+    // workaround MacOS bug: atan2 seems to give the wrong result
+    if (direction.y == 0.0) {
+        if (direction.x > 0.0) {
+            angle_z = -0.5*3.14159265;
+        } else {
+            angle_z =  0.5*3.14159265;
+        }
+    } else {
+        angle_z = -1.0 * atan2(direction.x, direction.y);
+    }
+
+    let angle_x = -1.0 * acos(direction.z);
+    let cos_t = cos(angle_z);
+    let sin_t = sin(angle_z);
+    let cos_p = cos(angle_x);
+    let sin_p = sin(angle_x);
+
+    return mat4x4<f32>(
+        vec4<f32>(         cos_t,          sin_t,   0.0, 0.0), // first column
+        vec4<f32>(-cos_p * sin_t,  cos_p * cos_t, sin_p, 0.0), // 2nd column
+        vec4<f32>( sin_p * sin_t, -sin_p * cos_t, cos_p, 0.0), // 3rd column
+        center.position, // 4th column
+    );
+}
+"##.to_string()
+}
@@ -0,0 +1,317 @@
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Data;
+use crate::rendering::model::MODEL_CHUNK_VERTICES;
+use crate::rendering::StandardVertexData;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+// Each cell of the grid is processed by a single invocation, the grid is made of
+// CELLS_PER_QUALITY * quality cells along each axis.
+const CELLS_PER_QUALITY: usize = 8;
+const MAX_QUALITY: usize = 6;
+const WORKGROUP_SIZE: usize = 4;
+
+// The marching cubes algorithm produces at most 5 triangles for each cell
+const MAX_TRIANGLES_PER_CELL: usize = 5;
+const TABLE_ROW_LENGTH: usize = 3 * MAX_TRIANGLES_PER_CELL;
+
+// corners of the unit cell. The index of each corner is the bit used for it
+// in the configuration index that is computed for each cell
+const CELL_CORNERS: [[u32; 3]; 8] = [
+    [0, 0, 0], [1, 0, 0], [1, 1, 0], [0, 1, 0],
+    [0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1],
+];
+
+// pair of corners connected by each of the 12 edges of the cell
+const CELL_EDGES: [[usize; 2]; 12] = [
+    [0, 1], [1, 2], [2, 3], [3, 0],
+    [4, 5], [5, 6], [6, 7], [7, 4],
+    [0, 4], [1, 5], [2, 6], [3, 7],
+];
+
+// corners of each face of the cell, listed counter-clockwise when looking from outside
+const CELL_FACES: [[usize; 4]; 6] = [
+    [0, 3, 2, 1], [4, 5, 6, 7],
+    [0, 1, 5, 4], [3, 7, 6, 2],
+    [0, 4, 7, 3], [1, 2, 6, 5],
+];
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    function: String,
+    x_min: String,
+    x_max: String,
+    y_min: String,
+    y_max: String,
+    z_min: String,
+    z_max: String,
+    quality: usize,
+) -> SingleDataResult {
+    if !(1..=MAX_QUALITY).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(format!(" Implicit Surface quality \n must be an integer \n in the [1, {}] range ", MAX_QUALITY)));
+    }
+    if function.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for the implicit function ".into()));
+    }
+    let bounds = [&x_min, &x_max, &y_min, &y_max, &z_min, &z_max];
+    if bounds.iter().any(|bound| bound.is_empty()) {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for each side of the \n bounding box ".into()));
+    }
+
+    // Sanitize all input expressions. The bounding box can only depend on the global variables,
+    // while the implicit function is evaluated at each point (x, y, z) of the grid.
+    let sanitized_function = globals.sanitize_expression(&["x", "y", "z"], &function)?;
+    let sanitized_bounds = bounds.iter()
+        .map(|bound| globals.sanitize_expression(&[], bound))
+        .collect::<Result<Vec<String>, ProcessingError>>()?;
+
+    let n_cells = CELLS_PER_QUALITY * quality;
+    let total_cells = n_cells * n_cells * n_cells;
+    let vertices_count = total_cells * TABLE_ROW_LENGTH;
+    // the prefab code processes the vertices in chunks, make sure we are not leaving any out
+    assert!(vertices_count % MODEL_CHUNK_VERTICES == 0);
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct MatcapVertex {{
+    position: vec4<f32>;
+    normal: vec4<f32>;
+    uv_coords: vec2<f32>;
+    padding: vec2<f32>;
+    color: vec4<f32>;
+}};
+
+struct VertexBuffer {{
+    vertices: array<MatcapVertex>;
+}};
+
+struct TableBuffer {{
+    edges: array<i32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> triangle_table: TableBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_buff: VertexBuffer;
+
+fn implicit_function(point: vec3<f32>) -> f32 {{
+    let x = point.x;
+    let y = point.y;
+    let z = point.z;
+    return {function};
+}}
+
+fn compute_normal(point: vec3<f32>, delta: vec3<f32>) -> vec3<f32> {{
+    // the normal is the gradient of the implicit function, computed via central differences
+    let h = 0.05 * delta;
+    let gradient = vec3<f32>(
+        implicit_function(point + vec3<f32>(h.x, 0.0, 0.0)) - implicit_function(point - vec3<f32>(h.x, 0.0, 0.0)),
+        implicit_function(point + vec3<f32>(0.0, h.y, 0.0)) - implicit_function(point - vec3<f32>(0.0, h.y, 0.0)),
+        implicit_function(point + vec3<f32>(0.0, 0.0, h.z)) - implicit_function(point - vec3<f32>(0.0, 0.0, h.z)),
+    ) / (2.0 * h);
+    let len = length(gradient);
+    if (len > 1e-6) {{
+        return gradient / len;
+    }}
+    return vec3<f32>(0.0, 0.0, 0.0);
+}}
+
+[[stage(compute), workgroup_size({wg_size}, {wg_size}, {wg_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let n_cells = {n_cells}u;
+    let cell_index = global_id.x + n_cells * (global_id.y + n_cells * global_id.z);
+
+    let box_min = vec3<f32>({x_min}, {y_min}, {z_min});
+    let box_max = vec3<f32>({x_max}, {y_max}, {z_max});
+    let delta = (box_max - box_min) / f32(n_cells);
+
+    // evaluate the function at the corners of the cell and build the configuration index
+    var corners: array<vec3<f32>, 8>;
+    var values: array<f32, 8>;
+    var configuration = 0u;
+    {corners_code}
+    for (var i = 0u; i < 8u; i = i + 1u) {{
+        values[i] = implicit_function(corners[i]);
+        if (values[i] < 0.0) {{
+            configuration = configuration | (1u << i);
+        }}
+    }}
+
+    // compute the intersection point of the surface with each one of the edges of the cell
+    var edge_points: array<vec3<f32>, 12>;
+    {edges_code}
+
+    // each cell owns a fixed amount of vertices. The unused ones are collapsed into
+    // degenerate triangles, which do not produce any fragment.
+    let first_vertex = cell_index * {row_length}u;
+    let first_entry = configuration * {row_length}u;
+    for (var i = 0u; i < {row_length}u; i = i + 1u) {{
+        let edge = triangle_table.edges[first_entry + i];
+        var vertex: MatcapVertex;
+        if (edge < 0) {{
+            vertex.position = vec4<f32>(box_min, 1.0);
+            vertex.normal = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+            vertex.uv_coords = vec2<f32>(0.0, 0.0);
+        }} else {{
+            let point = edge_points[edge];
+            let normal = compute_normal(point, delta);
+            // project the point on the bounding box face that is most aligned to the surface
+            let uvw = (point - box_min) / (box_max - box_min);
+            let abs_normal = abs(normal);
+            var uv_coords: vec2<f32>;
+            if (abs_normal.x >= abs_normal.y && abs_normal.x >= abs_normal.z) {{
+                uv_coords = uvw.yz;
+            }} else if (abs_normal.y >= abs_normal.z) {{
+                uv_coords = uvw.xz;
+            }} else {{
+                uv_coords = uvw.xy;
+            }}
+            vertex.position = vec4<f32>(point, 1.0);
+            vertex.normal = vec4<f32>(normal, 0.0);
+            vertex.uv_coords = uv_coords;
+        }}
+        vertex.padding = vec2<f32>(0.0, 0.0);
+        vertex.color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        out_buff.vertices[first_vertex + i] = vertex;
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), function=sanitized_function,
+x_min=sanitized_bounds[0], x_max=sanitized_bounds[1],
+y_min=sanitized_bounds[2], y_max=sanitized_bounds[3],
+z_min=sanitized_bounds[4], z_max=sanitized_bounds[5],
+corners_code=corners_wgsl_code(), edges_code=edges_wgsl_code(),
+n_cells=n_cells, wg_size=WORKGROUP_SIZE, row_length=TABLE_ROW_LENGTH,
+);
+    //println!("implicit surface shader source:\n {}", &wgsl_source);
+
+    use wgpu::util::DeviceExt;
+    let table = triangle_table();
+    let table_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+        label: Some("marching cubes table buffer"),
+        contents: bytemuck::cast_slice(&table),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    // every vertex is used by exactly one triangle, so the index buffer is just a sequence
+    let indices: Vec<u32> = (0..vertices_count as u32).collect();
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
+        label: Some("implicit surface index buffer"),
+        contents: bytemuck::cast_slice(&indices),
+        usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
+    });
+
+    let out_buffer = util::create_storage_buffer(device, std::mem::size_of::<StandardVertexData>() * vertices_count);
+
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &table_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &out_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let new_data = Data::Prefab {
+        vertex_buffer: out_buffer,
+        chunks_count: vertices_count / MODEL_CHUNK_VERTICES,
+        index_buffer: Rc::new(index_buffer),
+        index_count: vertices_count as u32,
+    };
+    let workgroups = (n_cells / WORKGROUP_SIZE) as u32;
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [workgroups, workgroups, workgroups],
+    };
+
+    Ok((new_data, operation))
+}
+
+// WGSL code that fills the `corners` array with the position of the corners of the current cell
+fn corners_wgsl_code() -> String {
+    CELL_CORNERS.iter()
+        .enumerate()
+        .map(|(i, [x, y, z])| {
+            format!("corners[{}] = box_min + delta * vec3<f32>(global_id + vec3<u32>({}u, {}u, {}u));\n", i, x, y, z)
+        })
+        .collect()
+}
+
+// WGSL code that fills the `edge_points` array by linearly interpolating the function values
+// at the two ends of each edge. If the edge is not crossed by the surface the result is unused.
+fn edges_wgsl_code() -> String {
+    CELL_EDGES.iter()
+        .enumerate()
+        .map(|(i, [a, b])| {
+            format!("edge_points[{i}] = mix(corners[{a}], corners[{b}], clamp(values[{a}] / (values[{a}] - values[{b}]), 0.0, 1.0));\n", i=i, a=a, b=b)
+        })
+        .collect()
+}
+
+fn edge_between(a: usize, b: usize) -> usize {
+    CELL_EDGES.iter()
+        .position(|edge| (edge[0] == a && edge[1] == b) || (edge[0] == b && edge[1] == a))
+        .unwrap()
+}
+
+// Build the triangle table used by the marching cubes shader. For each of the 256 possible
+// configurations of the cell corners, the table lists the edges that make up each triangle,
+// padded with -1.
+// Instead of hardcoding the classic table, the polygons are obtained by walking along the faces
+// of the cell: each face is crossed by segments that cut away the runs of corners that lie inside
+// the surface. This rule only depends on the signs at the corners of a face, therefore two
+// adjacent cells always agree on how to connect their shared edges and the mesh has no holes.
+fn triangle_table() -> Vec<i32> {
+    let mut table = Vec::<i32>::with_capacity(256 * TABLE_ROW_LENGTH);
+    for configuration in 0..256usize {
+        let inside = |corner: usize| configuration & (1 << corner) != 0;
+        // next_edge[e] is the edge reached by following the segment that starts on edge e
+        let mut next_edge: [Option<usize>; 12] = [None; 12];
+        for face in CELL_FACES.iter() {
+            // the boolean tells us if the crossing enters a run of inside corners
+            let mut crossings = Vec::<(usize, bool)>::new();
+            for i in 0..4 {
+                let (a, b) = (face[i], face[(i + 1) % 4]);
+                if inside(a) != inside(b) {
+                    crossings.push((edge_between(a, b), inside(b)));
+                }
+            }
+            for (i, &(edge, entering)) in crossings.iter().enumerate() {
+                if entering {
+                    let (exit_edge, _) = crossings[(i + 1) % crossings.len()];
+                    next_edge[edge] = Some(exit_edge);
+                }
+            }
+        }
+
+        // chain the segments into closed polygons and fan-triangulate each one of them
+        let mut row = Vec::<i32>::with_capacity(TABLE_ROW_LENGTH);
+        let mut visited = [false; 12];
+        for start in 0..12 {
+            if visited[start] || next_edge[start].is_none() {
+                continue;
+            }
+            let mut polygon = Vec::<usize>::new();
+            let mut edge = start;
+            while !visited[edge] {
+                visited[edge] = true;
+                polygon.push(edge);
+                edge = next_edge[edge].unwrap();
+            }
+            for i in 1..polygon.len() - 1 {
+                row.extend_from_slice(&[polygon[0] as i32, polygon[i] as i32, polygon[i + 1] as i32]);
+            }
+        }
+        assert!(row.len() <= TABLE_ROW_LENGTH);
+        row.resize(TABLE_ROW_LENGTH, -1);
+        table.extend(row);
+    }
+    table
+}
@@ -0,0 +1,478 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::ProcessingError;
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub type IntersectionResult = Result<(Data, Vec<Operation>), ProcessingError>;
+
+// the intersections are collected into a point set with a fixed number of points,
+// the points that are not used have w = 0 and they are not rendered.
+const MAX_INTERSECTIONS_SEGMENTS: u32 = 4;
+
+// Computes the intersections between a curve and the plane passing through the given point
+// and orthogonal to the given normal. The signed distance from the plane is evaluated on each
+// point of the curve, and a root is searched on every segment where it changes sign.
+// If the expressions of the curve are known the root is refined with bisection,
+// otherwise it is interpolated linearly between the two points of the segment.
+pub fn create_curve_plane(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    point_id: Option<DataID>,
+    normal_id: Option<DataID>,
+    expressions: Option<[String; 3]>,
+) -> IntersectionResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Curve-Plane Intersection node \n is missing its Geometry input ".into()))?;
+    let point_id = point_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Curve-Plane Intersection node \n is missing its point input ".into()))?;
+    let normal_id = normal_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Curve-Plane Intersection node \n is missing its normal input ".into()))?;
+    let (curve_buffer, curve_param) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => (buffer, param),
+        _ => return Err(ProcessingError::IncorrectInput(" the input geometry \n is not a curve ".into())),
+    };
+    let point_buffer = match data_map.get(&point_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom0D { buffer } => buffer,
+        _ => return Err(ProcessingError::IncorrectInput(" the plane point \n is not a single point ".into())),
+    };
+    let normal_buffer = match data_map.get(&normal_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Vector { buffer } => buffer,
+        _ => return Err(ProcessingError::IncorrectInput(" the plane normal \n is not a vector ".into())),
+    };
+
+    let refine_code = match (expressions, &curve_param.name) {
+        (Some([fx, fy, fz]), Some(name)) => {
+            let sanitized_fx = globals.sanitize_expression(&[name.as_str()], &fx)?;
+            let sanitized_fy = globals.sanitize_expression(&[name.as_str()], &fy)?;
+            let sanitized_fz = globals.sanitize_expression(&[name.as_str()], &fz)?;
+            format!(r##"
+fn curve_at({name}: f32) -> vec3<f32> {{
+    return vec3<f32>({fx}, {fy}, {fz});
+}}
+
+fn refine_root(idx: u32, f_0: f32, f_1: f32) -> vec3<f32> {{
+    let h = (interval_end - interval_begin) / f32({n_points}u - 1u);
+    var t_0 = interval_begin + h * f32(idx);
+    var t_1 = t_0 + h;
+    var f_low = f_0;
+    for (var i: i32 = 0; i < 24; i = i + 1) {{
+        let t_mid = 0.5 * (t_0 + t_1);
+        let f_mid = signed_distance(curve_at(t_mid));
+        if (f_low * f_mid <= 0.0) {{
+            t_1 = t_mid;
+        }} else {{
+            t_0 = t_mid;
+            f_low = f_mid;
+        }}
+    }}
+    return curve_at(0.5 * (t_0 + t_1));
+}}
+"##, name=name, fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz, n_points=curve_param.n_points(),
+            )
+        },
+        _ => String::from(r##"
+fn refine_root(idx: u32, f_0: f32, f_1: f32) -> vec3<f32> {
+    let alpha = f_0 / (f_0 - f_1);
+    return mix(in_curve.positions[idx].xyz, in_curve.positions[idx + 1u].xyz, alpha);
+}
+"##),
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct VectorBuffer {{
+    direction: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    candidates: array<vec4<f32>>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read> in_point: PointBuffer;
+[[group(0), binding(3)]] var<storage, read> in_normal: VectorBuffer;
+[[group(0), binding(4)]] var<storage, read_write> out: OutputBuffer;
+
+var<private> interval_begin: f32;
+var<private> interval_end: f32;
+
+fn signed_distance(position: vec3<f32>) -> f32 {{
+    return dot(position - in_point.position.xyz, in_normal.direction.xyz);
+}}
+
+{refine_code}
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    interval_begin = {begin};
+    interval_end = {end};
+    out.candidates[idx] = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    // a null normal does not define any plane
+    if (length(in_normal.direction.xyz) == 0.0) {{
+        return;
+    }}
+    let f_0 = signed_distance(in_curve.positions[idx].xyz);
+    if (f_0 == 0.0) {{
+        out.candidates[idx] = vec4<f32>(in_curve.positions[idx].xyz, 1.0);
+        return;
+    }}
+    // the last point has no segment after it
+    if (idx + 1u == {n_points}u) {{
+        return;
+    }}
+    let f_1 = signed_distance(in_curve.positions[idx + 1u].xyz);
+    if (f_0 * f_1 < 0.0) {{
+        out.candidates[idx] = vec4<f32>(refine_root(idx, f_0, f_1), 1.0);
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), refine_code=refine_code, pps=Parameter::POINTS_PER_SEGMENT,
+begin=&curve_param.begin, end=&curve_param.end, n_points=curve_param.n_points(),
+);
+
+    //println!("curve-plane intersection shader source:\n {}", &wgsl_source);
+
+    let candidates_buffer = util::create_storage_buffer(device, curve_param.n_points() * std::mem::size_of::<glam::Vec4>());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: curve_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: point_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: normal_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &candidates_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+    let search_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [curve_param.segments, 1, 1],
+    };
+
+    let (new_data, compact_operation) = compact_candidates(device, &candidates_buffer, curve_param.n_points());
+    Ok((new_data, vec![search_operation, compact_operation]))
+}
+
+// Computes the intersections between a surface and the line passing through the given point
+// with the given direction. Each cell of the surface is split into two triangles, which are
+// intersected with the line. If the expressions of the surface are known, each intersection
+// is then refined with a few Newton iterations on the parametric equations of the surface.
+pub fn create_line_surface(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    point_id: Option<DataID>,
+    direction_id: Option<DataID>,
+    expressions: Option<[String; 3]>,
+) -> IntersectionResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Line-Surface Intersection node \n is missing its Geometry input ".into()))?;
+    let point_id = point_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Line-Surface Intersection node \n is missing its point input ".into()))?;
+    let direction_id = direction_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Line-Surface Intersection node \n is missing its direction input ".into()))?;
+    let (surface_buffer, param_1, param_2) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom2D { buffer, param1, param2 } => (buffer, param1, param2),
+        _ => return Err(ProcessingError::IncorrectInput(" the input geometry \n is not a surface ".into())),
+    };
+    let point_buffer = match data_map.get(&point_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom0D { buffer } => buffer,
+        _ => return Err(ProcessingError::IncorrectInput(" the line point \n is not a single point ".into())),
+    };
+    let direction_buffer = match data_map.get(&direction_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Vector { buffer } => buffer,
+        _ => return Err(ProcessingError::IncorrectInput(" the line direction \n is not a vector ".into())),
+    };
+
+    let refine_code = match (expressions, &param_1.name, &param_2.name) {
+        (Some(expressions), Some(name_1), Some(name_2)) => {
+            let (a, b) = (name_1.as_str(), name_2.as_str());
+            let partials: [&[&str]; 3] = [&[], &[a], &[b]];
+            let mut vectors = Vec::<String>::new();
+            for variables in partials {
+                let mut coords = Vec::<String>::new();
+                for expression in expressions.iter() {
+                    coords.push(globals.sanitize_partial_derivative(&[a, b], expression, variables)?);
+                }
+                vectors.push(format!("vec3<f32>({})", coords.join(", ")));
+            }
+            format!(r##"
+fn surface_at({a}: f32, {b}: f32) -> vec3<f32> {{
+    return {s};
+}}
+
+fn surface_da({a}: f32, {b}: f32) -> vec3<f32> {{
+    return {sa};
+}}
+
+fn surface_db({a}: f32, {b}: f32) -> vec3<f32> {{
+    return {sb};
+}}
+
+// solves surface_at(a, b) = origin + s * direction with Newton's method, starting from the
+// parameter values found on the triangle. If the iterations do not converge, the
+// intersection with the triangle is kept.
+fn refine_root(start: vec2<f32>, fallback: vec3<f32>, origin: vec3<f32>, direction: vec3<f32>) -> vec3<f32> {{
+    var ab = start;
+    var s = dot(fallback - origin, direction) / dot(direction, direction);
+    for (var i: i32 = 0; i < 8; i = i + 1) {{
+        let f = surface_at(ab.x, ab.y) - origin - s * direction;
+        let da = surface_da(ab.x, ab.y);
+        let db = surface_db(ab.x, ab.y);
+        let det = dot(da, cross(db, -direction));
+        if (abs(det) < 1e-12) {{
+            return fallback;
+        }}
+        // Cramer's rule on the jacobian [da, db, -direction]
+        let delta_a = dot(-f, cross(db, -direction)) / det;
+        let delta_b = dot(da, cross(-f, -direction)) / det;
+        let delta_s = dot(da, cross(db, -f)) / det;
+        ab = ab + vec2<f32>(delta_a, delta_b);
+        s = s + delta_s;
+    }}
+    let refined = surface_at(ab.x, ab.y);
+    // a root that wandered far away from the triangle is not trusted
+    if (length(refined - origin - s * direction) > 1e-3 || distance(refined, fallback) > 4.0 * cell_size) {{
+        return fallback;
+    }}
+    return refined;
+}}
+"##, a=a, b=b, s=vectors[0], sa=vectors[1], sb=vectors[2],
+            )
+        },
+        _ => String::from(r##"
+fn refine_root(start: vec2<f32>, fallback: vec3<f32>, origin: vec3<f32>, direction: vec3<f32>) -> vec3<f32> {
+    return fallback;
+}
+"##),
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct VectorBuffer {{
+    direction: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    candidates: array<vec4<f32>>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_surface: SurfaceBuffer;
+[[group(0), binding(2)]] var<storage, read> in_point: PointBuffer;
+[[group(0), binding(3)]] var<storage, read> in_direction: VectorBuffer;
+[[group(0), binding(4)]] var<storage, read_write> out: OutputBuffer;
+
+var<private> cell_size: f32;
+
+fn point_at(i: u32, j: u32) -> vec3<f32> {{
+    return in_surface.positions[i + {size_a}u * j].xyz;
+}}
+
+// intersection of the line with the triangle p0, p1, p2. Returns the barycentric
+// coordinates of the intersection with respect to p1 and p2, and w = 1 if it exists.
+fn intersect_triangle(p0: vec3<f32>, p1: vec3<f32>, p2: vec3<f32>, origin: vec3<f32>, direction: vec3<f32>) -> vec4<f32> {{
+    let e1 = p1 - p0;
+    let e2 = p2 - p0;
+    let p = cross(direction, e2);
+    let det = dot(e1, p);
+    if (abs(det) < 1e-12) {{
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }}
+    let t = origin - p0;
+    let u = dot(t, p) / det;
+    let q = cross(t, e1);
+    let v = dot(direction, q) / det;
+    if (u < 0.0 || v < 0.0 || u + v > 1.0) {{
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }}
+    return vec4<f32>(u, v, 0.0, 1.0);
+}}
+
+{refine_code}
+
+[[stage(compute), workgroup_size({pps}, {pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let i = global_id.x;
+    let j = global_id.y;
+    let idx = i + {size_a}u * j;
+    out.candidates[idx] = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    let origin = in_point.position.xyz;
+    let direction = in_direction.direction.xyz;
+    // the last row and column have no cells after them, and a null vector does not define any line
+    if (i + 1u == {size_a}u || j + 1u == {size_b}u || length(direction) == 0.0) {{
+        return;
+    }}
+    let begin_a: f32 = {begin_a};
+    let end_a: f32 = {end_a};
+    let begin_b: f32 = {begin_b};
+    let end_b: f32 = {end_b};
+    let ha = (end_a - begin_a) / f32({size_a}u - 1u);
+    let hb = (end_b - begin_b) / f32({size_b}u - 1u);
+
+    let p00 = point_at(i, j);
+    let p10 = point_at(i + 1u, j);
+    let p01 = point_at(i, j + 1u);
+    let p11 = point_at(i + 1u, j + 1u);
+    cell_size = max(distance(p00, p11), distance(p10, p01));
+
+    // first triangle: p00, p10, p11
+    let first_hit = intersect_triangle(p00, p10, p11, origin, direction);
+    if (first_hit.w != 0.0) {{
+        let position = p00 + first_hit.x * (p10 - p00) + first_hit.y * (p11 - p00);
+        let start = vec2<f32>(begin_a + ha * (f32(i) + first_hit.x + first_hit.y), begin_b + hb * (f32(j) + first_hit.y));
+        out.candidates[idx] = vec4<f32>(refine_root(start, position, origin, direction), 1.0);
+        return;
+    }}
+    // second triangle: p00, p11, p01
+    let second_hit = intersect_triangle(p00, p11, p01, origin, direction);
+    if (second_hit.w != 0.0) {{
+        let position = p00 + second_hit.x * (p11 - p00) + second_hit.y * (p01 - p00);
+        let start = vec2<f32>(begin_a + ha * (f32(i) + second_hit.x), begin_b + hb * (f32(j) + second_hit.x + second_hit.y));
+        out.candidates[idx] = vec4<f32>(refine_root(start, position, origin, direction), 1.0);
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), refine_code=refine_code, pps=Parameter::POINTS_PER_SEGMENT,
+size_a=param_1.n_points(), size_b=param_2.n_points(),
+begin_a=&param_1.begin, end_a=&param_1.end, begin_b=&param_2.begin, end_b=&param_2.end,
+);
+
+    //println!("line-surface intersection shader source:\n {}", &wgsl_source);
+
+    let n_candidates = param_1.n_points() * param_2.n_points();
+    let candidates_buffer = util::create_storage_buffer(device, n_candidates * std::mem::size_of::<glam::Vec4>());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: surface_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: point_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: direction_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &candidates_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+    let search_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param_1.segments, param_2.segments, 1],
+    };
+
+    let (new_data, compact_operation) = compact_candidates(device, &candidates_buffer, n_candidates);
+    Ok((new_data, vec![search_operation, compact_operation]))
+}
+
+// Moves the candidates that contain an intersection (w != 0) at the beginning of the output
+// point set, keeping their order and skipping the duplicates, which appear when an
+// intersection falls exactly on a point or on an edge shared by two segments or cells.
+// This is done by a single invocation, the number of intersections is small anyway.
+fn compact_candidates(device: &wgpu::Device, candidates_buffer: &wgpu::Buffer, n_candidates: usize) -> (Data, Operation) {
+    let param = Parameter {
+        name: None,
+        segments: MAX_INTERSECTIONS_SEGMENTS,
+        begin: "0.0".into(),
+        end: "1.0".into(),
+        use_interval_as_uv: false,
+    };
+
+    let wgsl_source = format!(r##"
+struct PointsBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_candidates: PointsBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out: PointsBuffer;
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    var count: u32 = 0u;
+    for (var i: u32 = 0u; i < {n_candidates}u; i = i + 1u) {{
+        let candidate = in_candidates.positions[i];
+        if (candidate.w == 0.0 || count == {max_count}u) {{
+            continue;
+        }}
+        var duplicate = false;
+        for (var k: u32 = 0u; k < count; k = k + 1u) {{
+            if (distance(out.positions[k].xyz, candidate.xyz) < 1e-4) {{
+                duplicate = true;
+                break;
+            }}
+        }}
+        if (!duplicate) {{
+            out.positions[count] = candidate;
+            count = count + 1u;
+        }}
+    }}
+    for (var k: u32 = count; k < {max_count}u; k = k + 1u) {{
+        out.positions[k] = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }}
+}}
+"##, n_candidates=n_candidates, max_count=param.n_points(),
+);
+
+    //println!("intersection compaction shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, param.n_points() * std::mem::size_of::<glam::Vec4>());
+    let bind_info = vec![
+        BindInfo {
+            buffer: candidates_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+
+    (Data::PointSet { buffer: output_buffer, param }, operation)
+}
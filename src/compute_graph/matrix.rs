@@ -5,6 +5,7 @@ use super::Operation;
 use super::globals::Globals;
 use super::{SingleDataResult, ProcessingError};
 use super::{DataID, Data};
+use super::Parameter;
 use crate::util;
 use crate::shader_processing::{naga_compute_pipeline, BindInfo};
 
@@ -266,3 +267,292 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
         Ok((new_data, operation))
     }
 }
+
+pub fn create_from_scale(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    x: String,
+    y: String,
+    z: String,
+) -> SingleDataResult {
+    let row_1 = [x, "0.0".into(), "0.0".into(), "0.0".into()];
+    let row_2 = ["0.0".into(), y, "0.0".into(), "0.0".into()];
+    let row_3 = ["0.0".into(), "0.0".into(), z, "0.0".into()];
+    create_from_rows(
+        device,
+        globals,
+        data_map,
+        None,
+        row_1,
+        row_2,
+        row_3,
+    )
+}
+
+// rotation of the given angle around an arbitrary axis through the origin, using Rodrigues' formula
+pub fn create_from_axis_angle(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    axis_id: Option<DataID>,
+    angle: String,
+) -> SingleDataResult {
+    let data_id = axis_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Axis Rotation Matrix node \n is missing its axis input ".into()))?;
+    let found_data = data_map
+        .get(&data_id)
+        .ok_or(ProcessingError::NoInputData)?;
+    let axis_buffer = match found_data {
+        Data::Vector { buffer } => buffer,
+        _ => return Err(ProcessingError::IncorrectInput(" Axis Rotation Matrix input \n is not a vector ".into()))
+    };
+    let sanitized_angle = globals.sanitize_expression(&[], &angle)?;
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct VectorBuffer {{
+    direction: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    matrix: mat4x4<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> input: VectorBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: OutputBuffer;
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    // a null axis gives no rotation at all
+    if (length(input.direction.xyz) < 1e-6) {{
+        output.matrix = mat4x4<f32>(
+            vec4<f32>(1.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 1.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 1.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 1.0),
+        );
+        return;
+    }}
+    let k = normalize(input.direction.xyz);
+    let angle = {angle};
+    let c = cos(angle);
+    let s = sin(angle);
+    let t = 1.0 - c;
+    output.matrix = mat4x4<f32>(
+        vec4<f32>(t * k.x * k.x + c, t * k.x * k.y + s * k.z, t * k.x * k.z - s * k.y, 0.0),
+        vec4<f32>(t * k.x * k.y - s * k.z, t * k.y * k.y + c, t * k.y * k.z + s * k.x, 0.0),
+        vec4<f32>(t * k.x * k.z + s * k.y, t * k.y * k.z - s * k.x, t * k.z * k.z + c, 0.0),
+        vec4<f32>(0.0, 0.0, 0.0, 1.0),
+    );
+}}
+"##, wgsl_header=globals.get_wgsl_header(), angle=sanitized_angle,
+);
+
+    //println!("axis rotation matrix wgsl shader: {}", wgsl_source);
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Mat4>());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: axis_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+    let new_data = Data::Matrix0D {
+        buffer: output_buffer,
+    };
+    Ok((new_data, operation))
+}
+
+// The product applies the second matrix first, just like transforming a geometry
+// with the second matrix and then transforming the result with the first one.
+pub fn create_product(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    first_id: Option<DataID>,
+    second_id: Option<DataID>,
+) -> SingleDataResult {
+    let data_id = first_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Matrix Product node \n is missing its first input ".into()))?;
+    let (first_buffer, first_param) = get_matrix(data_map, data_id)?;
+    let data_id = second_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Matrix Product node \n is missing its second input ".into()))?;
+    let (second_buffer, second_param) = get_matrix(data_map, data_id)?;
+
+    let param = match (first_param, second_param) {
+        (None, None) => None,
+        (Some(param), None) | (None, Some(param)) => Some(param),
+        (Some(param), Some(other)) if param.is_equal(other)? => Some(param),
+        (Some(_), Some(_)) => return Err(ProcessingError::IncorrectInput(" the two matrices \n depend on different parameters, \n which is not supported ".into())),
+    };
+    // a single matrix is multiplied by all the matrices of the other input
+    let first_idx = if first_param.is_some() { "index" } else { "0u" };
+    let second_idx = if second_param.is_some() { "index" } else { "0u" };
+    let body = format!("let result = in_first.matrices[{}] * in_second.matrices[{}];", first_idx, second_idx);
+
+    let wgsl_source = format!(r##"
+struct MatrixBuffer {{
+    matrices: array<mat4x4<f32>>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_first: MatrixBuffer;
+[[group(0), binding(1)]] var<storage, read> in_second: MatrixBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: MatrixBuffer;
+
+[[stage(compute), workgroup_size({workgroup_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.x;
+    {body}
+    output.matrices[index] = result;
+}}
+"##, workgroup_size=if param.is_some() { 16 } else { 1 }, body=body,
+);
+
+    //println!("matrix product wgsl shader: {}", wgsl_source);
+    let bind_info = vec![
+        BindInfo {
+            buffer: first_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: second_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+    ];
+    matrix_operation(device, &wgsl_source, bind_info, param.cloned())
+}
+
+pub fn create_inverse(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    matrix_id: Option<DataID>,
+) -> SingleDataResult {
+    let data_id = matrix_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Inverse Matrix node \n is missing its input ".into()))?;
+    let (buffer, param) = get_matrix(data_map, data_id)?;
+
+    // the inverse of the linear part is computed from its adjugate,
+    // then the translation is undone by applying the inverse to it
+    unary_matrix_operation(device, buffer, param, r##"
+    let c0 = m[0].xyz;
+    let c1 = m[1].xyz;
+    let c2 = m[2].xyz;
+    let det = dot(c0, cross(c1, c2));
+    var result = mat4x4<f32>(
+        vec4<f32>(0.0, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, 0.0, 0.0),
+        vec4<f32>(0.0, 0.0, 0.0, 1.0),
+    );
+    // a singular matrix has no inverse: the result collapses everything to the origin
+    if (abs(det) > 1e-8) {
+        let inverse = transpose(mat3x3<f32>(cross(c1, c2), cross(c2, c0), cross(c0, c1))) * (1.0 / det);
+        let translation = -(inverse * m[3].xyz);
+        result = mat4x4<f32>(
+            vec4<f32>(inverse[0], 0.0),
+            vec4<f32>(inverse[1], 0.0),
+            vec4<f32>(inverse[2], 0.0),
+            vec4<f32>(translation, 1.0),
+        );
+    }
+"##)
+}
+
+// Only the 3x3 linear part is transposed, while the translation is left untouched:
+// this way the transpose of a rotation is still the inverse rotation.
+pub fn create_transpose(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    matrix_id: Option<DataID>,
+) -> SingleDataResult {
+    let data_id = matrix_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Transpose Matrix node \n is missing its input ".into()))?;
+    let (buffer, param) = get_matrix(data_map, data_id)?;
+
+    unary_matrix_operation(device, buffer, param, r##"
+    let transposed = transpose(mat3x3<f32>(m[0].xyz, m[1].xyz, m[2].xyz));
+    let result = mat4x4<f32>(
+        vec4<f32>(transposed[0], 0.0),
+        vec4<f32>(transposed[1], 0.0),
+        vec4<f32>(transposed[2], 0.0),
+        m[3],
+    );
+"##)
+}
+
+fn get_matrix(data_map: &BTreeMap<DataID, Data>, data_id: DataID) -> Result<(&wgpu::Buffer, Option<&Parameter>), ProcessingError> {
+    match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Matrix0D { buffer } => Ok((buffer, None)),
+        Data::Matrix1D { buffer, param } => Ok((buffer, Some(param))),
+        _ => Err(ProcessingError::IncorrectInput(" the input provided \n is not a matrix ".into())),
+    }
+}
+
+// applies the body to each matrix `m` of the input, the body must define the `result` matrix
+fn unary_matrix_operation(device: &wgpu::Device, buffer: &wgpu::Buffer, param: Option<&Parameter>, body: &str) -> SingleDataResult {
+    let wgsl_source = format!(r##"
+struct MatrixBuffer {{
+    matrices: array<mat4x4<f32>>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> input: MatrixBuffer;
+[[group(0), binding(1)]] var<storage, read_write> output: MatrixBuffer;
+
+[[stage(compute), workgroup_size({workgroup_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.x;
+    let m = input.matrices[index];{body}
+    output.matrices[index] = result;
+}}
+"##, workgroup_size=if param.is_some() { 16 } else { 1 }, body=body,
+);
+
+    //println!("matrix operation wgsl shader: {}", wgsl_source);
+    let bind_info = vec![
+        BindInfo {
+            buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+    ];
+    matrix_operation(device, &wgsl_source, bind_info, param.cloned())
+}
+
+// creates the output buffer, binds it after the inputs and builds the operation
+fn matrix_operation(device: &wgpu::Device, wgsl_source: &str, input_bind_info: Vec<BindInfo>, param: Option<Parameter>) -> SingleDataResult {
+    let n_matrices = param.as_ref().map_or(1, |param| param.n_points());
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Mat4>() * n_matrices);
+    let mut bind_info: Vec<BindInfo> = input_bind_info;
+    bind_info.push(BindInfo {
+        buffer: &output_buffer,
+        ty: wgpu::BufferBindingType::Storage { read_only: false },
+    });
+    let (pipeline, bind_group) = naga_compute_pipeline(device, wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param.as_ref().map_or(1, |param| param.segments), 1, 1],
+    };
+    let new_data = match param {
+        Some(param) => Data::Matrix1D {
+            buffer: output_buffer,
+            param,
+        },
+        None => Data::Matrix0D {
+            buffer: output_buffer,
+        },
+    };
+    Ok((new_data, operation))
+}
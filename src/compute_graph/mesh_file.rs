@@ -0,0 +1,85 @@
+use std::path::Path;
+use glam::{Mat3, Mat4, Vec3};
+use super::{MatcapData, ProcessingError};
+use crate::rendering::mesh_file::{self, MeshData};
+use crate::rendering::model::Model;
+use crate::rendering::{InstanceData, StandardVertexData};
+use crate::util;
+
+// The embedded mesh is used if there is one, otherwise the mesh is read from its file.
+// Relative paths are relative to the directory of the scene file.
+pub fn load_model(
+    device: &wgpu::Device,
+    directory: Option<&Path>,
+    path: &str,
+    embedded: Option<&MeshData>,
+) -> Result<Model, ProcessingError> {
+    let to_processing_error = |error: String| ProcessingError::IncorrectAttributes(format!(" {} ", error));
+    if let Some(mesh) = embedded {
+        return Model::from_mesh(device, mesh).map_err(to_processing_error);
+    }
+    if path.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please choose \n a mesh file ".into()));
+    }
+    let mesh = MeshData::load(&mesh_file::resolve_path(directory, path))
+        .map_err(to_processing_error)?;
+    Model::from_mesh(device, &mesh).map_err(to_processing_error)
+}
+
+// Reads back the triangles of a renderable, which must be called after the compute has run.
+// Instanced renderables are turned into a single mesh with a copy of the triangles for each instance,
+// and only the triangles with a non-zero area and the vertices that they use are kept.
+pub fn read_mesh(device: &wgpu::Device, matcap: &MatcapData) -> MeshData {
+    let vertex_floats = std::mem::size_of::<StandardVertexData>() / std::mem::size_of::<f32>();
+    let vertices = util::copy_buffer_as::<f32>(&matcap.vertex_buffer, device);
+    let indices = util::copy_buffer_as::<u32>(&matcap.index_buffer, device);
+    let indices = &indices[.. matcap.index_count as usize];
+
+    let transforms: Vec<Mat4> = if let Some(instance_buffer) = &matcap.instance_buffer {
+        let instance_floats = std::mem::size_of::<InstanceData>() / std::mem::size_of::<f32>();
+        util::copy_buffer_as::<f32>(instance_buffer, device)
+            .chunks_exact(instance_floats)
+            .take(matcap.instance_count as usize)
+            .map(|instance| Mat4::from_cols_slice(&instance[0 .. 16]))
+            // collapsed instances (e.g. the points that are not part of a point set) are not drawn
+            .filter(|transform| transform.determinant() != 0.0)
+            .collect()
+    } else {
+        vec![Mat4::IDENTITY]
+    };
+
+    let mut mesh = MeshData::default();
+    for transform in transforms.iter() {
+        let normal_transform = Mat3::from_mat4(*transform).inverse().transpose();
+        let mut new_indices: Vec<Option<u32>> = vec![None; matcap.vertex_count as usize];
+        for triangle in indices.chunks_exact(3) {
+            let corners = [0, 1, 2].map(|i| &vertices[triangle[i] as usize * vertex_floats ..]);
+            let positions = corners.map(|vertex| transform.transform_point3(Vec3::new(vertex[0], vertex[1], vertex[2])));
+            // some meshes draw more triangles than they need, e.g. the unused vertices of the implicit surfaces
+            // are collapsed onto a single point. Such triangles would only be noise in the exported file.
+            let has_missing_corner = corners.iter().any(|vertex| vertex[3] == 0.0);
+            let has_zero_area = (positions[1] - positions[0]).cross(positions[2] - positions[0]) == Vec3::ZERO;
+            if has_missing_corner || has_zero_area {
+                continue;
+            }
+            for i in 0..3 {
+                let index = triangle[i] as usize;
+                let new_index = match new_indices[index] {
+                    Some(new_index) => new_index,
+                    None => {
+                        let vertex = corners[i];
+                        let normal = (normal_transform * Vec3::new(vertex[4], vertex[5], vertex[6])).normalize_or_zero();
+                        mesh.positions.push(positions[i].into());
+                        mesh.normals.push(normal.into());
+                        mesh.uv_coords.push([vertex[8], vertex[9]]);
+                        let new_index = mesh.positions.len() as u32 - 1;
+                        new_indices[index] = Some(new_index);
+                        new_index
+                    }
+                };
+                mesh.indices.push(new_index);
+            }
+        }
+    }
+    mesh
+}
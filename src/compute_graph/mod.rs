@@ -1,27 +1,50 @@
 use std::collections::BTreeMap;
 use std::collections::btree_map::Iter;
 use std::rc::Rc;
+use std::path::Path;
 use indexmap::IndexMap;
 pub use crate::node_graph::{NodeGraph, NodeID, NodeContents};
+use crate::node_graph::{CoordinateSystem, Colormap, AttributeID};
+use vector_algebra::VectorOperation;
 use crate::compute_graph::globals::{Globals, NameValuePair};
 use crate::state::UserState;
 use crate::state::Assets;
+use crate::rendering::model::Model;
+use crate::rendering::mesh_file::MeshData;
 
 pub mod globals;
 
 mod point;
 mod vector;
+mod vector_algebra;
 mod interval;
 mod curve;
 mod bezier;
+mod spline;
+mod spline_surface;
 mod geometry_render;
 mod vector_render;
+mod vector_field;
 mod surface;
+mod volume;
+mod revolution;
+mod sweep;
+mod ruled_surface;
 mod matrix;
 mod transform;
 mod sample;
+mod name_parameters;
+mod frenet;
+mod surface_frame;
+mod arc_length;
+mod trail;
+mod colormap;
+mod intersection;
 mod prefab;
+mod mesh_file;
 mod plane;
+mod implicit_surface;
+mod implicit_curve;
 
 pub type DataID = i32;
 pub type PrefabId = i32;
@@ -63,7 +86,7 @@ pub enum ProcessingError {
     IncorrectInput(String),
 }
 pub type SingleDataResult = Result<(Data, Operation), ProcessingError>;
-pub type MatcapIter<'a> = Iter<'a, NodeID, MatcapData>;
+pub type MatcapIter<'a> = Iter<'a, NodeID, Vec<MatcapData>>;
 
 // a parameter can be anonymous, e.g. when created by a Bezier node
 #[derive(Debug, Clone)]
@@ -113,6 +136,11 @@ pub enum Data {
     Vector {
         buffer: wgpu::Buffer,
     },
+    // a family of vectors along a parameter, e.g. the vectors joining the points of a curve to a point
+    Vector1D {
+        buffer: wgpu::Buffer,
+        param: Parameter,
+    },
     Interval {
         buffer: wgpu::Buffer,
         param: Parameter,
@@ -129,6 +157,29 @@ pub enum Data {
         param1: Parameter,
         param2: Parameter,
     },
+    Geom3D {
+        buffer: wgpu::Buffer,
+        param1: Parameter,
+        param2: Parameter,
+        param3: Parameter,
+    },
+    // a family of points that are not connected to each other, e.g. the samples of a curve
+    // taken at every value of an interval. It is stored like a curve, one point per value of the parameter.
+    // Points with w = 0 are not part of the set, e.g. the unused slots of the Intersection nodes.
+    PointSet {
+        buffer: wgpu::Buffer,
+        param: Parameter,
+    },
+    // a family of curves with the same parameter, e.g. the curves of a surface taken by a Sample node
+    // at several values of the other parameter. The curves are stored one after the other.
+    // The name and values of the sampled parameter are kept, so that they can be used to color the family.
+    CurveFamily {
+        buffer: wgpu::Buffer,
+        param: Parameter,
+        curves_count: usize,
+        sampled_name: String,
+        sampled_values: Vec<String>,
+    },
     Matrix0D {
         buffer: wgpu::Buffer,
     },
@@ -142,25 +193,61 @@ pub enum Data {
         index_buffer: Rc<wgpu::Buffer>,
         index_count: u32,
     },
+    // a set of disconnected segments, e.g. the level set computed by the Implicit Curve node.
+    // Each segment is stored as a pair of points, and each point is made of three vec4:
+    // its position, the tangent to the curve and the "up" direction of the tube section.
+    Segments {
+        buffer: wgpu::Buffer,
+        segments_count: usize,
+    },
+    // a single number, e.g. the curvature of a curve at a given point.
+    // Its value is read back after each compute, so that it can be shown in the node graph.
+    Scalar {
+        buffer: wgpu::Buffer,
+    },
+    // a family of numbers along a parameter, e.g. the length of a family of vectors
+    Scalar1D {
+        buffer: wgpu::Buffer,
+        param: Parameter,
+    },
+}
+
+impl Data {
+    pub const SEGMENT_POINT_SIZE: usize = 3 * std::mem::size_of::<glam::Vec4>();
 }
 
 pub struct MatcapData {
     pub vertex_buffer: wgpu::Buffer,
+    pub vertex_count: u32,
     pub index_buffer: Rc<wgpu::Buffer>,
     pub index_count: u32,
     pub mask_id: usize,
     pub material_id: usize,
+    // when present, the mesh is drawn once for each element of the buffer,
+    // which contains an InstanceData (transform and color) per instance
+    pub instance_buffer: Option<wgpu::Buffer>,
+    pub instance_count: u32,
 }
 // The compute graph contains:
 // - a map of all the Data in the graph
 // - a list of all the operations that are to be executed (that also implies: all the compute
-// shaders that are to be run)
-// - a list of all the renderables that were created as outputs.
+// shaders that are to be run). Most nodes need a single operation, but some of them
+// (e.g. the Spline node) need to run a few compute passes in sequence.
+// - a list of all the renderables that were created as outputs. A rendering node can
+// output more than one renderable (e.g. a surface and its u-v lines).
+// - the ranges of values spanned by the colormaps, which are shown in the legends.
+// - the trails of the Trajectory nodes, which run the operations of other nodes many times
+// and therefore are encoded separately.
+// - the meshes loaded by the Mesh File nodes, which are read by their operations.
 pub struct ComputeGraph {
     pub globals: Globals,
     data: BTreeMap<DataID, Data>,
-    renderables: BTreeMap<NodeID, MatcapData>,
-    operations: IndexMap<NodeID, Operation>,
+    renderables: BTreeMap<NodeID, Vec<MatcapData>>,
+    operations: IndexMap<NodeID, Vec<Operation>>,
+    color_ranges: BTreeMap<AttributeID, wgpu::Buffer>,
+    trails: BTreeMap<NodeID, trail::Trail>,
+    models: BTreeMap<NodeID, Model>,
+    readback_buffer: Option<wgpu::Buffer>,
 }
 
 pub fn create_compute_graph(device: &wgpu::Device, assets: &Assets, user_state: &UserState) -> Result<(ComputeGraph, Vec<RecoverableError>), UnrecoverableError> {
@@ -202,10 +289,14 @@ pub fn create_compute_graph(device: &wgpu::Device, assets: &Assets, user_state:
             data: BTreeMap::new(),
             operations: IndexMap::new(),
             renderables: BTreeMap::new(),
+            color_ranges: BTreeMap::new(),
+            trails: BTreeMap::new(),
+            models: BTreeMap::new(),
+            readback_buffer: None,
             globals,
         };
         for id in sorted_ids.into_iter().rev() {
-            let node_result = compute_graph.process_single_node(device, assets, id, graph);
+            let node_result = compute_graph.process_single_node(device, assets, user_state.directory.as_deref(), id, graph);
             if let Err(error) = node_result {
                 recoverable_errors.push(RecoverableError{
                     node_id: id,
@@ -213,6 +304,7 @@ pub fn create_compute_graph(device: &wgpu::Device, assets: &Assets, user_state:
                 });
             };
         }
+        compute_graph.create_readback_buffer(device);
         Ok((compute_graph, recoverable_errors))
 }
 
@@ -226,25 +318,122 @@ impl ComputeGraph {
             device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Compute Encoder this time"),
         });
-        for op in self.operations.values() {
-            op.encode(&mut encoder);
+        for node_id in self.operations.keys() {
+            self.encode_node(*node_id, &mut encoder);
         }
+        self.encode_readback(&mut encoder);
         let compute_queue = encoder.finish();
         queue.submit(std::iter::once(compute_queue));
     }
 
-    pub fn update_globals(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, pairs: Vec<NameValuePair>) {
+    fn encode_node(&self, node_id: NodeID, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(trail) = self.trails.get(&node_id) {
+            // the buffers are checked when the trail is created, and they cannot be removed
+            let point_buffer = match self.data.get(&trail.point_id) {
+                Some(Data::Geom0D { buffer }) => buffer,
+                _ => unreachable!("the point of a trail must be a 0d geometry"),
+            };
+            let output_buffer = match self.data.get(&trail.output_id) {
+                Some(Data::Geom1D { buffer, .. }) => buffer,
+                _ => unreachable!("the output of a trail must be a 1d geometry"),
+            };
+            let encode_ancestors = |encoder: &mut wgpu::CommandEncoder| {
+                for ancestor_id in trail.ancestors.iter() {
+                    self.encode_node(*ancestor_id, encoder);
+                }
+            };
+            trail.encode(encoder, self.globals.get_buffer(), point_buffer, output_buffer, &encode_ancestors);
+        }
+        for op in self.operations.get(&node_id).into_iter().flatten() {
+            op.encode(encoder);
+        }
+    }
+
+    // returns true if any global changed, and therefore the compute operations were run again
+    pub fn update_globals(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, pairs: Vec<NameValuePair>) -> bool {
         let values_changed = self.globals.update_buffer(queue, pairs);
         if values_changed {
             self.run_compute(device, queue);
         }
+        values_changed
+    }
+
+    // The values that are shown in the node graph (the scalars and the ranges of the colormaps)
+    // are copied into a single buffer at the end of each compute, so that they can be read back with a single map.
+    // The scalars come first, followed by two values for each colormap range.
+    fn scalar_buffers(&self) -> impl Iterator<Item = (&DataID, &wgpu::Buffer)> {
+        self.data.iter()
+            .filter_map(|(data_id, data)| {
+                if let Data::Scalar { buffer } = data {
+                    Some((data_id, buffer))
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn create_readback_buffer(&mut self, device: &wgpu::Device) {
+        let values_count = self.scalar_buffers().count() + 2 * self.color_ranges.len();
+        if values_count == 0 {
+            return;
+        }
+        self.readback_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback buffer"),
+            size: (values_count * std::mem::size_of::<f32>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+
+    fn encode_readback(&self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(readback_buffer) = &self.readback_buffer {
+            let value_size = std::mem::size_of::<f32>() as wgpu::BufferAddress;
+            let mut offset = 0;
+            for (_, buffer) in self.scalar_buffers() {
+                encoder.copy_buffer_to_buffer(buffer, 0, readback_buffer, offset, value_size);
+                offset += value_size;
+            }
+            for buffer in self.color_ranges.values() {
+                encoder.copy_buffer_to_buffer(buffer, 0, readback_buffer, offset, 2 * value_size);
+                offset += 2 * value_size;
+            }
+        }
+    }
+
+    // reads back the values of all the scalars and the ranges of the colormaps,
+    // which must be called after run_compute()
+    pub fn read_values(&self, device: &wgpu::Device) -> (BTreeMap<DataID, f32>, BTreeMap<AttributeID, [f32; 2]>) {
+        let readback_buffer = match &self.readback_buffer {
+            Some(buffer) => buffer,
+            None => return (BTreeMap::new(), BTreeMap::new()),
+        };
+        let values = crate::util::copy_buffer_as::<f32>(readback_buffer, device);
+        let scalars: BTreeMap<DataID, f32> = self.scalar_buffers()
+            .zip(values.iter())
+            .map(|((data_id, _), value)| (*data_id, *value))
+            .collect();
+        let color_ranges = self.color_ranges.keys()
+            .zip(values[scalars.len()..].chunks_exact(2))
+            .map(|(attribute_id, range)| (*attribute_id, [range[0], range[1]]))
+            .collect();
+        (scalars, color_ranges)
+    }
+
+    // reads back the meshes of all the renderables, together with their Rendering node
+    // and their material. This must be called after run_compute()
+    pub fn read_meshes(&self, device: &wgpu::Device) -> Vec<(NodeID, usize, MeshData)> {
+        self.renderables.iter()
+            .flat_map(|(node_id, matcaps)| {
+                matcaps.iter().map(move |matcap| (*node_id, matcap.material_id, mesh_file::read_mesh(device, matcap)))
+            })
+            .collect()
     }
 
     // process a single graph node.
     // If the operation is successful, then the internal state of the ComputeGraph is modified by storing
     // the newly created data and operation. If it fails, then a ProcessingError is returned and
     // the internal state is left untouched.
-    fn process_single_node(&mut self, device: &wgpu::Device, assets: &Assets, graph_node_id: NodeID, graph: &NodeGraph) ->  Result<(), ProcessingError> {
+    fn process_single_node(&mut self, device: &wgpu::Device, assets: &Assets, directory: Option<&Path>, graph_node_id: NodeID, graph: &NodeGraph) ->  Result<(), ProcessingError> {
         // TODO: turn this into an if let - else construct
         let to_process = match graph.get_node(graph_node_id) {
             Some(node) => node,
@@ -262,20 +451,153 @@ impl ComputeGraph {
                     graph.get_attribute_as_string(z).unwrap(),
                 )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
-            NodeContents::Point {
-                x, y, z, output
+            NodeContents::VectorFromPoints {
+                from, to, output
             } => {
-                let (new_data, operation) = point::create(
+                let (new_data, operation) = vector_algebra::create(
                     device,
                     &self.globals,
-                    graph.get_attribute_as_string(x).unwrap(),
-                    graph.get_attribute_as_string(y).unwrap(),
-                    graph.get_attribute_as_string(z).unwrap(),
-                )?;
+                    &self.data,
+                    VectorOperation::FromPoints,
+                    [graph.get_attribute_as_linked_output(from), graph.get_attribute_as_linked_output(to)],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::TranslatePoint {
+                point, vector, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::TranslatePoint,
+                    [graph.get_attribute_as_linked_output(point), graph.get_attribute_as_linked_output(vector)],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::VectorSum {
+                first, second, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Sum,
+                    [graph.get_attribute_as_linked_output(first), graph.get_attribute_as_linked_output(second)],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::VectorDifference {
+                first, second, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Difference,
+                    [graph.get_attribute_as_linked_output(first), graph.get_attribute_as_linked_output(second)],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::ScaleVector {
+                vector, scalar, factor, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Scale {
+                        factor: graph.get_attribute_as_string(factor).unwrap(),
+                    },
+                    [graph.get_attribute_as_linked_output(vector), graph.get_attribute_as_linked_output(scalar)],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::DotProduct {
+                first, second, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Dot,
+                    [graph.get_attribute_as_linked_output(first), graph.get_attribute_as_linked_output(second)],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::CrossProduct {
+                first, second, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Cross,
+                    [graph.get_attribute_as_linked_output(first), graph.get_attribute_as_linked_output(second)],
+                    )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::NormalizeVector {
+                vector, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Normalize,
+                    [graph.get_attribute_as_linked_output(vector), None],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::VectorLength {
+                vector, output
+            } => {
+                let (new_data, operation) = vector_algebra::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    VectorOperation::Length,
+                    [graph.get_attribute_as_linked_output(vector), None],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Point {
+                interval, x, y, z, output
+            } => {
+                // a point whose coordinates depend on an interval is a point set
+                let interval_id = interval.and_then(|interval| graph.get_attribute_as_linked_output(interval));
+                let (new_data, operation) = if let Some(interval_id) = interval_id {
+                    point::create_set(
+                        device,
+                        &self.globals,
+                        &self.data,
+                        interval_id,
+                        graph.get_attribute_as_string(x).unwrap(),
+                        graph.get_attribute_as_string(y).unwrap(),
+                        graph.get_attribute_as_string(z).unwrap(),
+                    )?
+                } else {
+                    point::create(
+                        device,
+                        &self.globals,
+                        graph.get_attribute_as_string(x).unwrap(),
+                        graph.get_attribute_as_string(y).unwrap(),
+                        graph.get_attribute_as_string(z).unwrap(),
+                    )?
+                };
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::Curve {
                 interval, fx, fy, fz, output
@@ -288,9 +610,26 @@ impl ComputeGraph {
                     graph.get_attribute_as_string(fx).unwrap(),
                     graph.get_attribute_as_string(fy).unwrap(),
                     graph.get_attribute_as_string(fz).unwrap(),
+                    CoordinateSystem::Cartesian,
                 )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::CoordinateCurve {
+                interval, coordinates, f1, f2, f3, output
+            } => {
+                let (new_data, operation) = curve::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(interval),
+                    graph.get_attribute_as_string(f1).unwrap(),
+                    graph.get_attribute_as_string(f2).unwrap(),
+                    graph.get_attribute_as_string(f3).unwrap(),
+                    graph.get_attribute_as_coordinate_system(coordinates).unwrap(),
+                )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::Surface {
                 interval_1, interval_2, fx, fy, fz, output,
@@ -304,9 +643,98 @@ impl ComputeGraph {
                     graph.get_attribute_as_string(fx).unwrap(),
                     graph.get_attribute_as_string(fy).unwrap(),
                     graph.get_attribute_as_string(fz).unwrap(),
+                    CoordinateSystem::Cartesian,
+                )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::CoordinateSurface {
+                interval_1, interval_2, coordinates, f1, f2, f3, output,
+            } => {
+                let (new_data, operation) = surface::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(interval_1),
+                    graph.get_attribute_as_linked_output(interval_2),
+                    graph.get_attribute_as_string(f1).unwrap(),
+                    graph.get_attribute_as_string(f2).unwrap(),
+                    graph.get_attribute_as_string(f3).unwrap(),
+                    graph.get_attribute_as_coordinate_system(coordinates).unwrap(),
+                )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Volume {
+                interval_1, interval_2, interval_3, fx, fy, fz, output,
+            } => {
+                let (new_data, operation) = volume::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(interval_1),
+                    graph.get_attribute_as_linked_output(interval_2),
+                    graph.get_attribute_as_linked_output(interval_3),
+                    graph.get_attribute_as_string(fx).unwrap(),
+                    graph.get_attribute_as_string(fy).unwrap(),
+                    graph.get_attribute_as_string(fz).unwrap(),
+                )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Revolution {
+                geometry, axis, axis_point, axis_direction, name, begin, end, quality, output,
+            } => {
+                // files saved before the angle parameter had a name get the default one
+                let angle_name = name.and_then(|name| graph.get_attribute_as_string(name))
+                    .unwrap_or_else(|| String::from("theta"));
+                let (new_data, operation) = revolution::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    graph.get_attribute_as_axis(axis).unwrap(),
+                    graph.get_attribute_as_linked_output(axis_point),
+                    graph.get_attribute_as_linked_output(axis_direction),
+                    angle_name,
+                    graph.get_attribute_as_string(begin).unwrap(),
+                    graph.get_attribute_as_string(end).unwrap(),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Sweep {
+                path, profile, radius, quality, output,
+            } => {
+                let (new_data, operation) = sweep::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(path),
+                    graph.get_attribute_as_linked_output(profile),
+                    graph.get_attribute_as_string(radius).unwrap(),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::RuledSurface {
+                curve_1, curve_2, name, quality, output,
+            } => {
+                // files saved before the rulings parameter had a name get the default one
+                let rulings_name = name.and_then(|name| graph.get_attribute_as_string(name))
+                    .unwrap_or_else(|| String::from("s"));
+                let (new_data, operation) = ruled_surface::create(
+                    device,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(curve_1),
+                    graph.get_attribute_as_linked_output(curve_2),
+                    rulings_name,
+                    graph.get_attribute_as_usize(quality).unwrap(),
                 )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::Bezier {
                 p0, p1, p2, p3, quality, output
@@ -331,7 +759,46 @@ impl ComputeGraph {
                     graph.get_attribute_as_usize(quality).unwrap(),
                 )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Spline {
+                p0, p1, p2, p3, p4, p5, p6, p7, kind, degree, weights, quality, output, polygon
+            } => {
+                // the control points are the linked inputs, in order: unconnected pins are skipped
+                let points: Vec<DataID> = [p0, p1, p2, p3, p4, p5, p6, p7].iter()
+                    .filter_map(|pin| graph.get_attribute_as_linked_output(*pin))
+                    .collect();
+                let (curve_data, polygon_data, operations) = spline::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    points,
+                    graph.get_attribute_as_spline_kind(kind).unwrap(),
+                    graph.get_attribute_as_usize(degree).unwrap(),
+                    graph.get_attribute_as_string(weights).unwrap(),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                )?;
+                self.data.insert(output, curve_data);
+                self.data.insert(polygon, polygon_data);
+                self.operations.insert(graph_node_id, operations);
+            },
+            NodeContents::SplineSurface {
+                kind, degree, row_1, row_2, row_3, row_4, row_5, row_6, quality, output, control_net
+            } => {
+                let rows: Vec<String> = [row_1, row_2, row_3, row_4, row_5, row_6].iter()
+                    .map(|row| graph.get_attribute_as_string(*row).unwrap())
+                    .collect();
+                let (surface_data, net_data, operations) = spline_surface::create(
+                    device,
+                    &self.globals,
+                    rows,
+                    graph.get_attribute_as_spline_kind(kind).unwrap(),
+                    graph.get_attribute_as_usize(degree).unwrap(),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                )?;
+                self.data.insert(output, surface_data);
+                self.data.insert(control_net, net_data);
+                self.operations.insert(graph_node_id, operations);
             },
             NodeContents::Interval {
                 variable, begin, end, quality, output,
@@ -345,7 +812,7 @@ impl ComputeGraph {
                     graph.get_attribute_as_usize(quality).unwrap(),
                 )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::TranslationMatrix {
                 vector, output,
@@ -356,7 +823,7 @@ impl ComputeGraph {
                     graph.get_attribute_as_linked_output(vector),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::RotationMatrix {
                 axis, angle, output,
@@ -369,7 +836,68 @@ impl ComputeGraph {
                     graph.get_attribute_as_string(angle).unwrap(),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::ScaleMatrix {
+                x, y, z, output,
+            } => {
+                let (new_data, operation) = matrix::create_from_scale(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_string(x).unwrap(),
+                    graph.get_attribute_as_string(y).unwrap(),
+                    graph.get_attribute_as_string(z).unwrap(),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::AxisRotationMatrix {
+                axis, angle, output,
+            } => {
+                let (new_data, operation) = matrix::create_from_axis_angle(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(axis),
+                    graph.get_attribute_as_string(angle).unwrap(),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::MatrixProduct {
+                first, second, output,
+            } => {
+                let (new_data, operation) = matrix::create_product(
+                    device,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(first),
+                    graph.get_attribute_as_linked_output(second),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::InverseMatrix {
+                matrix, output,
+            } => {
+                let (new_data, operation) = matrix::create_inverse(
+                    device,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(matrix),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::TransposeMatrix {
+                matrix, output,
+            } => {
+                let (new_data, operation) = matrix::create_transpose(
+                    device,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(matrix),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::Matrix {
                 interval, row_1, row_2, row_3, output,
@@ -384,21 +912,191 @@ impl ComputeGraph {
                     graph.get_attribute_as_matrix_row(row_3).unwrap(),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Trajectory {
+                point, variable, begin, end, quality, output,
+            } => {
+                // collect all the nodes the point depends on, in the order in which they were processed
+                let mut ancestors = std::collections::BTreeSet::<NodeID>::new();
+                let mut to_visit = to_process.get_input_nodes(graph);
+                while let Some(node_id) = to_visit.pop() {
+                    if ancestors.insert(node_id) {
+                        to_visit.extend(graph.get_node(node_id).map(|node| node.get_input_nodes(graph)).unwrap_or_default());
+                    }
+                }
+                let ancestors: Vec<NodeID> = self.operations.keys()
+                    .filter(|node_id| ancestors.contains(node_id))
+                    .cloned()
+                    .collect();
+                let (new_data, trail) = trail::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(point),
+                    output,
+                    ancestors,
+                    graph.get_attribute_as_string(variable).unwrap(),
+                    graph.get_attribute_as_string(begin).unwrap(),
+                    graph.get_attribute_as_string(end).unwrap(),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                    )?;
+                self.data.insert(output, new_data);
+                self.trails.insert(graph_node_id, trail);
+                // the node has no operations of its own, but it must keep its place in the processing order
+                self.operations.insert(graph_node_id, Vec::new());
             },
             NodeContents::Sample {
-                geometry, parameter, value, output,
+                geometry, interval, parameter, value, samples, last_value, output,
             } => {
                 let (new_data, operation) = sample::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    interval.and_then(|interval| graph.get_attribute_as_linked_output(interval)),
+                    graph.get_attribute_as_string(parameter).unwrap(),
+                    graph.get_attribute_as_string(value).unwrap(),
+                    // files saved before families were introduced sample a single value
+                    samples.map_or(1, |samples| graph.get_attribute_as_usize(samples).unwrap()),
+                    last_value.map_or(String::new(), |last_value| graph.get_attribute_as_string(last_value).unwrap()),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::NameParameters {
+                geometry, name_1, name_2, name_3, output,
+            } => {
+                let (new_data, operation) = name_parameters::create(
+                    device,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    [
+                        graph.get_attribute_as_string(name_1).unwrap(),
+                        graph.get_attribute_as_string(name_2).unwrap(),
+                        graph.get_attribute_as_string(name_3).unwrap(),
+                    ],
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::FrenetFrame {
+                geometry, parameter, value, point, tangent, normal, binormal,
+            } => {
+                let ([point_data, tangent_data, normal_data, binormal_data], operation) = frenet::create_frame(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    graph.get_attribute_as_string(parameter).unwrap(),
+                    graph.get_attribute_as_string(value).unwrap(),
+                    curve_expressions(graph, geometry),
+                    )?;
+                self.data.insert(point, point_data);
+                self.data.insert(tangent, tangent_data);
+                self.data.insert(normal, normal_data);
+                self.data.insert(binormal, binormal_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::Curvature {
+                geometry, parameter, value, curvature, torsion, circle,
+            } => {
+                let ([curvature_data, torsion_data, circle_data], operation) = frenet::create_curvature(
                     device,
                     &self.globals,
                     &self.data,
                     graph.get_attribute_as_linked_output(geometry),
                     graph.get_attribute_as_string(parameter).unwrap(),
                     graph.get_attribute_as_string(value).unwrap(),
+                    curve_expressions(graph, geometry),
+                    )?;
+                self.data.insert(curvature, curvature_data);
+                self.data.insert(torsion, torsion_data);
+                self.data.insert(circle, circle_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::SurfaceFrame {
+                geometry, parameter_1, value_1, parameter_2, value_2, size,
+                point, u_tangent, v_tangent, normal, plane, e, f, g, gaussian, mean,
+            } => {
+                let (frame_data, mut operations) = surface_frame::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    [
+                        graph.get_attribute_as_string(parameter_1).unwrap(),
+                        graph.get_attribute_as_string(parameter_2).unwrap(),
+                    ],
+                    [
+                        graph.get_attribute_as_string(value_1).unwrap(),
+                        graph.get_attribute_as_string(value_2).unwrap(),
+                    ],
+                    surface_expressions(graph, geometry),
+                    )?;
+                let [point_data, u_data, v_data, normal_data, e_data, f_data, g_data, gaussian_data, mean_data] = frame_data;
+                self.data.insert(point, point_data);
+                self.data.insert(u_tangent, u_data);
+                self.data.insert(v_tangent, v_data);
+                self.data.insert(normal, normal_data);
+                self.data.insert(e, e_data);
+                self.data.insert(f, f_data);
+                self.data.insert(g, g_data);
+                self.data.insert(gaussian, gaussian_data);
+                self.data.insert(mean, mean_data);
+                // the tangent plane is just a Plane centered on the point, built from the outputs above
+                let (plane_data, plane_operation) = plane::create(
+                    device,
+                    &self.data,
+                    Some(point),
+                    Some(normal),
+                    graph.get_attribute_as_usize(size).unwrap(),
+                    )?;
+                self.data.insert(plane, plane_data);
+                operations.push(plane_operation);
+                self.operations.insert(graph_node_id, operations);
+            },
+            NodeContents::ArcLength {
+                geometry, output, length,
+            } => {
+                let ([curve_data, length_data], operations) = arc_length::create(
+                    device,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    )?;
+                self.data.insert(output, curve_data);
+                self.data.insert(length, length_data);
+                self.operations.insert(graph_node_id, operations);
+            },
+            NodeContents::CurvePlaneIntersection {
+                geometry, point, normal, output,
+            } => {
+                let (new_data, operations) = intersection::create_curve_plane(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    graph.get_attribute_as_linked_output(point),
+                    graph.get_attribute_as_linked_output(normal),
+                    curve_expressions(graph, geometry),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, operations);
+            },
+            NodeContents::LineSurfaceIntersection {
+                geometry, point, direction, output,
+            } => {
+                let (new_data, operations) = intersection::create_line_surface(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_linked_output(geometry),
+                    graph.get_attribute_as_linked_output(point),
+                    graph.get_attribute_as_linked_output(direction),
+                    surface_expressions(graph, geometry),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, operations);
             },
             NodeContents::Transform {
                 geometry, matrix, output,
@@ -410,7 +1108,7 @@ impl ComputeGraph {
                     graph.get_attribute_as_linked_output(matrix),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::Plane {
                 center, normal, size, output,
@@ -423,23 +1121,75 @@ impl ComputeGraph {
                     graph.get_attribute_as_usize(size).unwrap(),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::ImplicitSurface {
+                function, x_min, x_max, y_min, y_max, z_min, z_max, quality, output,
+            } => {
+                let (new_data, operation) = implicit_surface::create(
+                    device,
+                    &self.globals,
+                    graph.get_attribute_as_string(function).unwrap(),
+                    graph.get_attribute_as_string(x_min).unwrap(),
+                    graph.get_attribute_as_string(x_max).unwrap(),
+                    graph.get_attribute_as_string(y_min).unwrap(),
+                    graph.get_attribute_as_string(y_max).unwrap(),
+                    graph.get_attribute_as_string(z_min).unwrap(),
+                    graph.get_attribute_as_string(z_max).unwrap(),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::ImplicitCurve {
+                function, x_min, x_max, y_min, y_max, plane, center, normal, quality, output,
+            } => {
+                let (new_data, operation) = implicit_curve::create(
+                    device,
+                    &self.globals,
+                    &self.data,
+                    graph.get_attribute_as_string(function).unwrap(),
+                    graph.get_attribute_as_string(x_min).unwrap(),
+                    graph.get_attribute_as_string(x_max).unwrap(),
+                    graph.get_attribute_as_string(y_min).unwrap(),
+                    graph.get_attribute_as_string(y_max).unwrap(),
+                    graph.get_attribute_as_plane(plane).unwrap(),
+                    graph.get_attribute_as_linked_output(center),
+                    graph.get_attribute_as_linked_output(normal),
+                    graph.get_attribute_as_usize(quality).unwrap(),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
             },
             NodeContents::Primitive {
                 primitive, size, output,
             } => {
                 let (new_data, operation) = prefab::create(
                     device,
-                    &assets.models,
+                    assets.models.get(graph.get_attribute_as_usize(primitive).unwrap()).unwrap(),
+                    &self.globals,
+                    graph.get_attribute_as_string(size).unwrap(),
+                    )?;
+                self.data.insert(output, new_data);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::MeshFile {
+                file, size, output,
+            } => {
+                let (path, embedded) = graph.get_attribute_as_mesh_file(file).unwrap();
+                let model = mesh_file::load_model(device, directory, path, embedded)?;
+                let (new_data, operation) = prefab::create(
+                    device,
+                    &model,
                     &self.globals,
-                    graph.get_attribute_as_usize(primitive).unwrap(),
                     graph.get_attribute_as_string(size).unwrap(),
                     )?;
                 self.data.insert(output, new_data);
-                self.operations.insert(graph_node_id, operation);
+                self.operations.insert(graph_node_id, vec![operation]);
+                self.models.insert(graph_node_id, model);
             },
             NodeContents::Rendering {
-                geometry, thickness, mask, material,
+                geometry, thickness, mask, material, lines, lines_material, colormap, color_function,
             } => {
                 let (renderable, operation) = geometry_render::create(
                     device,
@@ -449,8 +1199,45 @@ impl ComputeGraph {
                     graph.get_attribute_as_usize(mask).unwrap(),
                     graph.get_attribute_as_usize(material).unwrap(),
                 )?;
-                self.renderables.insert(graph_node_id, renderable);
-                self.operations.insert(graph_node_id, operation);
+                // files saved before the u-v lines were introduced have no attributes for them
+                let (lines_renderables, lines_operations) = match (lines, lines_material) {
+                    (Some(lines), Some(lines_material)) => geometry_render::create_uv_lines(
+                        device,
+                        &self.data,
+                        graph.get_attribute_as_linked_output(geometry),
+                        graph.get_attribute_as_usize(lines).unwrap(),
+                        graph.get_attribute_as_usize(thickness).unwrap(),
+                        graph.get_attribute_as_usize(lines_material).unwrap(),
+                    )?,
+                    _ => (Vec::new(), Vec::new()),
+                };
+                // same for the colormap. The colors are computed after the vertices of the geometry
+                let colormap_result = match (colormap, color_function) {
+                    (Some(colormap), Some(color_function)) => match graph.get_attribute_as_colormap(colormap).unwrap() {
+                        Colormap::Material => None,
+                        selected => Some((colormap, colormap::create(
+                            device,
+                            &self.globals,
+                            &self.data,
+                            graph.get_attribute_as_linked_output(geometry),
+                            &renderable,
+                            selected,
+                            graph.get_attribute_as_string(color_function).unwrap(),
+                            curve_expressions(graph, geometry).or_else(|| surface_expressions(graph, geometry)),
+                        )?)),
+                    },
+                    _ => None,
+                };
+                let mut renderables = vec![renderable];
+                renderables.extend(lines_renderables);
+                let mut operations = vec![operation];
+                if let Some((colormap, (range_buffer, colormap_operations))) = colormap_result {
+                    operations.extend(colormap_operations);
+                    self.color_ranges.insert(colormap, range_buffer);
+                }
+                operations.extend(lines_operations);
+                self.renderables.insert(graph_node_id, renderables);
+                self.operations.insert(graph_node_id, operations);
             },
             NodeContents::VectorRendering {
                 application_point, vector, thickness, material,
@@ -463,8 +1250,29 @@ impl ComputeGraph {
                     graph.get_attribute_as_usize(thickness).unwrap(),
                     graph.get_attribute_as_usize(material).unwrap(),
                 )?;
-                self.renderables.insert(graph_node_id, renderable);
-                self.operations.insert(graph_node_id, operation);
+                self.renderables.insert(graph_node_id, vec![renderable]);
+                self.operations.insert(graph_node_id, vec![operation]);
+            },
+            NodeContents::VectorField {
+                fx, fy, fz, size, grid, points, scale, thickness, material,
+            } => {
+                let (renderable, operations) = vector_field::create(
+                    device,
+                    &self.globals,
+                    [
+                        graph.get_attribute_as_string(fx).unwrap(),
+                        graph.get_attribute_as_string(fy).unwrap(),
+                        graph.get_attribute_as_string(fz).unwrap(),
+                    ],
+                    graph.get_attribute_as_string(size).unwrap(),
+                    graph.get_attribute_as_field_grid(grid).unwrap(),
+                    graph.get_attribute_as_usize(points).unwrap(),
+                    graph.get_attribute_as_string(scale).unwrap(),
+                    graph.get_attribute_as_usize(thickness).unwrap(),
+                    graph.get_attribute_as_usize(material).unwrap(),
+                )?;
+                self.renderables.insert(graph_node_id, vec![renderable]);
+                self.operations.insert(graph_node_id, operations);
             },
             _ => todo!("handle all graph node kinds!")
         }
@@ -472,3 +1280,39 @@ impl ComputeGraph {
     }
 
 }
+
+// if the given input is directly linked to a Curve, Cylindrical Curve or Spherical Curve node, returns
+// the cartesian expressions that define the curve, so that its derivatives can be computed analytically.
+// The expressions of curves that come from any other node (e.g. transformed or renamed curves) are not known.
+fn curve_expressions(graph: &NodeGraph, input: AttributeID) -> Option<[String; 3]> {
+    let node_id = graph.get_attribute_as_linked_node(input)?;
+    match *graph.get_node(node_id)?.contents() {
+        NodeContents::Curve { fx, fy, fz, .. } => Some([
+            graph.get_attribute_as_string(fx)?,
+            graph.get_attribute_as_string(fy)?,
+            graph.get_attribute_as_string(fz)?,
+        ]),
+        NodeContents::CoordinateCurve { coordinates, f1, f2, f3, .. } => Some(curve::cartesian_expressions(
+            graph.get_attribute_as_coordinate_system(coordinates)?,
+            [
+                graph.get_attribute_as_string(f1)?,
+                graph.get_attribute_as_string(f2)?,
+                graph.get_attribute_as_string(f3)?,
+            ],
+        )),
+        _ => None,
+    }
+}
+
+// same as curve_expressions(), for inputs linked directly to a Surface node
+fn surface_expressions(graph: &NodeGraph, input: AttributeID) -> Option<[String; 3]> {
+    let node_id = graph.get_attribute_as_linked_node(input)?;
+    match *graph.get_node(node_id)?.contents() {
+        NodeContents::Surface { fx, fy, fz, .. } => Some([
+            graph.get_attribute_as_string(fx)?,
+            graph.get_attribute_as_string(fy)?,
+            graph.get_attribute_as_string(fz)?,
+        ]),
+        _ => None,
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+const WORKGROUP_SIZE: usize = Parameter::POINTS_PER_SEGMENT;
+
+// Some nodes (e.g. Bezier, Spline or Revolution) create parameters that have no name,
+// which means that they cannot be sampled nor used by a matrix. This node gives them a name,
+// copying the geometry so that the original one is left untouched.
+pub fn create(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    names: [String; 3],
+) -> SingleDataResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Name Parameters node \n is missing its Geometry input ".into()))?;
+    let geometry_data = data_map
+        .get(&data_id)
+        .ok_or(ProcessingError::NoInputData)?;
+
+    let (buffer, params) = match geometry_data {
+        Data::Geom1D { buffer, param }
+            => (buffer, vec![param.clone()]),
+        Data::Geom2D { buffer, param1, param2 }
+            => (buffer, vec![param1.clone(), param2.clone()]),
+        Data::Geom3D { buffer, param1, param2, param3 }
+            => (buffer, vec![param1.clone(), param2.clone(), param3.clone()]),
+        _ => return Err(ProcessingError::IncorrectInput(" only curves, surfaces \n and volumes have parameters ".into())),
+    };
+
+    let mut renamed_params = Vec::<Parameter>::new();
+    for (i, name) in names.iter().enumerate() {
+        if name.trim().is_empty() {
+            // an empty name leaves the parameter as it is
+            if let Some(param) = params.get(i) {
+                renamed_params.push(param.clone());
+            }
+            continue;
+        }
+        let mut param = params.get(i)
+            .cloned()
+            .ok_or_else(|| ProcessingError::IncorrectAttributes(format!(" this geometry has \n only {} parameter(s) ", params.len())))?;
+        param.name = Some(Globals::sanitize_variable_name(name)?);
+        renamed_params.push(param);
+    }
+
+    // two parameters with the same name would make sampling and transforming ambiguous
+    for (i, param) in renamed_params.iter().enumerate() {
+        if param.name.is_some() && renamed_params[i + 1..].iter().any(|other| other.name == param.name) {
+            return Err(ProcessingError::IncorrectAttributes(" the parameters of a geometry \n must have different names ".into()));
+        }
+    }
+
+    // the copy is dispatched along all the dimensions of the geometry, since a volume
+    // can contain more points than the workgroups allowed in a single dimension
+    let mut sizes = [1usize; 3];
+    for (size, param) in sizes.iter_mut().zip(renamed_params.iter()) {
+        *size = param.n_points();
+    }
+    let n_points: usize = sizes.iter().product();
+
+    let wgsl_source = format!(r##"
+struct PositionBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_buff: PositionBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out_buff: PositionBuffer;
+
+[[stage(compute), workgroup_size({wg_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.x + {size_x}u * (global_id.y + {size_y}u * global_id.z);
+    out_buff.positions[index] = in_buff.positions[index];
+}}
+"##, wg_size=WORKGROUP_SIZE, size_x=sizes[0], size_y=sizes[1],
+);
+
+    //println!("name parameters shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * n_points);
+
+    let bind_info = vec![
+        BindInfo {
+            buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [(sizes[0] / WORKGROUP_SIZE) as u32, sizes[1] as u32, sizes[2] as u32],
+    };
+    let mut renamed_params = renamed_params.into_iter();
+    let new_data = match geometry_data {
+        Data::Geom1D { .. } => Data::Geom1D {
+            buffer: output_buffer,
+            param: renamed_params.next().unwrap(),
+        },
+        Data::Geom2D { .. } => Data::Geom2D {
+            buffer: output_buffer,
+            param1: renamed_params.next().unwrap(),
+            param2: renamed_params.next().unwrap(),
+        },
+        Data::Geom3D { .. } => Data::Geom3D {
+            buffer: output_buffer,
+            param1: renamed_params.next().unwrap(),
+            param2: renamed_params.next().unwrap(),
+            param3: renamed_params.next().unwrap(),
+        },
+        _ => return Err(ProcessingError::InternalError(" unexpected geometry kind ".into())),
+    };
+
+    Ok((new_data, operation))
+}
@@ -56,7 +56,7 @@ pub fn create(
     let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
         label: Some("model index buffer"),
         contents: bytemuck::cast_slice(&plane_indices),
-        usage: wgpu::BufferUsages::INDEX,
+        usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
     });
 
     let chunks_count = prefab_vertices.len() / MODEL_CHUNK_VERTICES;
@@ -67,6 +67,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 struct PointBuffer {{
@@ -124,6 +125,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     out_buff.vertices[index].normal = normal.direction;
     out_buff.vertices[index].uv_coords = prefab.vertices[index].uv_coords;
     out_buff.vertices[index].padding = prefab.vertices[index].padding;
+    out_buff.vertices[index].color = prefab.vertices[index].color;
 }}
 "##, vertices_per_chunk = MODEL_CHUNK_VERTICES,
 );
@@ -179,24 +181,28 @@ fn plane_prefab(side_length: f32) -> (Vec<StandardVertexData>, Vec<u32>) {
             normal: [0.0, 0.0, 1.0, 0.0],
             uv_coords: [0.0, 0.0],
             _padding: [2.22, 3.33],
+            color: [0.0, 0.0, 0.0, 0.0],
         },
         StandardVertexData{
             position: [side_length/2.0, -side_length/2.0, 0.0, 1.0],
             normal: [0.0, 0.0, 1.0, 0.0],
             uv_coords: [corner_uv, 0.0],
             _padding: [2.22, 3.33],
+            color: [0.0, 0.0, 0.0, 0.0],
         },
         StandardVertexData{
             position: [side_length/2.0, side_length/2.0, 0.0, 1.0],
             normal: [0.0, 0.0, 1.0, 0.0],
             uv_coords: [corner_uv, corner_uv],
             _padding: [2.22, 3.33],
+            color: [0.0, 0.0, 0.0, 0.0],
         },
         StandardVertexData{
             position: [-side_length/2.0, side_length/2.0, 0.0, 1.0],
             normal: [0.0, 0.0, 1.0, 0.0],
             uv_coords: [0.0, corner_uv],
             _padding: [2.22, 3.33],
+            color: [0.0, 0.0, 0.0, 0.0],
         },
     ];
 
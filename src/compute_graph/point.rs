@@ -1,10 +1,12 @@
+use std::collections::BTreeMap;
 use std::rc::Rc;
 use glam::Vec4;
 
 use super::Operation;
 use super::globals::Globals;
-use super::SingleDataResult;
-use super::Data;
+use super::{SingleDataResult, ProcessingError};
+use super::{DataID, Data};
+use crate::node_graph::CoordinateSystem;
 use crate::util;
 use crate::shader_processing::{naga_compute_pipeline, BindInfo};
 
@@ -62,4 +64,20 @@ fn main() {{
     Ok((new_data, operation))
 }
 
-
+// A point whose coordinates depend on an interval becomes a set of points, one for each
+// value of the interval. The points are computed exactly like the ones of a curve.
+pub fn create_set(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    interval_id: DataID,
+    fx: String,
+    fy: String,
+    fz: String,
+) -> SingleDataResult {
+    let (new_data, operation) = super::curve::create(device, globals, data_map, Some(interval_id), fx, fy, fz, CoordinateSystem::Cartesian)?;
+    match new_data {
+        Data::Geom1D { buffer, param } => Ok((Data::PointSet { buffer, param }, operation)),
+        _ => Err(ProcessingError::InternalError("the points of a point set were not computed as a curve".into())),
+    }
+}
@@ -12,9 +12,8 @@ use super::{SingleDataResult, ProcessingError};
 
 pub fn create(
     device: &wgpu::Device,
-    models: &[Model],
+    model: &Model,
     globals: &Globals,
-    primitive_id: usize,
     size: String,
 ) -> SingleDataResult {
     if size.is_empty() {
@@ -24,8 +23,6 @@ pub fn create(
     // Sanitize all input expressions
     let sanitized_size = globals.sanitize_expression(&[], &size)?;
 
-    let model = models.get(primitive_id).unwrap();
-
     let wgsl_source = format!(r##"
 {wgsl_globals}
 
@@ -34,6 +31,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 struct VertexBuffer {{
@@ -55,6 +53,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     out_buff.vertices[index].normal = in_buff.vertices[index].normal;
     out_buff.vertices[index].uv_coords = in_buff.vertices[index].uv_coords;
     out_buff.vertices[index].padding = in_buff.vertices[index].padding;
+    out_buff.vertices[index].color = in_buff.vertices[index].color;
 }}
 "##, wgsl_globals=&globals.get_wgsl_header(), vertices_per_chunk = MODEL_CHUNK_VERTICES, scaling=sanitized_size
 );
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::node_graph::Axis;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    axis: Axis,
+    axis_point: Option<DataID>,
+    axis_direction: Option<DataID>,
+    name: String,
+    begin: String,
+    end: String,
+    quality: usize,
+) -> SingleDataResult {
+    if !(1..=16).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(" Revolution quality \n must be an integer \n in the [1, 16] range ".into()));
+    }
+    if begin.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for the begin angle ".into()));
+    }
+    if end.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for the end angle ".into()));
+    }
+
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Revolution node \n is missing its curve input ".into()))?;
+    let (curve_buffer, curve_param) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => (buffer, param),
+        _ => return Err(ProcessingError::IncorrectInput(" Revolution first input \n is not a curve ".into()))
+    };
+
+    // The curve revolves around one of the coordinate axes, unless both a point and a direction
+    // are provided: in that case the axis is the line through the point with that direction.
+    let axis_buffers = match (axis_point, axis_direction) {
+        (None, None) => None,
+        (Some(point_id), Some(direction_id)) => {
+            let point_buffer = match data_map.get(&point_id).ok_or(ProcessingError::NoInputData)? {
+                Data::Geom0D { buffer } => buffer,
+                _ => return Err(ProcessingError::IncorrectInput(" Revolution axis point \n is not a point ".into()))
+            };
+            let direction_buffer = match data_map.get(&direction_id).ok_or(ProcessingError::NoInputData)? {
+                Data::Vector { buffer } => buffer,
+                _ => return Err(ProcessingError::IncorrectInput(" Revolution axis direction \n is not a vector ".into()))
+            };
+            Some((point_buffer, direction_buffer))
+        },
+        _ => return Err(ProcessingError::InputMissing(" please connect both the axis point \n and the axis direction, \n or none of them ".into())),
+    };
+
+    // the angle parameter is named just like the parameter of an Interval node,
+    // so that it can be sampled and matched with the parameter of a matrix
+    if name.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide a name \n for the angle parameter ".into()));
+    }
+    let sanitized_name = Globals::sanitize_variable_name(&name)?;
+    if curve_param.name.as_ref() == Some(&sanitized_name) {
+        return Err(ProcessingError::IncorrectAttributes(" the angle parameter must have \n a different name \n from the curve parameter ".into()));
+    }
+
+    // Just like in the Interval node, sanitizing also removes the whitespaces, so that
+    // the angle parameter can be compared with other ones.
+    let sanitized_begin = globals.sanitize_expression(&[], &begin)?;
+    let sanitized_end = globals.sanitize_expression(&[], &end)?;
+    let angle_param = Parameter {
+        name: Some(sanitized_name),
+        begin: sanitized_begin,
+        end: sanitized_end,
+        segments: quality as u32,
+        use_interval_as_uv: false,
+    };
+
+    let axis_code = match (axis_buffers, axis) {
+        (Some(_), _) => "let axis_point = axis_point_buff.position.xyz;\n    let axis_direction = normalize(axis_direction_buff.direction.xyz);",
+        (None, Axis::X) => "let axis_point = vec3<f32>(0.0, 0.0, 0.0);\n    let axis_direction = vec3<f32>(1.0, 0.0, 0.0);",
+        (None, Axis::Y) => "let axis_point = vec3<f32>(0.0, 0.0, 0.0);\n    let axis_direction = vec3<f32>(0.0, 1.0, 0.0);",
+        (None, Axis::Z) => "let axis_point = vec3<f32>(0.0, 0.0, 0.0);\n    let axis_direction = vec3<f32>(0.0, 0.0, 1.0);",
+    };
+    let axis_bindings = if axis_buffers.is_some() {
+        "[[group(0), binding(3)]] var<storage, read> axis_point_buff: PointBuffer;\n[[group(0), binding(4)]] var<storage, read> axis_direction_buff: VectorBuffer;"
+    } else {
+        ""
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct VectorBuffer {{
+    direction: vec4<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: SurfaceBuffer;
+{axis_bindings}
+
+[[stage(compute), workgroup_size({pps}, {pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let par1_idx = global_id.x;
+    let par2_idx = global_id.y;
+    let index = par1_idx + {size_x}u * par2_idx;
+
+    {axis_code}
+    let angle_begin: f32 = {begin};
+    let angle_end: f32 = {end};
+    let angle = angle_begin + (angle_end - angle_begin) * f32(par2_idx) / f32({size_y}u - 1u);
+
+    // rotate the curve point around the axis using Rodrigues' rotation formula
+    let v = in_curve.positions[par1_idx].xyz - axis_point;
+    let c = cos(angle);
+    let s = sin(angle);
+    let rotated = v * c + cross(axis_direction, v) * s + axis_direction * dot(axis_direction, v) * (1.0 - c);
+    output.positions[index] = vec4<f32>(axis_point + rotated, 1.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), pps=Parameter::POINTS_PER_SEGMENT,
+axis_bindings=axis_bindings, axis_code=axis_code,
+begin=&angle_param.begin, end=&angle_param.end,
+size_x=curve_param.n_points(), size_y=angle_param.n_points(),
+);
+
+    //println!("revolution shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * curve_param.n_points() * angle_param.n_points());
+
+    let mut bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: curve_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    if let Some((point_buffer, direction_buffer)) = axis_buffers {
+        bind_info.push(BindInfo {
+            buffer: point_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        });
+        bind_info.push(BindInfo {
+            buffer: direction_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        });
+    }
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [curve_param.segments, angle_param.segments, 1],
+    };
+    let new_data = Data::Geom2D {
+        buffer: output_buffer,
+        param1: curve_param.clone(),
+        param2: angle_param,
+    };
+
+    Ok((new_data, operation))
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub fn create(
+    device: &wgpu::Device,
+    data_map: &BTreeMap<DataID, Data>,
+    curve_1: Option<DataID>,
+    curve_2: Option<DataID>,
+    name: String,
+    quality: usize,
+) -> SingleDataResult {
+    if !(1..=16).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(" Ruled Surface quality \n must be an integer \n in the [1, 16] range ".into()));
+    }
+
+    let data_id = curve_1
+        .ok_or_else(|| ProcessingError::InputMissing(" This Ruled Surface node \n is missing its first input ".into()))?;
+    let (buffer_1, param_1) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => (buffer, param),
+        _ => return Err(ProcessingError::IncorrectInput(" Ruled Surface first input \n is not a curve ".into()))
+    };
+    let data_id = curve_2
+        .ok_or_else(|| ProcessingError::InputMissing(" This Ruled Surface node \n is missing its second input ".into()))?;
+    let (buffer_2, param_2) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => (buffer, param),
+        _ => return Err(ProcessingError::IncorrectInput(" Ruled Surface second input \n is not a curve ".into()))
+    };
+
+    // points are joined by index, so the two curves must be defined over the same interval.
+    // Anonymous parameters (e.g. the ones of Bezier curves) cannot be compared by name,
+    // in that case we just require the same number of points.
+    let same_interval = match (&param_1.name, &param_2.name) {
+        (Some(_), Some(_)) => param_1.is_equal(param_2)?,
+        _ => param_1.segments == param_2.segments,
+    };
+    if !same_interval {
+        return Err(ProcessingError::IncorrectInput(" the two curves \n must share the same interval ".into()));
+    }
+
+    // the rulings parameter goes from the first curve (0) to the second one (1),
+    // and it is named so that it can be sampled and used by other nodes
+    if name.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide a name \n for the rulings parameter ".into()));
+    }
+    let sanitized_name = Globals::sanitize_variable_name(&name)?;
+    if param_1.name.as_ref() == Some(&sanitized_name) {
+        return Err(ProcessingError::IncorrectAttributes(" the rulings parameter must have \n a different name \n from the curve parameter ".into()));
+    }
+
+    let rulings_param = Parameter {
+        name: Some(sanitized_name),
+        begin: "0.0".into(),
+        end: "1.0".into(),
+        segments: quality as u32,
+        use_interval_as_uv: false,
+    };
+
+    let wgsl_source = format!(r##"
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_curve_1: CurveBuffer;
+[[group(0), binding(1)]] var<storage, read> in_curve_2: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: SurfaceBuffer;
+
+[[stage(compute), workgroup_size({pps}, {pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let par1_idx = global_id.x;
+    let par2_idx = global_id.y;
+    let index = par1_idx + {size_x}u * par2_idx;
+    let alpha = f32(par2_idx) / f32({size_y}u - 1u);
+    output.positions[index] = mix(in_curve_1.positions[par1_idx], in_curve_2.positions[par1_idx], alpha);
+}}
+"##, pps=Parameter::POINTS_PER_SEGMENT, size_x=param_1.n_points(), size_y=rulings_param.n_points(),
+);
+
+    //println!("ruled surface shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * param_1.n_points() * rulings_param.n_points());
+
+    let bind_info = vec![
+        BindInfo {
+            buffer: buffer_1,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: buffer_2,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param_1.segments, rulings_param.segments, 1],
+    };
+    let new_data = Data::Geom2D {
+        buffer: output_buffer,
+        param1: param_1.clone(),
+        param2: rulings_param,
+    };
+
+    Ok((new_data, operation))
+}
@@ -13,13 +13,20 @@ use crate::shader_processing::{naga_compute_pipeline, BindInfo};
 // possibly using a const-time formatting library like https://docs.rs/const_format/
 const CHUNK_SIZE: usize = super::Parameter::POINTS_PER_SEGMENT;
 
+// the largest number of values a family can be sampled at
+const MAX_FAMILY_SAMPLES: usize = 32;
+
+#[allow(clippy::too_many_arguments)]
 pub fn create(
     device: &wgpu::Device,
     globals: &Globals,
     data_map: &BTreeMap<DataID, Data>,
     geometry_id: Option<DataID>,
+    interval_id: Option<DataID>,
     parameter_name: String,
     sample_value: String,
+    samples: usize,
+    last_value: String,
 ) -> SingleDataResult {
     let data_id = geometry_id
         .ok_or_else(|| ProcessingError::InputMissing(" This Sample node \n is missing its Geometry input ".into()))?;
@@ -27,6 +34,33 @@ pub fn create(
         .get(&data_id)
         .ok_or(ProcessingError::NoInputData)?;
 
+    // if several values are given, the geometry is sampled at each of them and the results form a family
+    if let Some(family_values) = family_values(globals, &sample_value, samples, &last_value)? {
+        if interval_id.is_some() {
+            return Err(ProcessingError::IncorrectAttributes(" an interval cannot be used \n when sampling \n at several values ".into()));
+        }
+        return match &geometry_data {
+            Data::Geom1D{buffer, param}
+                => sample_1d_family(device, globals, buffer, param, &parameter_name, &family_values),
+            Data::Geom2D{buffer, param1, param2}
+                => sample_2d_family(device, globals, buffer, param1, param2, &parameter_name, family_values),
+            _ => Err(ProcessingError::IncorrectInput(" only curves and surfaces \n can be sampled \n at several values ".into())),
+        };
+    }
+
+    // if an interval is connected, the sample value can depend on it and a point is taken for each of its values
+    if let Some(data_id) = interval_id {
+        let (interval_buffer, interval_param) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+            Data::Interval { buffer, param } => (buffer, param),
+            _ => return Err(ProcessingError::IncorrectInput(" the input provided to the Sample node \n is not an interval ".into())),
+        };
+        return match &geometry_data {
+            Data::Geom1D{buffer, param}
+                => sample_1d_points(device, globals, buffer, param, interval_buffer, interval_param, &parameter_name, &sample_value),
+            _ => Err(ProcessingError::IncorrectInput(" only curves can be sampled \n at every value of an interval ".into())),
+        };
+    }
+
     match &geometry_data {
         Data::Geom0D{..}
             => Err(ProcessingError::IncorrectInput(" cannot sample from \n a point (0d geometry) ".into())),
@@ -37,9 +71,15 @@ pub fn create(
         Data::Geom2D{buffer, param1, param2}
             => sample_2d_1d(device, globals, buffer, param1, param2, &parameter_name, &sample_value),
 
+        Data::Geom3D{buffer, param1, param2, param3}
+            => sample_3d_2d(device, globals, buffer, [param1, param2, param3], &parameter_name, &sample_value),
+
         Data::Prefab { .. }
             => Err(ProcessingError::IncorrectInput(" cannot sample from \n a primitive ".into())),
 
+        Data::Segments { .. }
+            => Err(ProcessingError::IncorrectInput(" cannot sample from \n an implicit curve ".into())),
+
         _ => Err(ProcessingError::InternalError(" input provided to sample \n is not a geometry ".into()))
     }
 
@@ -66,9 +106,8 @@ fn sample_1d_0d(
             return Err(ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into()));
         }
     } else {
-        // if the geometry parameter does not exist, error our as well.
-        // TODO: we might want to change this, so that one can sample a Bezier curve
-        return Err(ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into()));
+        // anonymous parameters (e.g. the one of a Bezier curve) must be named before sampling
+        return Err(ProcessingError::IncorrectAttributes(" the curve parameter \n has no name: use a \n Name Parameters node ".into()));
     }
 
     let wgsl_source = format!(r##"
@@ -136,6 +175,101 @@ fn main() {{
     Ok((new_data, operation))
 }
 
+// samples a curve once for each value of the interval, the output is a point set
+#[allow(clippy::too_many_arguments)]
+fn sample_1d_points(
+    device: &wgpu::Device,
+    globals: &Globals,
+    geom_buffer: &wgpu::Buffer,
+    geom_param: &Parameter,
+    interval_buffer: &wgpu::Buffer,
+    interval_param: &Parameter,
+    parameter_name: &str,
+    sample_value: &str,
+    ) -> SingleDataResult {
+
+    // Sanitize all input expressions, the sample value can use the interval variable
+    let sanitized_name = Globals::sanitize_variable_name(parameter_name)?;
+    let interval_name = interval_param.name.as_ref().unwrap();
+    let sanitized_value = globals.sanitize_expression(&[interval_name.as_str()], sample_value)?;
+
+    match geom_param.name.as_ref() {
+        Some(name) if name == &sanitized_name => {},
+        Some(_) => return Err(ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into())),
+        None => return Err(ProcessingError::IncorrectAttributes(" the curve parameter \n has no name: use a \n Name Parameters node ".into())),
+    }
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct IntervalBuffer {{
+    values: array<f32>;
+}};
+
+// binding 0 used by global vars, as usual
+[[group(0), binding(1)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read> in_interval: IntervalBuffer;
+[[group(0), binding(3)]] var<storage, read_write> output: CurveBuffer;
+
+[[stage(compute), workgroup_size({CHUNK_SIZE})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let {interval_name} = in_interval.values[global_id.x];
+    // parameter space is linear, so we can figure out which index we should access
+    let size = f32({array_size});
+    let interval_begin: f32 = {begin};
+    let interval_end: f32 = {end};
+    // transform the interval so that it extends from 0 to size-1, and scale the sampling value accordingly
+    let value = ({sample_value} - interval_begin) * (size - 1.0) / (interval_end - interval_begin);
+    // compute the indices to use in the interpolation and interpolation weight
+    let inf_value = floor(value);
+    let sup_value = ceil(value);
+    let alpha = fract(value);
+    // clamp index acces to make sure nothing bad happens,
+    // even if the provided value was outside of parameter interval
+    let inf_idx = i32(clamp(inf_value, 0.0, size - 1.0));
+    let sup_idx = i32(clamp(sup_value, 0.0, size - 1.0));
+    output.positions[global_id.x] = (1.0 - alpha) * in_curve.positions[inf_idx] + alpha * in_curve.positions[sup_idx];
+}}
+"##, wgsl_header=globals.get_wgsl_header(), interval_name=interval_name, begin=&geom_param.begin, end=&geom_param.end,
+    sample_value=sanitized_value, array_size=geom_param.n_points(), CHUNK_SIZE=CHUNK_SIZE);
+
+    //println!("sample 1d->points shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * interval_param.n_points());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: interval_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [interval_param.segments, 1, 1],
+    };
+    let new_data = Data::PointSet {
+        buffer: output_buffer,
+        param: interval_param.clone(),
+    };
+
+    Ok((new_data, operation))
+}
+
 fn sample_2d_1d(
     device: &wgpu::Device,
     globals: &Globals,
@@ -245,3 +379,358 @@ begin=&sampled_param.begin, end=&sampled_param.end, sample_value=sanitized_value
 
     Ok((new_data, operation))
 }
+
+fn sample_3d_2d(
+    device: &wgpu::Device,
+    globals: &Globals,
+    geom_buffer: &wgpu::Buffer,
+    geom_params: [&Parameter; 3],
+    parameter_name: &str,
+    sample_value: &str,
+    ) -> SingleDataResult {
+
+    // Sanitize all input expressions
+    let sanitized_name = Globals::sanitize_variable_name(parameter_name)?;
+    let sanitized_value = globals.sanitize_expression(&[], sample_value)?;
+
+    let which_param = geom_params.iter()
+        .position(|param| param.name.as_ref() == Some(&sanitized_name))
+        .ok_or_else(|| ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into()))?;
+
+    // the two parameters that are not sampled become the parameters of the output surface,
+    // in the same order in which they appear in the volume
+    let sampled_param = geom_params[which_param].clone();
+    let mut nonsampled_params = geom_params.iter()
+        .enumerate()
+        .filter(|(i, _)| *i != which_param)
+        .map(|(_, param)| (*param).clone());
+    let out_param1 = nonsampled_params.next().unwrap();
+    let out_param2 = nonsampled_params.next().unwrap();
+
+    // the volume coordinates of the sampled points, depending on which param is being sampled
+    let volume_coords = match which_param {
+        0 => "vec3<u32>(idx, global_id.x, global_id.y)",
+        1 => "vec3<u32>(global_id.x, idx, global_id.y)",
+        _ => "vec3<u32>(global_id.x, global_id.y, idx)",
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct VolumeBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+// binding 0 used by global vars, as usual
+[[group(0), binding(1)]] var<storage, read> in_volume: VolumeBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: SurfaceBuffer;
+
+fn volume_index(idx: u32, global_id: vec3<u32>) -> u32 {{
+    let coords = {volume_coords};
+    return coords.x + {size_x}u * (coords.y + {size_y}u * coords.z);
+}}
+
+[[stage(compute), workgroup_size({CHUNK_SIZE}, {CHUNK_SIZE})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    // parameter space is linear, so we can figure out which index we should access
+    let size = f32({sampled_array_size});
+    let interval_begin: f32 = {begin};
+    let interval_end: f32 = {end};
+    // transform the interval so that it extends from 0 to size-1, and scale the sampling value accordingly
+    let value = ({sample_value} - interval_begin) * (size - 1.0) / (interval_end - interval_begin);
+    // compute the indices to use in the interpolation and interpolation weight
+    let inf_value = floor(value);
+    let sup_value = ceil(value);
+    let alpha = fract(value);
+    // clamp index acces to make sure nothing bad happens,
+    // even if the provided value was outside of parameter interval
+    let inf_idx = u32(clamp(inf_value, 0.0, size - 1.0));
+    let sup_idx = u32(clamp(sup_value, 0.0, size - 1.0));
+
+    let inf_index = volume_index(inf_idx, global_id);
+    let sup_index = volume_index(sup_idx, global_id);
+    let index = global_id.x + {out_size_x}u * global_id.y;
+    output.positions[index] = (1.0 - alpha) * in_volume.positions[inf_index] + alpha * in_volume.positions[sup_index];
+}}
+"##, wgsl_header=&globals.get_wgsl_header(), sampled_array_size=sampled_param.n_points(),
+volume_coords=volume_coords, size_x=geom_params[0].n_points(), size_y=geom_params[1].n_points(),
+out_size_x=out_param1.n_points(), CHUNK_SIZE=CHUNK_SIZE,
+begin=&sampled_param.begin, end=&sampled_param.end, sample_value=sanitized_value);
+
+    //println!("sample 3d->2d shader source:\n {}", &wgsl_source);
+    // the whole volume is bound to the shader
+    super::volume::check_points_count(device, geom_params.iter().map(|param| param.n_points()).product())?;
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * out_param1.n_points() * out_param2.n_points());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [out_param1.segments, out_param2.segments, 1],
+    };
+    let new_data = Data::Geom2D {
+        buffer: output_buffer,
+        param1: out_param1,
+        param2: out_param2,
+    };
+
+    Ok((new_data, operation))
+}
+
+// Returns the sanitized values at which a family is sampled, or None if a single value is used.
+// The value can be a comma separated list of expressions, otherwise if more than one sample
+// is requested the samples are evenly spaced between the value and the last value (both included).
+fn family_values(globals: &Globals, sample_value: &str, samples: usize, last_value: &str) -> Result<Option<Vec<String>>, ProcessingError> {
+    // split the value on the commas that are not inside parentheses, e.g. the ones of max(a, b)
+    let mut items = Vec::<&str>::new();
+    let mut depth = 0;
+    let mut item_begin = 0;
+    for (i, c) in sample_value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&sample_value[item_begin..i]);
+                item_begin = i + 1;
+            },
+            _ => {},
+        }
+    }
+    items.push(&sample_value[item_begin..]);
+
+    let values = if items.len() > 1 {
+        items.into_iter()
+            .map(|item| globals.sanitize_expression(&[], item))
+            .collect::<Result<Vec<String>, ProcessingError>>()?
+    } else if samples > 1 {
+        let first = globals.sanitize_expression(&[], sample_value)?;
+        let last = globals.sanitize_expression(&[], last_value)?;
+        (0..samples)
+            .map(|i| format!("({first}) + (({last}) - ({first})) * {alpha:?}", first=first, last=last, alpha=i as f32 / (samples - 1) as f32))
+            .collect()
+    } else {
+        return Ok(None);
+    };
+    if values.len() > MAX_FAMILY_SAMPLES {
+        return Err(ProcessingError::IncorrectAttributes(format!(" at most {} values \n can be sampled ", MAX_FAMILY_SAMPLES)));
+    }
+    Ok(Some(values))
+}
+
+// samples a curve at several values, the output is a point set.
+// The point set is made of whole segments, the points past the last value are hidden.
+fn sample_1d_family(
+    device: &wgpu::Device,
+    globals: &Globals,
+    geom_buffer: &wgpu::Buffer,
+    geom_param: &Parameter,
+    parameter_name: &str,
+    family_values: &[String],
+    ) -> SingleDataResult {
+
+    let sanitized_name = Globals::sanitize_variable_name(parameter_name)?;
+    match geom_param.name.as_ref() {
+        Some(name) if name == &sanitized_name => {},
+        Some(_) => return Err(ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into())),
+        None => return Err(ProcessingError::IncorrectAttributes(" the curve parameter \n has no name: use a \n Name Parameters node ".into())),
+    }
+
+    let out_param = Parameter {
+        name: None,
+        segments: ((family_values.len() - 1) / CHUNK_SIZE + 1) as u32,
+        begin: "0.0".into(),
+        end: "1.0".into(),
+        use_interval_as_uv: false,
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+// binding 0 used by global vars, as usual
+[[group(0), binding(1)]] var<storage, read> in_curve: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: CurveBuffer;
+
+[[stage(compute), workgroup_size({CHUNK_SIZE})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    if (global_id.x >= {samples_count}u) {{
+        output.positions[global_id.x] = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        return;
+    }}
+    var sample_values = array<f32, {samples_count}>({sample_values});
+    // parameter space is linear, so we can figure out which index we should access
+    let size = f32({array_size});
+    let interval_begin: f32 = {begin};
+    let interval_end: f32 = {end};
+    // transform the interval so that it extends from 0 to size-1, and scale the sampling value accordingly
+    let value = (sample_values[global_id.x] - interval_begin) * (size - 1.0) / (interval_end - interval_begin);
+    // compute the indices to use in the interpolation and interpolation weight
+    let inf_value = floor(value);
+    let sup_value = ceil(value);
+    let alpha = fract(value);
+    // clamp index acces to make sure nothing bad happens,
+    // even if the provided value was outside of parameter interval
+    let inf_idx = i32(clamp(inf_value, 0.0, size - 1.0));
+    let sup_idx = i32(clamp(sup_value, 0.0, size - 1.0));
+    output.positions[global_id.x] = (1.0 - alpha) * in_curve.positions[inf_idx] + alpha * in_curve.positions[sup_idx];
+}}
+"##, wgsl_header=globals.get_wgsl_header(), begin=&geom_param.begin, end=&geom_param.end,
+    samples_count=family_values.len(), sample_values=family_values.join(", "),
+    array_size=geom_param.n_points(), CHUNK_SIZE=CHUNK_SIZE);
+
+    //println!("sample 1d->family shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * out_param.n_points());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [out_param.segments, 1, 1],
+    };
+    let new_data = Data::PointSet {
+        buffer: output_buffer,
+        param: out_param,
+    };
+
+    Ok((new_data, operation))
+}
+
+// samples a surface at several values of one of its parameters, the output is a family of curves
+fn sample_2d_family(
+    device: &wgpu::Device,
+    globals: &Globals,
+    geom_buffer: &wgpu::Buffer,
+    geom_param1: &Parameter,
+    geom_param2: &Parameter,
+    parameter_name: &str,
+    family_values: Vec<String>,
+    ) -> SingleDataResult {
+
+    let sanitized_name = Globals::sanitize_variable_name(parameter_name)?;
+    let which_param = match (geom_param1.name.as_ref(), geom_param2.name.as_ref()) {
+        (Some(name), _) if name == &sanitized_name => 1,
+        (_, Some(name)) if name == &sanitized_name => 2,
+        _ => return Err(ProcessingError::IncorrectAttributes(" the parameter used \n is not known ".into())),
+    };
+    let (sampled_param, nonsampled_param) = if which_param == 1 {
+        (geom_param1.clone(), geom_param2.clone())
+    } else {
+        (geom_param2.clone(), geom_param1.clone())
+    };
+    let curves_count = family_values.len();
+
+    // each row of workgroups computes one of the curves
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+// binding 0 used by global vars, as usual
+[[group(0), binding(1)]] var<storage, read> in_surface: SurfaceBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: CurveBuffer;
+
+[[stage(compute), workgroup_size({CHUNK_SIZE})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    var sample_values = array<f32, {curves_count}>({sample_values});
+    // parameter space is linear, so we can figure out which index we should access
+    let size = f32({sampled_array_size});
+    let interval_begin: f32 = {begin};
+    let interval_end: f32 = {end};
+    // transform the interval so that it extends from 0 to size-1, and scale the sampling value accordingly
+    let value = (sample_values[global_id.y] - interval_begin) * (size - 1.0) / (interval_end - interval_begin);
+    // compute the indices to use in the interpolation and interpolation weight
+    let inf_value = floor(value);
+    let sup_value = ceil(value);
+    let alpha = fract(value);
+    // clamp index acces to make sure nothing bad happens,
+    // even if the provided value was outside of parameter interval
+    let inf_idx = u32(clamp(inf_value, 0.0, size - 1.0));
+    let sup_idx = u32(clamp(sup_value, 0.0, size - 1.0));
+
+    // we now have to compute the two indices differently, depending on the parameter
+    var inf_index: u32;
+    var sup_index: u32;
+    if ({sampling_first_param}) {{
+        // if the param being sampled is the first one
+        inf_index = inf_idx + {first_array_size}u * global_id.x;
+        sup_index = sup_idx + {first_array_size}u * global_id.x;
+    }} else {{
+        // if the param being sampled is the second one
+        inf_index = global_id.x + {first_array_size}u * inf_idx;
+        sup_index = global_id.x + {first_array_size}u * sup_idx;
+    }}
+    let out_index = global_id.x + {curve_size}u * global_id.y;
+    output.positions[out_index] = (1.0 - alpha) * in_surface.positions[inf_index] + alpha * in_surface.positions[sup_index];
+}}
+"##, wgsl_header=&globals.get_wgsl_header(), sampled_array_size=sampled_param.n_points(),
+first_array_size=geom_param1.n_points(), curve_size=nonsampled_param.n_points(),
+sampling_first_param= which_param==1, CHUNK_SIZE=CHUNK_SIZE,
+curves_count=curves_count, sample_values=family_values.join(", "),
+begin=&sampled_param.begin, end=&sampled_param.end);
+
+    //println!("sample 2d->family shader source:\n {}", &wgsl_source);
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * nonsampled_param.n_points() * curves_count);
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [nonsampled_param.segments, curves_count as u32, 1],
+    };
+    let new_data = Data::CurveFamily {
+        buffer: output_buffer,
+        param: nonsampled_param,
+        curves_count,
+        sampled_name: sanitized_name,
+        sampled_values: family_values,
+    };
+
+    Ok((new_data, operation))
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::ProcessingError;
+use super::{DataID, Data};
+use super::Parameter;
+use crate::node_graph::SplineKind;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub const MAX_CONTROL_POINTS: usize = 8;
+
+// the device only guarantees a handful of storage buffers per shader, so the control points
+// are first gathered into a single buffer, a few of them at a time.
+const GATHER_CHUNK_SIZE: usize = 4;
+
+// the Spline node outputs both the curve and its control polygon
+pub type SplineResult = Result<(Data, Data, Vec<Operation>), ProcessingError>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    control_points_ids: Vec<DataID>,
+    kind: SplineKind,
+    degree: usize,
+    weights: String,
+    quality: usize,
+) -> SplineResult {
+    if !(1..=16).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(" Spline quality \n must be an integer \n in the [1, 16] range ".into()));
+    }
+    let n_control = control_points_ids.len();
+    if n_control < 2 {
+        return Err(ProcessingError::InputMissing(" A Spline curve requires \n at least 2 points ".into()));
+    }
+    if n_control > MAX_CONTROL_POINTS {
+        return Err(ProcessingError::InternalError(" too many control points ".into()));
+    }
+
+    let degree = resolve_degree(kind, n_control, degree)?;
+    let (knots, domain_begin, domain_end) = compute_knots(kind, n_control, degree);
+
+    // an empty string means that all the weights are 1, i.e: the curve is not rational
+    let sanitized_weights: Vec<String> = if weights.trim().is_empty() {
+        vec!["1.0".into(); n_control]
+    } else {
+        let weights: Vec<&str> = weights.split(',').collect();
+        if weights.len() != n_control {
+            return Err(ProcessingError::IncorrectAttributes(format!(" {} weights were given \n for {} control points: \n write one weight \n for each linked point ", weights.len(), n_control)));
+        }
+        weights.into_iter()
+            .map(|weight| globals.sanitize_expression(&[], weight))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut point_buffers = Vec::<&wgpu::Buffer>::new();
+    for id in control_points_ids.iter() {
+        point_buffers.push(get_point_buffer(data_map, *id)?);
+    }
+
+    let param = Parameter {
+        name: None,
+        begin: "0.0".into(),
+        end: "1.0".into(),
+        segments: quality as u32,
+        use_interval_as_uv: false,
+    };
+    let segments_count = n_control - 1;
+
+    let control_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * n_control);
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * param.n_points());
+    let polygon_buffer = util::create_storage_buffer(device, Data::SEGMENT_POINT_SIZE * 2 * segments_count);
+
+    let mut operations = Vec::<Operation>::new();
+    for (chunk_idx, chunk) in point_buffers.chunks(GATHER_CHUNK_SIZE).enumerate() {
+        operations.push(gather_operation(device, chunk, chunk_idx * GATHER_CHUNK_SIZE, &control_buffer));
+    }
+
+    let knots_list: Vec<String> = knots.iter().map(|knot| format!("{:?}", knot)).collect();
+    let domain_begin = format!("{:?}", domain_begin);
+    let domain_end = format!("{:?}", domain_end);
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct ControlBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct OutputBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct SegmentPoint {{
+    position: vec4<f32>;
+    tangent: vec4<f32>;
+    up: vec4<f32>;
+}};
+
+struct SegmentBuffer {{
+    points: array<SegmentPoint>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> control: ControlBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: OutputBuffer;
+[[group(0), binding(3)]] var<storage, read_write> polygon: SegmentBuffer;
+
+fn compute_default_up(tangent: vec3<f32>) -> vec3<f32> {{
+    if (abs(tangent.x) > 0.333) {{
+        return normalize(cross(tangent, vec3<f32>(0.0, 1.0, 0.0)));
+    }} else {{
+        return normalize(cross(tangent, vec3<f32>(1.0, 0.0, 0.0)));
+    }}
+}}
+
+[[stage(compute), workgroup_size({chunk_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.x;
+
+    // the first invocations also write the control polygon, one segment for each side
+    if (index < {segments_count}u) {{
+        let begin = control.positions[index];
+        let end = control.positions[index + 1u];
+        let direction = end.xyz - begin.xyz;
+        var point: SegmentPoint;
+        if (length(direction) < 1e-6) {{
+            // coincident points: the renderer turns a segment with no tangent into degenerate triangles
+            point.tangent = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+            point.up = vec4<f32>(0.0, 0.0, 1.0, 0.0);
+        }} else {{
+            point.tangent = vec4<f32>(normalize(direction), 0.0);
+            point.up = vec4<f32>(compute_default_up(point.tangent.xyz), 0.0);
+        }}
+        point.position = begin;
+        polygon.points[2u * index] = point;
+        point.position = end;
+        polygon.points[2u * index + 1u] = point;
+    }}
+
+    var weights = array<f32, {n_control}>({weights});
+    var knots = array<f32, {n_knots}>({knots});
+    let u = {domain_begin} + ({domain_end} - {domain_begin}) * f32(index) / f32({n_points}u - 1u);
+
+    // find the knot span that contains u
+    var span = {degree};
+    for (var j = {degree} + 1; j < {n_control}; j = j + 1) {{
+        if (knots[j] <= u) {{
+            span = j;
+        }}
+    }}
+
+    // de Boor's algorithm, run on homogeneous coordinates to support rational curves
+    var d: array<vec4<f32>, {n_local}>;
+    for (var j = 0; j <= {degree}; j = j + 1) {{
+        let i = j + span - {degree};
+        let w = weights[i];
+        d[j] = vec4<f32>(control.positions[i].xyz * w, w);
+    }}
+    for (var r = 1; r <= {degree}; r = r + 1) {{
+        for (var j = {degree}; j >= r; j = j - 1) {{
+            let i = j + span - {degree};
+            let alpha = (u - knots[i]) / (knots[i + 1 + {degree} - r] - knots[i]);
+            d[j] = mix(d[j - 1], d[j], alpha);
+        }}
+    }}
+    output.positions[index] = vec4<f32>(d[{degree}].xyz / d[{degree}].w, 1.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), chunk_size=Parameter::POINTS_PER_SEGMENT,
+segments_count=segments_count, n_control=n_control, weights=sanitized_weights.join(", "),
+n_knots=knots.len(), knots=knots_list.join(", "), domain_begin=domain_begin,
+domain_end=domain_end, n_points=param.n_points(), degree=degree, n_local=degree + 1,
+);
+
+    //println!("spline shader source:\n {}", &wgsl_source);
+
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &control_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &polygon_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    operations.push(Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param.segments, 1, 1],
+    });
+    let curve_data = Data::Geom1D {
+        buffer: output_buffer,
+        param,
+    };
+    let polygon_data = Data::Segments {
+        buffer: polygon_buffer,
+        segments_count,
+    };
+
+    Ok((curve_data, polygon_data, operations))
+}
+
+// A Bézier curve is a clamped B-spline whose degree is the number of control points minus one:
+// in that case de Boor's algorithm performs exactly the same steps as de Casteljau's one.
+pub fn resolve_degree(kind: SplineKind, n_control: usize, degree: usize) -> Result<usize, ProcessingError> {
+    match kind {
+        SplineKind::Bezier => Ok(n_control - 1),
+        SplineKind::UniformBSpline | SplineKind::ClampedBSpline => {
+            if degree < 1 || degree >= n_control {
+                Err(ProcessingError::IncorrectAttributes(" the degree must be lower \n than the number \n of control points ".into()))
+            } else {
+                Ok(degree)
+            }
+        },
+    }
+}
+
+// returns the knot vector and the interval of the knots over which the curve is defined
+pub fn compute_knots(kind: SplineKind, n_control: usize, degree: usize) -> (Vec<f32>, f32, f32) {
+    let n_knots = n_control + degree + 1;
+    match kind {
+        SplineKind::UniformBSpline => {
+            let knots = (0..n_knots).map(|j| j as f32).collect();
+            (knots, degree as f32, n_control as f32)
+        },
+        SplineKind::Bezier | SplineKind::ClampedBSpline => {
+            // the first and last knots are repeated degree + 1 times,
+            // so that the curve starts and ends on the first and last control points
+            let last = (n_control - degree) as f32;
+            let knots = (0..n_knots)
+                .map(|j| (j as f32 - degree as f32).clamp(0.0, last))
+                .collect();
+            (knots, 0.0, last)
+        },
+    }
+}
+
+// copies the position of each point into the control buffer, starting from the given offset
+fn gather_operation(device: &wgpu::Device, point_buffers: &[&wgpu::Buffer], offset: usize, control_buffer: &wgpu::Buffer) -> Operation {
+    let mut bindings = String::new();
+    let mut copies = String::new();
+    for i in 0..point_buffers.len() {
+        bindings += &format!("[[group(0), binding({i})]] var<storage, read> p{i}: PointBuffer;\n", i=i);
+        copies += &format!("    control.positions[{idx}] = p{i}.position;\n", idx=offset + i, i=i);
+    }
+
+    let wgsl_source = format!(r##"
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct ControlBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+{bindings}
+[[group(0), binding({control_binding})]] var<storage, read_write> control: ControlBuffer;
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+{copies}}}
+"##, bindings=bindings, control_binding=point_buffers.len(), copies=copies,
+);
+
+    //println!("spline gather shader source:\n {}", &wgsl_source);
+
+    let mut bind_info: Vec<BindInfo> = point_buffers.iter()
+        .map(|buffer| BindInfo {
+            buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        })
+        .collect();
+    bind_info.push(BindInfo {
+        buffer: control_buffer,
+        ty: wgpu::BufferBindingType::Storage { read_only: false },
+    });
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    }
+}
+
+fn get_point_buffer(data_map: &BTreeMap<DataID, Data>, id: DataID) -> Result<&wgpu::Buffer, ProcessingError> {
+    let found_element = data_map
+        .get(&id)
+        .ok_or(ProcessingError::NoInputData)?;
+    match found_element {
+        Data::Geom0D{ buffer } => Ok(buffer),
+        _ => Err(ProcessingError::IncorrectInput(" the input provided to Spline \n is not a Point ".into()))
+    }
+}
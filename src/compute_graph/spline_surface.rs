@@ -0,0 +1,242 @@
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::ProcessingError;
+use super::Data;
+use super::Parameter;
+use super::spline::{resolve_degree, compute_knots, SplineResult};
+use crate::node_graph::SplineKind;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub const MAX_NET_SIZE: usize = 8;
+
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    rows: Vec<String>,
+    kind: SplineKind,
+    degree: usize,
+    quality: usize,
+) -> SplineResult {
+    if !(1..=16).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(" Spline Surface quality \n must be an integer \n in the [1, 16] range ".into()));
+    }
+
+    // each row of the control net is a list of points separated by semicolons,
+    // and each point is a list of three expressions separated by commas, e.g. "0,0,0; 1,0,1; 2,0,0".
+    // Empty rows are skipped, just like unconnected control points in the Spline node.
+    let mut net = Vec::<Vec<String>>::new();
+    for row in rows.iter().filter(|row| !row.trim().is_empty()) {
+        let mut net_row = Vec::<String>::new();
+        for point in row.split(';') {
+            let coords: Vec<&str> = point.split(',').collect();
+            if coords.len() != 3 {
+                return Err(ProcessingError::IncorrectAttributes(" each control point \n must have three coordinates \n separated by commas ".into()));
+            }
+            let x = globals.sanitize_expression(&[], coords[0])?;
+            let y = globals.sanitize_expression(&[], coords[1])?;
+            let z = globals.sanitize_expression(&[], coords[2])?;
+            net_row.push(format!("vec4<f32>({}, {}, {}, 1.0)", x, y, z));
+        }
+        net.push(net_row);
+    }
+    let n_rows = net.len();
+    if n_rows < 2 {
+        return Err(ProcessingError::IncorrectAttributes(" A Spline Surface requires \n at least 2 rows of points ".into()));
+    }
+    let n_cols = net[0].len();
+    if net.iter().any(|net_row| net_row.len() != n_cols) {
+        return Err(ProcessingError::IncorrectAttributes(" all the rows must have \n the same number of points ".into()));
+    }
+    if n_cols < 2 {
+        return Err(ProcessingError::IncorrectAttributes(" each row requires \n at least 2 points ".into()));
+    }
+    if n_cols > MAX_NET_SIZE {
+        return Err(ProcessingError::IncorrectAttributes(" each row can contain \n at most 8 points ".into()));
+    }
+
+    // the first parameter goes along the rows, the second one across them
+    let u_degree = resolve_degree(kind, n_cols, degree)?;
+    let v_degree = resolve_degree(kind, n_rows, degree)?;
+    let (u_knots, u_begin, u_end) = compute_knots(kind, n_cols, u_degree);
+    let (v_knots, v_begin, v_end) = compute_knots(kind, n_rows, v_degree);
+
+    let param_1 = Parameter {
+        name: None,
+        begin: "0.0".into(),
+        end: "1.0".into(),
+        segments: quality as u32,
+        use_interval_as_uv: false,
+    };
+    let param_2 = param_1.clone();
+    // the control net overlay draws all the sides of the net, first along the rows and then along the columns
+    let row_segments = n_rows * (n_cols - 1);
+    let segments_count = row_segments + n_cols * (n_rows - 1);
+
+    let net_list: Vec<String> = net.into_iter().flatten().collect();
+    let u_knots_list: Vec<String> = u_knots.iter().map(|knot| format!("{:?}", knot)).collect();
+    let v_knots_list: Vec<String> = v_knots.iter().map(|knot| format!("{:?}", knot)).collect();
+    let u_begin = format!("{:?}", u_begin);
+    let u_end = format!("{:?}", u_end);
+    let v_begin = format!("{:?}", v_begin);
+    let v_end = format!("{:?}", v_end);
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct OutputBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct SegmentPoint {{
+    position: vec4<f32>;
+    tangent: vec4<f32>;
+    up: vec4<f32>;
+}};
+
+struct SegmentBuffer {{
+    points: array<SegmentPoint>;
+}};
+
+[[group(0), binding(1)]] var<storage, read_write> output: OutputBuffer;
+[[group(0), binding(2)]] var<storage, read_write> net_overlay: SegmentBuffer;
+
+var<private> net: array<vec4<f32>, {n_net}>;
+var<private> u_knots: array<f32, {n_u_knots}>;
+var<private> v_knots: array<f32, {n_v_knots}>;
+
+fn compute_default_up(tangent: vec3<f32>) -> vec3<f32> {{
+    if (abs(tangent.x) > 0.333) {{
+        return normalize(cross(tangent, vec3<f32>(0.0, 1.0, 0.0)));
+    }} else {{
+        return normalize(cross(tangent, vec3<f32>(1.0, 0.0, 0.0)));
+    }}
+}}
+
+fn write_net_segment(segment: u32, begin: vec4<f32>, end: vec4<f32>) {{
+    let direction = end.xyz - begin.xyz;
+    var point: SegmentPoint;
+    if (length(direction) < 1e-6) {{
+        point.tangent = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        point.up = vec4<f32>(0.0, 0.0, 1.0, 0.0);
+    }} else {{
+        point.tangent = vec4<f32>(normalize(direction), 0.0);
+        point.up = vec4<f32>(compute_default_up(point.tangent.xyz), 0.0);
+    }}
+    point.position = begin;
+    net_overlay.points[2u * segment] = point;
+    point.position = end;
+    net_overlay.points[2u * segment + 1u] = point;
+}}
+
+// evaluates the B-spline defined by a single row of the control net using de Boor's algorithm
+fn eval_row(row: i32, u: f32) -> vec4<f32> {{
+    var span = {u_degree};
+    for (var j = {u_degree} + 1; j < {n_cols}; j = j + 1) {{
+        if (u_knots[j] <= u) {{
+            span = j;
+        }}
+    }}
+    var d: array<vec4<f32>, {u_local}>;
+    for (var j = 0; j <= {u_degree}; j = j + 1) {{
+        d[j] = net[row * {n_cols} + j + span - {u_degree}];
+    }}
+    for (var r = 1; r <= {u_degree}; r = r + 1) {{
+        for (var j = {u_degree}; j >= r; j = j - 1) {{
+            let i = j + span - {u_degree};
+            let alpha = (u - u_knots[i]) / (u_knots[i + 1 + {u_degree} - r] - u_knots[i]);
+            d[j] = mix(d[j - 1], d[j], alpha);
+        }}
+    }}
+    return d[{u_degree}];
+}}
+
+[[stage(compute), workgroup_size({pps}, {pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let par1_idx = global_id.x;
+    let par2_idx = global_id.y;
+    let index = par1_idx + {size_x}u * par2_idx;
+
+    net = array<vec4<f32>, {n_net}>({net});
+    u_knots = array<f32, {n_u_knots}>({u_knots});
+    v_knots = array<f32, {n_v_knots}>({v_knots});
+
+    if (index < {row_segments}u) {{
+        let row = index / {n_cols_minus_one}u;
+        let col = index % {n_cols_minus_one}u;
+        write_net_segment(index, net[row * {n_cols}u + col], net[row * {n_cols}u + col + 1u]);
+    }} else if (index < {segments_count}u) {{
+        let col = (index - {row_segments}u) / {n_rows_minus_one}u;
+        let row = (index - {row_segments}u) % {n_rows_minus_one}u;
+        write_net_segment(index, net[row * {n_cols}u + col], net[(row + 1u) * {n_cols}u + col]);
+    }}
+
+    let u = {u_begin} + ({u_end} - {u_begin}) * f32(par1_idx) / f32({size_x}u - 1u);
+    let v = {v_begin} + ({v_end} - {v_begin}) * f32(par2_idx) / f32({size_y}u - 1u);
+
+    // tensor product: evaluate the rows at u, then run de Boor's algorithm on the results
+    var span = {v_degree};
+    for (var j = {v_degree} + 1; j < {n_rows}; j = j + 1) {{
+        if (v_knots[j] <= v) {{
+            span = j;
+        }}
+    }}
+    var d: array<vec4<f32>, {v_local}>;
+    for (var j = 0; j <= {v_degree}; j = j + 1) {{
+        d[j] = eval_row(j + span - {v_degree}, u);
+    }}
+    for (var r = 1; r <= {v_degree}; r = r + 1) {{
+        for (var j = {v_degree}; j >= r; j = j - 1) {{
+            let i = j + span - {v_degree};
+            let alpha = (v - v_knots[i]) / (v_knots[i + 1 + {v_degree} - r] - v_knots[i]);
+            d[j] = mix(d[j - 1], d[j], alpha);
+        }}
+    }}
+    output.positions[index] = vec4<f32>(d[{v_degree}].xyz, 1.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), pps=Parameter::POINTS_PER_SEGMENT,
+n_net=net_list.len(), net=net_list.join(", "), n_rows=n_rows, n_cols=n_cols,
+n_rows_minus_one=n_rows - 1, n_cols_minus_one=n_cols - 1,
+row_segments=row_segments, segments_count=segments_count,
+n_u_knots=u_knots.len(), u_knots=u_knots_list.join(", "), u_begin=u_begin, u_end=u_end,
+n_v_knots=v_knots.len(), v_knots=v_knots_list.join(", "), v_begin=v_begin, v_end=v_end,
+u_degree=u_degree, v_degree=v_degree, u_local=u_degree + 1, v_local=v_degree + 1,
+size_x=param_1.n_points(), size_y=param_2.n_points(),
+);
+
+    //println!("spline surface shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * param_1.n_points() * param_2.n_points());
+    let net_buffer = util::create_storage_buffer(device, Data::SEGMENT_POINT_SIZE * 2 * segments_count);
+
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+        BindInfo {
+            buffer: &net_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param_1.segments, param_2.segments, 1],
+    };
+    let surface_data = Data::Geom2D {
+        buffer: output_buffer,
+        param1: param_1,
+        param2: param_2,
+    };
+    let net_data = Data::Segments {
+        buffer: net_buffer,
+        segments_count,
+    };
+
+    Ok((surface_data, net_data, vec![operation]))
+}
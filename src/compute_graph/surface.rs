@@ -5,6 +5,7 @@ use super::globals::Globals;
 use super::{SingleDataResult, ProcessingError};
 use super::Parameter;
 use super::{DataID, Data};
+use crate::node_graph::CoordinateSystem;
 use crate::util;
 use crate::shader_processing::{naga_compute_pipeline, BindInfo};
 
@@ -18,6 +19,7 @@ pub fn create(
     fx: String,
     fy: String,
     fz: String,
+    coordinates: CoordinateSystem,
 ) -> SingleDataResult {
     //println!("new curve processing");
     let interval_1_id = interval_1
@@ -76,11 +78,12 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     let fx = {fx};
     let fy = {fy};
     let fz = {fz};
-    output.positions[index] = vec4<f32>(fx, fy, fz, 1.0);
+    output.positions[index] = {position};
 }}
 "##, wgsl_header=globals.get_wgsl_header(), pps=Parameter::POINTS_PER_SEGMENT,
 par1=param_1_name, par2=param_2_name,
-fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz, size_x=param_1.n_points()
+fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz, size_x=param_1.n_points(),
+position=super::curve::position_wgsl_code(coordinates),
 );
 
     //println!("surface shader source:\n {}", &wgsl_source);
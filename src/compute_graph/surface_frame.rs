@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::ProcessingError;
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+// point, tangent vectors and normal, followed by E, F, G and the Gaussian and mean curvatures
+pub type SurfaceFrameResult = Result<([Data; 9], Vec<Operation>), ProcessingError>;
+
+// Computes the differential quantities of a surface at the point identified by the values
+// of its two parameters. The tangent vectors are the partial derivatives with respect to the
+// parameters in the order given by the user, which also sets the orientation of the normal.
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    geometry_id: Option<DataID>,
+    parameter_names: [String; 2],
+    sample_values: [String; 2],
+    expressions: Option<[String; 3]>,
+) -> SurfaceFrameResult {
+    let data_id = geometry_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Surface Frame node \n is missing its Geometry input ".into()))?;
+    let (surface_buffer, param_1, param_2) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom2D { buffer, param1, param2 } => (buffer, param1, param2),
+        _ => return Err(ProcessingError::IncorrectInput(" the input geometry \n is not a surface ".into())),
+    };
+    let derivatives_code = derivatives_wgsl_code(globals, param_1, param_2, &parameter_names, &sample_values, expressions)?;
+
+    let frame_source = format!(r##"
+{wgsl_header}
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct PointBuffer {{
+    position: vec4<f32>;
+}};
+
+struct VectorBuffer {{
+    direction: vec4<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_surface: SurfaceBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_point: PointBuffer;
+[[group(0), binding(3)]] var<storage, read_write> out_u_tangent: VectorBuffer;
+[[group(0), binding(4)]] var<storage, read_write> out_v_tangent: VectorBuffer;
+[[group(0), binding(5)]] var<storage, read_write> out_normal: VectorBuffer;
+
+{derivatives_code}
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    compute_derivatives();
+    let su_cross_sv = cross(su, sv);
+    var normal = vec3<f32>(0.0, 0.0, 0.0);
+    // on singular points (e.g. the poles of a sphere) the normal is not defined
+    if (length(su_cross_sv) > 1e-6) {{
+        normal = normalize(su_cross_sv);
+    }}
+    out_point.position = vec4<f32>(position, 1.0);
+    out_u_tangent.direction = vec4<f32>(su, 0.0);
+    out_v_tangent.direction = vec4<f32>(sv, 0.0);
+    out_normal.direction = vec4<f32>(normal, 0.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), derivatives_code=derivatives_code,
+);
+
+    //println!("surface frame shader source:\n {}", &frame_source);
+
+    // the device allows just a few storage buffers in each shader,
+    // so the fundamental forms are computed by a second operation
+    let forms_source = format!(r##"
+{wgsl_header}
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct ScalarBuffer {{
+    value: f32;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_surface: SurfaceBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out_e: ScalarBuffer;
+[[group(0), binding(3)]] var<storage, read_write> out_f: ScalarBuffer;
+[[group(0), binding(4)]] var<storage, read_write> out_g: ScalarBuffer;
+[[group(0), binding(5)]] var<storage, read_write> out_gaussian: ScalarBuffer;
+[[group(0), binding(6)]] var<storage, read_write> out_mean: ScalarBuffer;
+
+{derivatives_code}
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    compute_derivatives();
+    // first fundamental form
+    let e = dot(su, su);
+    let f = dot(su, sv);
+    let g = dot(sv, sv);
+    let determinant = e * g - f * f;
+    var gaussian = 0.0;
+    var mean = 0.0;
+    if (determinant > 1e-12) {{
+        // second fundamental form
+        let normal = normalize(cross(su, sv));
+        let l = dot(suu, normal);
+        let m = dot(suv, normal);
+        let n = dot(svv, normal);
+        gaussian = (l * n - m * m) / determinant;
+        mean = (e * n - 2.0 * f * m + g * l) / (2.0 * determinant);
+    }}
+    out_e.value = e;
+    out_f.value = f;
+    out_g.value = g;
+    out_gaussian.value = gaussian;
+    out_mean.value = mean;
+}}
+"##, wgsl_header=globals.get_wgsl_header(), derivatives_code=derivatives_code,
+);
+
+    //println!("fundamental forms shader source:\n {}", &forms_source);
+
+    let point_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let u_tangent_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let v_tangent_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let normal_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>());
+    let e_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let f_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let g_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let gaussian_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let mean_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+
+    let mut frame_bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: surface_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+    ];
+    for buffer in [&point_buffer, &u_tangent_buffer, &v_tangent_buffer, &normal_buffer] {
+        frame_bind_info.push(BindInfo {
+            buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        });
+    }
+    let (frame_pipeline, frame_bind_group) = naga_compute_pipeline(device, &frame_source, &frame_bind_info);
+
+    let mut forms_bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: surface_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+    ];
+    for buffer in [&e_buffer, &f_buffer, &g_buffer, &gaussian_buffer, &mean_buffer] {
+        forms_bind_info.push(BindInfo {
+            buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        });
+    }
+    let (forms_pipeline, forms_bind_group) = naga_compute_pipeline(device, &forms_source, &forms_bind_info);
+
+    let operations = vec![
+        Operation {
+            bind_group: frame_bind_group,
+            pipeline: Rc::new(frame_pipeline),
+            dim: [1, 1, 1],
+        },
+        Operation {
+            bind_group: forms_bind_group,
+            pipeline: Rc::new(forms_pipeline),
+            dim: [1, 1, 1],
+        },
+    ];
+    let new_data = [
+        Data::Geom0D { buffer: point_buffer },
+        Data::Vector { buffer: u_tangent_buffer },
+        Data::Vector { buffer: v_tangent_buffer },
+        Data::Vector { buffer: normal_buffer },
+        Data::Scalar { buffer: e_buffer },
+        Data::Scalar { buffer: f_buffer },
+        Data::Scalar { buffer: g_buffer },
+        Data::Scalar { buffer: gaussian_buffer },
+        Data::Scalar { buffer: mean_buffer },
+    ];
+
+    Ok((new_data, operations))
+}
+
+// Returns the WGSL code of a compute_derivatives() function, which stores the position and the
+// first and second partial derivatives of the surface into private variables. Just like for curves,
+// the derivatives are analytic if the expressions of the surface are known, otherwise they are
+// estimated with finite differences on the grid of points of the surface.
+fn derivatives_wgsl_code(
+    globals: &Globals,
+    param_1: &Parameter,
+    param_2: &Parameter,
+    parameter_names: &[String; 2],
+    sample_values: &[String; 2],
+    expressions: Option<[String; 3]>,
+) -> Result<String, ProcessingError> {
+    let (name_1, name_2) = match (&param_1.name, &param_2.name) {
+        (Some(name_1), Some(name_2)) => (name_1, name_2),
+        _ => return Err(ProcessingError::IncorrectAttributes(" the surface parameters \n have no name: use a \n Name Parameters node ".into())),
+    };
+    let sanitized_names = [
+        Globals::sanitize_variable_name(&parameter_names[0])?,
+        Globals::sanitize_variable_name(&parameter_names[1])?,
+    ];
+    // the values are reordered to match the parameters of the surface,
+    // the derivatives are swapped back at the end if needed
+    let swapped = match (sanitized_names[0].as_str(), sanitized_names[1].as_str()) {
+        (first, second) if first == name_1 && second == name_2 => false,
+        (first, second) if first == name_2 && second == name_1 => true,
+        _ => return Err(ProcessingError::IncorrectAttributes(" the parameters used \n are not known ".into())),
+    };
+    let sanitized_value_1 = globals.sanitize_expression(&[], &sample_values[0])?;
+    let sanitized_value_2 = globals.sanitize_expression(&[], &sample_values[1])?;
+    let (value_a, value_b) = if swapped {
+        (sanitized_value_2, sanitized_value_1)
+    } else {
+        (sanitized_value_1, sanitized_value_2)
+    };
+
+    let body = if let Some(expressions) = expressions {
+        let (a, b) = (name_1.as_str(), name_2.as_str());
+        let partials: [&[&str]; 6] = [&[], &[a], &[b], &[a, a], &[a, b], &[b, b]];
+        let mut vectors = Vec::<String>::new();
+        for variables in partials {
+            let mut coords = Vec::<String>::new();
+            for expression in expressions.iter() {
+                coords.push(globals.sanitize_partial_derivative(&[a, b], expression, variables)?);
+            }
+            vectors.push(format!("vec3<f32>({})", coords.join(", ")));
+        }
+        format!(r##"
+    let {name_1} = {value_a};
+    let {name_2} = {value_b};
+    position = {p};
+    sa = {sa};
+    sb = {sb};
+    saa = {saa};
+    sab = {sab};
+    sbb = {sbb};
+"##, name_1=name_1, name_2=name_2, value_a=value_a, value_b=value_b,
+p=vectors[0], sa=vectors[1], sb=vectors[2], saa=vectors[3], sab=vectors[4], sbb=vectors[5],
+        )
+    } else {
+        let mut interpolated = String::new();
+        for derivative in ["a", "b", "aa", "ab", "bb"] {
+            interpolated += &format!(
+                "    s{d} = mix(mix(fd_{d}(i, j, ha, hb), fd_{d}(i + 1, j, ha, hb), alpha_a), mix(fd_{d}(i, j + 1, ha, hb), fd_{d}(i + 1, j + 1, ha, hb), alpha_a), alpha_b);\n",
+                d=derivative);
+        }
+        format!(r##"
+    let size_a = {size_a};
+    let size_b = {size_b};
+    let begin_a: f32 = {begin_a};
+    let end_a: f32 = {end_a};
+    let begin_b: f32 = {begin_b};
+    let end_b: f32 = {end_b};
+    let ha = (end_a - begin_a) / f32(size_a - 1);
+    let hb = (end_b - begin_b) / f32(size_b - 1);
+    let value_a = ({value_a} - begin_a) / ha;
+    let value_b = ({value_b} - begin_b) / hb;
+    // the position is interpolated bilinearly between the four closest points
+    let inf_a = i32(clamp(floor(value_a), 0.0, f32(size_a - 1)));
+    let sup_a = i32(clamp(ceil(value_a), 0.0, f32(size_a - 1)));
+    let inf_b = i32(clamp(floor(value_b), 0.0, f32(size_b - 1)));
+    let sup_b = i32(clamp(ceil(value_b), 0.0, f32(size_b - 1)));
+    position = mix(
+        mix(point_at(inf_a, inf_b), point_at(sup_a, inf_b), fract(value_a)),
+        mix(point_at(inf_a, sup_b), point_at(sup_a, sup_b), fract(value_a)),
+        fract(value_b));
+    // central differences need a point on each side, so the derivatives
+    // on the border of the surface are the ones of the nearest valid point
+    let i = clamp(i32(floor(value_a)), 1, size_a - 3);
+    let j = clamp(i32(floor(value_b)), 1, size_b - 3);
+    let alpha_a = clamp(value_a - f32(i), 0.0, 1.0);
+    let alpha_b = clamp(value_b - f32(j), 0.0, 1.0);
+{interpolated}"##, size_a=param_1.n_points(), size_b=param_2.n_points(),
+begin_a=&param_1.begin, end_a=&param_1.end, begin_b=&param_2.begin, end_b=&param_2.end,
+value_a=value_a, value_b=value_b, interpolated=interpolated,
+        )
+    };
+
+    let (su, sv, suu, svv) = if swapped {
+        ("sb", "sa", "sbb", "saa")
+    } else {
+        ("sa", "sb", "saa", "sbb")
+    };
+
+    Ok(format!(r##"
+var<private> position: vec3<f32>;
+var<private> sa: vec3<f32>;
+var<private> sb: vec3<f32>;
+var<private> saa: vec3<f32>;
+var<private> sab: vec3<f32>;
+var<private> sbb: vec3<f32>;
+var<private> su: vec3<f32>;
+var<private> sv: vec3<f32>;
+var<private> suu: vec3<f32>;
+var<private> suv: vec3<f32>;
+var<private> svv: vec3<f32>;
+
+fn point_at(i: i32, j: i32) -> vec3<f32> {{
+    return in_surface.positions[i + {size_a} * j].xyz;
+}}
+
+fn fd_a(i: i32, j: i32, ha: f32, hb: f32) -> vec3<f32> {{
+    return (point_at(i + 1, j) - point_at(i - 1, j)) / (2.0 * ha);
+}}
+
+fn fd_b(i: i32, j: i32, ha: f32, hb: f32) -> vec3<f32> {{
+    return (point_at(i, j + 1) - point_at(i, j - 1)) / (2.0 * hb);
+}}
+
+fn fd_aa(i: i32, j: i32, ha: f32, hb: f32) -> vec3<f32> {{
+    return (point_at(i + 1, j) - 2.0 * point_at(i, j) + point_at(i - 1, j)) / (ha * ha);
+}}
+
+fn fd_ab(i: i32, j: i32, ha: f32, hb: f32) -> vec3<f32> {{
+    return (point_at(i + 1, j + 1) - point_at(i + 1, j - 1) - point_at(i - 1, j + 1) + point_at(i - 1, j - 1)) / (4.0 * ha * hb);
+}}
+
+fn fd_bb(i: i32, j: i32, ha: f32, hb: f32) -> vec3<f32> {{
+    return (point_at(i, j + 1) - 2.0 * point_at(i, j) + point_at(i, j - 1)) / (hb * hb);
+}}
+
+fn compute_derivatives() {{{body}
+    su = {su};
+    sv = {sv};
+    suu = {suu};
+    suv = sab;
+    svv = {svv};
+}}
+"##, size_a=param_1.n_points(), body=body, su=su, sv=sv, suu=suu, svv=svv))
+}
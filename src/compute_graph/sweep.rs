@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    path_id: Option<DataID>,
+    profile_id: Option<DataID>,
+    radius: String,
+    quality: usize,
+) -> SingleDataResult {
+    if !(1..=16).contains(&quality) {
+        return Err(ProcessingError::IncorrectAttributes(" Sweep quality \n must be an integer \n in the [1, 16] range ".into()));
+    }
+    if radius.is_empty() {
+        return Err(ProcessingError::IncorrectAttributes(" please provide an expression \n for the radius ".into()));
+    }
+
+    let data_id = path_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Sweep node \n is missing its path input ".into()))?;
+    let (path_buffer, path_param) = match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom1D { buffer, param } => (buffer, param),
+        _ => return Err(ProcessingError::IncorrectInput(" Sweep path \n is not a curve ".into()))
+    };
+
+    // the profile is optional: if it is missing we sweep a circle, whose parameter is the angle
+    let profile = match profile_id {
+        Some(data_id) => match data_map.get(&data_id).ok_or(ProcessingError::NoInputData)? {
+            Data::Geom1D { buffer, param } => Some((buffer, param.clone())),
+            _ => return Err(ProcessingError::IncorrectInput(" Sweep profile \n is not a curve ".into()))
+        },
+        None => None,
+    };
+    let profile_param = match &profile {
+        Some((_, param)) => param.clone(),
+        None => Parameter {
+            name: None,
+            begin: "0.0".into(),
+            end: format!("{:?}", 2.0 * std::f32::consts::PI),
+            segments: quality as u32,
+            use_interval_as_uv: false,
+        },
+    };
+
+    // the radius can vary along the path, but it can depend on the path parameter only if
+    // the parameter has a name, otherwise it must be a constant expression
+    let (path_param_name, sanitized_radius) = match &path_param.name {
+        Some(name) => (name.clone(), globals.sanitize_expression(&[name.as_str()], &radius)?),
+        None => match globals.sanitize_expression(&[], &radius) {
+            Ok(sanitized_radius) => (String::from("_path_param"), sanitized_radius),
+            // an unknown variable is most likely the path parameter, any other error is reported as it is
+            Err(ProcessingError::IncorrectExpression(message)) if message.starts_with("Unknown variable") => {
+                return Err(ProcessingError::IncorrectAttributes(" the path parameter has no name: \n use a Name Parameters node ".into()));
+            },
+            Err(error) => return Err(error),
+        },
+    };
+
+    let profile_code = if profile.is_some() {
+        "let profile_point = in_profile.positions[j].xyz;"
+    } else {
+        "let angle = f32(j) / f32(profile_size - 1u) * 2.0 * 3.14159265;\n        let profile_point = vec3<f32>(cos(angle), sin(angle), 0.0);"
+    };
+    let profile_binding = if profile.is_some() {
+        "[[group(0), binding(3)]] var<storage, read> in_profile: CurveBuffer;"
+    } else {
+        ""
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct CurveBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct SurfaceBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_path: CurveBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: SurfaceBuffer;
+{profile_binding}
+
+var<workgroup> tangent_buff: array<vec3<f32>, {path_size}>;
+var<workgroup> spine_buff: array<vec3<f32>, {path_size}>;
+
+// TODO: DRY: the following functions are taken from the curve rendering code in geometry_render.rs
+fn compute_tangent(idx: i32, size_x: i32) -> vec3<f32> {{
+    var tangent: vec3<f32>;
+    if (idx == 0) {{
+        tangent = (-1.5*in_path.positions[idx] + 2.0*in_path.positions[idx + 1] - 0.5*in_path.positions[idx + 2]).xyz;
+    }} else if (idx == size_x - 1) {{
+        tangent = ( 1.5*in_path.positions[idx] - 2.0*in_path.positions[idx - 1] + 0.5*in_path.positions[idx - 2]).xyz;
+    }} else {{
+        tangent = (-0.5*in_path.positions[idx - 1] + 0.5*in_path.positions[idx+1]).xyz;
+    }}
+
+    return normalize(tangent);
+}}
+
+fn compute_default_spine(tangent: vec3<f32>) -> vec3<f32> {{
+    if (abs(tangent.x) > 0.333) {{
+        return normalize(cross(tangent, vec3<f32>(0.0, 1.0, 0.0)));
+    }} else {{
+        return normalize(cross(tangent, vec3<f32>(1.0, 0.0, 0.0)));
+    }}
+}}
+
+// computes the next spine vector using the double reflection method. See
+// "Computation of Rotation Minimizing Frame in Computer Graphics", 2007, Wenping Wang
+fn compute_next_spine_dr(x_i: vec3<f32>, spine_i: vec3<f32>, t_i: vec3<f32>, x_j: vec3<f32>, t_j: vec3<f32>) -> vec3<f32> {{
+    let v_1: vec3<f32> = x_j - x_i;
+    let c_1: f32 = dot(v_1, v_1);
+    if (c_1 < 1e-12) {{
+        // the two points are coincident, there is nothing to reflect
+        return spine_i;
+    }}
+    let spine_i_L = spine_i - (2.0/c_1) * dot(v_1, spine_i) * v_1;
+    let t_i_L = t_i - (2.0/c_1) * dot(v_1, t_i) * v_1;
+    let v_2: vec3<f32> = t_j - t_i_L;
+    let c_2: f32 = dot(v_2, v_2);
+    if (c_2 < 1e-12) {{
+        return normalize(spine_i_L);
+    }}
+    let spine_j = spine_i_L - (2.0/c_2) * dot(v_2, spine_i_L) * v_2;
+    return normalize(spine_j);
+}}
+
+[[stage(compute), workgroup_size({path_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let path_size = {path_size}u;
+    let profile_size = {profile_size}u;
+    let i = global_id.x;
+
+    tangent_buff[i] = compute_tangent(i32(i), i32(path_size));
+
+    workgroupBarrier();
+
+    // the rotation minimizing frame is inherently sequential, a single invocation computes it
+    if (i == 0u) {{
+        spine_buff[0] = compute_default_spine(tangent_buff[0]);
+        for (var k = 1u; k < path_size; k = k + 1u) {{
+            spine_buff[k] = compute_next_spine_dr(
+                in_path.positions[k - 1u].xyz, spine_buff[k - 1u], tangent_buff[k - 1u],
+                in_path.positions[k].xyz, tangent_buff[k]
+            );
+        }}
+    }}
+
+    workgroupBarrier();
+
+    let tangent = tangent_buff[i];
+    let normal = spine_buff[i];
+    let binormal = cross(tangent, normal);
+    let center = in_path.positions[i].xyz;
+
+    let {path_param_name} = {path_begin} + ({path_end} - {path_begin}) * f32(i) / f32(path_size - 1u);
+    let radius = {radius};
+
+    for (var j = 0u; j < profile_size; j = j + 1u) {{
+        {profile_code}
+        // the profile lies on the normal plane of the path: its x and y coordinates are taken
+        // along the normal and binormal directions, while z goes along the tangent.
+        let offset = profile_point.x * normal + profile_point.y * binormal + profile_point.z * tangent;
+        output.positions[i + path_size * j] = vec4<f32>(center + radius * offset, 1.0);
+    }}
+}}
+"##, wgsl_header=globals.get_wgsl_header(), profile_binding=profile_binding,
+path_size=path_param.n_points(), profile_size=profile_param.n_points(),
+path_param_name=path_param_name, path_begin=&path_param.begin, path_end=&path_param.end,
+radius=sanitized_radius, profile_code=profile_code,
+);
+
+    //println!("sweep shader source:\n {}", &wgsl_source);
+
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * path_param.n_points() * profile_param.n_points());
+
+    let mut bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: path_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    if let Some((profile_buffer, _)) = &profile {
+        bind_info.push(BindInfo {
+            buffer: profile_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        });
+    }
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+    let new_data = Data::Geom2D {
+        buffer: output_buffer,
+        param1: path_param.clone(),
+        param2: profile_param,
+    };
+
+    Ok((new_data, operation))
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::ProcessingError;
+use super::Parameter;
+use super::{DataID, Data, NodeID};
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub type TrailResult = Result<(Data, Trail), ProcessingError>;
+
+// The trail of a point is computed by running all the operations the point depends on
+// once for each value of a global variable, and by copying the point after each run.
+// This cannot be done by a single compute operation: the global variables are written
+// by copying a whole set of them into the globals buffer before each run.
+pub struct Trail {
+    pub point_id: DataID,
+    pub output_id: DataID,
+    // the nodes the point depends on, in the order in which they are processed
+    pub ancestors: Vec<NodeID>,
+    samples: usize,
+    globals_size: wgpu::BufferAddress,
+    // one set of globals for each sample, plus a copy of the current values to restore them at the end
+    globals_table: wgpu::Buffer,
+    table_operation: Operation,
+}
+
+impl Trail {
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder, globals_buffer: &wgpu::Buffer, point_buffer: &wgpu::Buffer,
+        output_buffer: &wgpu::Buffer, encode_ancestors: &dyn Fn(&mut wgpu::CommandEncoder)) {
+        let point_size = std::mem::size_of::<glam::Vec4>() as wgpu::BufferAddress;
+        // fill the table with the current values, then overwrite the swept variable
+        for i in 0 ..= self.samples as wgpu::BufferAddress {
+            encoder.copy_buffer_to_buffer(globals_buffer, 0, &self.globals_table, i * self.globals_size, self.globals_size);
+        }
+        self.table_operation.encode(encoder);
+        for i in 0 .. self.samples as wgpu::BufferAddress {
+            encoder.copy_buffer_to_buffer(&self.globals_table, i * self.globals_size, globals_buffer, 0, self.globals_size);
+            encode_ancestors(encoder);
+            encoder.copy_buffer_to_buffer(point_buffer, 0, output_buffer, i * point_size, point_size);
+        }
+        // restore the globals, and run the operations again so that the other nodes
+        // that depend on them see the current values
+        encoder.copy_buffer_to_buffer(&self.globals_table, self.samples as wgpu::BufferAddress * self.globals_size, globals_buffer, 0, self.globals_size);
+        encode_ancestors(encoder);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    point_id: Option<DataID>,
+    output_id: DataID,
+    ancestors: Vec<NodeID>,
+    variable_name: String,
+    begin: String,
+    end: String,
+    quality: usize,
+) -> TrailResult {
+    let point_id = point_id
+        .ok_or_else(|| ProcessingError::InputMissing(" This Trajectory node \n is missing its Point input ".into()))?;
+    match data_map.get(&point_id).ok_or(ProcessingError::NoInputData)? {
+        Data::Geom0D { .. } => {},
+        _ => return Err(ProcessingError::IncorrectInput(" the input geometry \n is not a point ".into())),
+    }
+    let sanitized_name = Globals::sanitize_variable_name(&variable_name)?;
+    let variable_index = globals.get_variable_index(&sanitized_name)
+        .ok_or_else(|| ProcessingError::IncorrectAttributes(" the variable used \n is not a global variable ".into()))?;
+    let sanitized_begin = globals.sanitize_expression(&[], &begin)?;
+    let sanitized_end = globals.sanitize_expression(&[], &end)?;
+
+    // the curve parameter is anonymous, like the one of a Bezier curve
+    let param = Parameter {
+        name: None,
+        segments: quality as u32,
+        begin: "0.0".into(),
+        end: "1.0".into(),
+        use_interval_as_uv: false,
+    };
+    let samples = param.n_points();
+    let values_count = globals.get_values_count();
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct TableBuffer {{
+    values: array<f32>;
+}};
+
+[[group(0), binding(1)]] var<storage, read_write> out_table: TableBuffer;
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let idx = global_id.x;
+    let begin: f32 = {begin};
+    let end: f32 = {end};
+    out_table.values[idx * {values_count}u + {variable_index}u] = begin + (end - begin) * f32(idx) / f32({samples}u - 1u);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), begin=sanitized_begin, end=sanitized_end, values_count=values_count,
+    variable_index=variable_index, samples=samples, pps=Parameter::POINTS_PER_SEGMENT,
+);
+
+    //println!("trail globals table shader source:\n {}", &wgsl_source);
+
+    let globals_size = (values_count * std::mem::size_of::<f32>()) as wgpu::BufferAddress;
+    let globals_table = create_copy_buffer(device, (samples + 1) * values_count * std::mem::size_of::<f32>());
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &globals_table,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+    let table_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param.segments, 1, 1],
+    };
+
+    let output_buffer = create_copy_buffer(device, samples * std::mem::size_of::<glam::Vec4>());
+    let trail = Trail {
+        point_id,
+        output_id,
+        ancestors,
+        samples,
+        globals_size,
+        globals_table,
+        table_operation,
+    };
+    let new_data = Data::Geom1D {
+        buffer: output_buffer,
+        param,
+    };
+
+    Ok((new_data, trail))
+}
+
+// unlike the usual storage buffers, the trail buffers are also written by copies
+fn create_copy_buffer(device: &wgpu::Device, buffer_size: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        mapped_at_creation: false,
+        size: buffer_size as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::MAP_READ,
+    })
+}
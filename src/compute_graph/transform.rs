@@ -56,8 +56,51 @@ pub fn create(
             if param1.is_equal(matrix_param)? || param2.is_equal(matrix_param)?
                 => t_2d_same_param(device, buffer, param1, param2, matrix_buffer, matrix_param),
 
-        (Data::Geom2D{..}, Data::Matrix1D{..})
-            => Err(ProcessingError::IncorrectInput(" this operation would create \n an object with three parameters, \n which is not supported ".into())),
+        (Data::Geom2D{buffer, param1, param2}, Data::Matrix1D{buffer: matrix_buffer, param: matrix_param})
+            => t_2d_up_3d(device, buffer, param1, param2, matrix_buffer, matrix_param),
+
+        (Data::Geom3D{buffer, param1, param2, param3}, Data::Matrix0D{buffer: matrix_buffer})
+            => t_3d_3d(device, buffer, param1, param2, param3, matrix_buffer),
+
+        (Data::Geom3D{buffer, param1, param2, param3}, Data::Matrix1D{buffer: matrix_buffer, param: matrix_param})
+            if param1.is_equal(matrix_param)? || param2.is_equal(matrix_param)? || param3.is_equal(matrix_param)?
+                => t_3d_same_param(device, buffer, param1, param2, param3, matrix_buffer, matrix_param),
+
+        (Data::Geom3D{..}, Data::Matrix1D{..})
+            => Err(ProcessingError::IncorrectInput(" this operation would create \n an object with four parameters, \n which is not supported ".into())),
+
+        // point sets are stored like curves, but the points of the output must stay disconnected
+        (Data::PointSet{buffer, param}, Data::Matrix0D{buffer: matrix_buffer})
+            => t_1d_1d(device, buffer, param, matrix_buffer).map(into_point_set),
+
+        (Data::PointSet{buffer, param}, Data::Matrix1D{buffer: matrix_buffer, param: matrix_param})
+            if param.is_equal(matrix_param)?
+                => t_1d_same_param(device, buffer, matrix_buffer, matrix_param).map(into_point_set),
+
+        (Data::PointSet { .. }, Data::Matrix1D { .. })
+            => Err(ProcessingError::IncorrectInput(" a point set can only be transformed \n by a matrix with the same parameter ".into())),
+
+        // the curves of a family are stored one after the other, they are transformed as a single long curve
+        (Data::CurveFamily { buffer, param, curves_count, sampled_name, sampled_values }, Data::Matrix0D { buffer: matrix_buffer })
+            => {
+                let all_points = Parameter {
+                    segments: param.segments * *curves_count as u32,
+                    ..param.clone()
+                };
+                t_1d_1d(device, buffer, &all_points, matrix_buffer).map(|(data, operation)| match data {
+                    Data::Geom1D { buffer, .. } => (Data::CurveFamily {
+                        buffer,
+                        param: param.clone(),
+                        curves_count: *curves_count,
+                        sampled_name: sampled_name.clone(),
+                        sampled_values: sampled_values.clone(),
+                    }, operation),
+                    _ => (data, operation),
+                })
+            },
+
+        (Data::CurveFamily { .. }, Data::Matrix1D { .. })
+            => Err(ProcessingError::IncorrectInput(" parametric transforms on families \n are not allowed ".into())),
 
         (Data::Prefab { vertex_buffer, chunks_count, index_buffer, index_count }, Data::Matrix0D { buffer: matrix_buffer })
             => t_prefab(device, vertex_buffer, *chunks_count, index_buffer, *index_count, matrix_buffer),
@@ -65,11 +108,24 @@ pub fn create(
         (Data::Prefab { .. }, Data::Matrix1D { .. })
             => Err(ProcessingError::IncorrectInput(" parametric transforms on primitives \n are not allowed ".into())),
 
+        (Data::Segments { buffer, segments_count }, Data::Matrix0D { buffer: matrix_buffer })
+            => t_segments(device, buffer, *segments_count, matrix_buffer),
+
+        (Data::Segments { .. }, Data::Matrix1D { .. })
+            => Err(ProcessingError::IncorrectInput(" parametric transforms on implicit curves \n are not allowed ".into())),
+
         _ => Err(ProcessingError::InternalError("unhandled transform case".into()))
     }
 
 }
 
+fn into_point_set((data, operation): (Data, Operation)) -> (Data, Operation) {
+    match data {
+        Data::Geom1D { buffer, param } => (Data::PointSet { buffer, param }, operation),
+        _ => (data, operation),
+    }
+}
+
 fn t_0d_0d(
     device: &wgpu::Device,
     geom_buffer: &wgpu::Buffer,
@@ -502,6 +558,219 @@ fn main(
 }
 
 
+fn t_2d_up_3d(
+    device: &wgpu::Device,
+    geom_buffer: &wgpu::Buffer,
+    geom_param1: &Parameter,
+    geom_param2: &Parameter,
+    matrix_buffer: &wgpu::Buffer,
+    matrix_param: &Parameter,
+    ) -> SingleDataResult {
+    let wgsl_source = r##"
+struct SurfaceBuffer {
+    positions: array<vec4<f32>>;
+};
+
+struct MatrixBuffer {
+    matrices: array<mat4x4<f32>>;
+};
+
+struct VolumeBuffer {
+    positions: array<vec4<f32>>;
+};
+
+[[group(0), binding(0)]] var<storage, read> in_surface: SurfaceBuffer;
+[[group(0), binding(1)]] var<storage, read> in_matrices: MatrixBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: VolumeBuffer;
+
+[[stage(compute), workgroup_size(16, 16)]]
+fn main(
+    [[builtin(global_invocation_id)]] global_id: vec3<u32>,
+    [[builtin(num_workgroups)]] num_groups: vec3<u32>,
+) {
+    let par1_idx = global_id.x;
+    let par2_idx = global_id.y;
+    let par3_idx = global_id.z;
+    let surface_index = par1_idx + num_groups.x * 16u * par2_idx;
+    let index = surface_index + num_groups.x * 16u * num_groups.y * 16u * par3_idx;
+    output.positions[index] = in_matrices.matrices[par3_idx] * in_surface.positions[surface_index];
+}
+"##.to_string();
+
+    super::volume::check_points_count(device, geom_param1.n_points() * geom_param2.n_points() * matrix_param.n_points())?;
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * geom_param1.n_points() * geom_param2.n_points() * matrix_param.n_points());
+    let bind_info = vec![
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: matrix_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [geom_param1.segments, geom_param2.segments, matrix_param.n_points() as u32],
+    };
+    let new_data = Data::Geom3D {
+        buffer: output_buffer,
+        param1: geom_param1.clone(),
+        param2: geom_param2.clone(),
+        param3: matrix_param.clone(),
+    };
+
+    Ok((new_data, operation))
+}
+
+fn t_3d_3d(
+    device: &wgpu::Device,
+    geom_buffer: &wgpu::Buffer,
+    geom_param1: &Parameter,
+    geom_param2: &Parameter,
+    geom_param3: &Parameter,
+    matrix_buffer: &wgpu::Buffer,
+    ) -> SingleDataResult {
+    let wgsl_source = r##"
+struct VolumeBuffer {
+    positions: array<vec4<f32>>;
+};
+
+struct MatrixBuffer {
+    matrix: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]] var<storage, read> in_volume: VolumeBuffer;
+[[group(0), binding(1)]] var<storage, read> in_matrix: MatrixBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: VolumeBuffer;
+
+[[stage(compute), workgroup_size(16, 16)]]
+fn main(
+    [[builtin(global_invocation_id)]] global_id: vec3<u32>,
+    [[builtin(num_workgroups)]] num_groups: vec3<u32>,
+) {
+    let size_x = num_groups.x * 16u;
+    let size_y = num_groups.y * 16u;
+    let index = global_id.x + size_x * (global_id.y + size_y * global_id.z);
+    output.positions[index] = in_matrix.matrix * in_volume.positions[index];
+}
+"##.to_string();
+
+    super::volume::check_points_count(device, geom_param1.n_points() * geom_param2.n_points() * geom_param3.n_points())?;
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * geom_param1.n_points() * geom_param2.n_points() * geom_param3.n_points());
+    let bind_info = vec![
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: matrix_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [geom_param1.segments, geom_param2.segments, geom_param3.n_points() as u32],
+    };
+    let new_data = Data::Geom3D {
+        buffer: output_buffer,
+        param1: geom_param1.clone(),
+        param2: geom_param2.clone(),
+        param3: geom_param3.clone(),
+    };
+
+    Ok((new_data, operation))
+}
+
+fn t_3d_same_param(
+    device: &wgpu::Device,
+    geom_buffer: &wgpu::Buffer,
+    geom_param1: &Parameter,
+    geom_param2: &Parameter,
+    geom_param3: &Parameter,
+    matrix_buffer: &wgpu::Buffer,
+    matrix_param: &Parameter,
+    ) -> SingleDataResult {
+
+    let which_idx = if geom_param1.is_equal(matrix_param)? {
+        "global_id.x"
+    } else if geom_param2.is_equal(matrix_param)? {
+        "global_id.y"
+    } else {
+        "global_id.z"
+    };
+    let wgsl_source = r##"
+struct VolumeBuffer {
+    positions: array<vec4<f32>>;
+};
+
+struct MatrixBuffer {
+    matrices: array<mat4x4<f32>>;
+};
+
+[[group(0), binding(0)]] var<storage, read> in_volume: VolumeBuffer;
+[[group(0), binding(1)]] var<storage, read> in_matrices: MatrixBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: VolumeBuffer;
+
+[[stage(compute), workgroup_size(16, 16)]]
+fn main(
+    [[builtin(global_invocation_id)]] global_id: vec3<u32>,
+    [[builtin(num_workgroups)]] num_groups: vec3<u32>,
+) {
+    let size_x = num_groups.x * 16u;
+    let size_y = num_groups.y * 16u;
+    let index = global_id.x + size_x * (global_id.y + size_y * global_id.z);
+    output.positions[index] = in_matrices.matrices["##.to_string() + which_idx + r##"] * in_volume.positions[index];
+}
+"##;
+
+    super::volume::check_points_count(device, geom_param1.n_points() * geom_param2.n_points() * geom_param3.n_points())?;
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * geom_param1.n_points() * geom_param2.n_points() * geom_param3.n_points());
+    let bind_info = vec![
+        BindInfo {
+            buffer: geom_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: matrix_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [geom_param1.segments, geom_param2.segments, geom_param3.n_points() as u32],
+    };
+    let new_data = Data::Geom3D {
+        buffer: output_buffer,
+        param1: geom_param1.clone(),
+        param2: geom_param2.clone(),
+        param3: geom_param3.clone(),
+    };
+
+    Ok((new_data, operation))
+}
+
 fn t_prefab (device: &wgpu::Device,
     vertex_buffer: &wgpu::Buffer,
     chunks_count: usize,
@@ -516,6 +785,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 struct PrefabBuffer {{
@@ -572,6 +842,7 @@ fn main(
     output.vertices[index].position = in_matrix.matrix * in_prefab.vertices[index].position;
     output.vertices[index].uv_coords = in_prefab.vertices[index].uv_coords;
     output.vertices[index].padding = in_prefab.vertices[index].padding;
+    output.vertices[index].color = in_prefab.vertices[index].color;
 }}
 "##, vertices_per_chunk=MODEL_CHUNK_VERTICES,);
 
@@ -607,3 +878,78 @@ fn main(
     Ok((new_data, operation))
 }
 
+
+fn t_segments(
+    device: &wgpu::Device,
+    segments_buffer: &wgpu::Buffer,
+    segments_count: usize,
+    matrix_buffer: &wgpu::Buffer,
+    ) -> SingleDataResult {
+    const WORKGROUP_SIZE: usize = 64;
+    let n_points = 2 * segments_count;
+
+    let wgsl_source = format!(r##"
+struct SegmentPoint {{
+    position: vec4<f32>;
+    tangent: vec4<f32>;
+    up: vec4<f32>;
+}};
+
+struct SegmentBuffer {{
+    points: array<SegmentPoint>;
+}};
+
+struct MatrixBuffer {{
+    matrix: mat4x4<f32>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_segments: SegmentBuffer;
+[[group(0), binding(1)]] var<storage, read> in_matrix: MatrixBuffer;
+[[group(0), binding(2)]] var<storage, read_write> output: SegmentBuffer;
+
+[[stage(compute), workgroup_size({wg_size})]]
+fn main(
+    [[builtin(global_invocation_id)]] global_id: vec3<u32>,
+) {{
+    let index = global_id.x;
+    if (index >= {n_points}u) {{
+        return;
+    }}
+
+    // tangents and up directions are transformed as directions. The up direction
+    // might not be orthogonal to the tangent anymore, but the renderer takes care of that.
+    output.points[index].position = in_matrix.matrix * in_segments.points[index].position;
+    output.points[index].tangent = in_matrix.matrix * in_segments.points[index].tangent;
+    output.points[index].up = in_matrix.matrix * in_segments.points[index].up;
+}}
+"##, n_points=n_points, wg_size=WORKGROUP_SIZE,);
+
+    let output_buffer = util::create_storage_buffer(device, n_points * Data::SEGMENT_POINT_SIZE);
+    let bind_info = vec![
+        BindInfo {
+            buffer: segments_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: matrix_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [((n_points - 1) / WORKGROUP_SIZE + 1) as u32, 1, 1],
+    };
+    let new_data = Data::Segments {
+        buffer: output_buffer,
+        segments_count,
+    };
+
+    Ok((new_data, operation))
+}
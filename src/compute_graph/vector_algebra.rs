@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub enum VectorOperation {
+    FromPoints,
+    TranslatePoint,
+    Sum,
+    Difference,
+    Scale { factor: String },
+    Dot,
+    Cross,
+    Normalize,
+    Length,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum OperandKind {
+    Point,
+    Vector,
+    Scalar,
+}
+
+impl VectorOperation {
+    fn node_name(&self) -> &'static str {
+        match self {
+            VectorOperation::FromPoints => "Vector from Points",
+            VectorOperation::TranslatePoint => "Translate Point",
+            VectorOperation::Sum => "Vector Sum",
+            VectorOperation::Difference => "Vector Difference",
+            VectorOperation::Scale { .. } => "Scale Vector",
+            VectorOperation::Dot => "Dot Product",
+            VectorOperation::Cross => "Cross Product",
+            VectorOperation::Normalize => "Normalize",
+            VectorOperation::Length => "Length",
+        }
+    }
+
+    fn operand_kinds(&self) -> Vec<OperandKind> {
+        match self {
+            VectorOperation::FromPoints => vec![OperandKind::Point, OperandKind::Point],
+            VectorOperation::TranslatePoint => vec![OperandKind::Point, OperandKind::Vector],
+            VectorOperation::Scale { .. } => vec![OperandKind::Vector, OperandKind::Scalar],
+            VectorOperation::Normalize | VectorOperation::Length => vec![OperandKind::Vector],
+            _ => vec![OperandKind::Vector, OperandKind::Vector],
+        }
+    }
+
+    // the scalar input of the Scale Vector node can be left unconnected, only the factor is used in that case
+    fn is_optional(&self, operand_idx: usize) -> bool {
+        matches!(self, VectorOperation::Scale { .. }) && operand_idx == 1
+    }
+
+    fn output_kind(&self) -> OperandKind {
+        match self {
+            VectorOperation::TranslatePoint => OperandKind::Point,
+            VectorOperation::Dot | VectorOperation::Length => OperandKind::Scalar,
+            _ => OperandKind::Vector,
+        }
+    }
+
+    // the WGSL expression of the result, given the operands `first` and `second`
+    fn wgsl_expression(&self, factor: &str) -> String {
+        match self {
+            VectorOperation::FromPoints => "vec4<f32>(second.xyz - first.xyz, 0.0)".into(),
+            VectorOperation::TranslatePoint => "vec4<f32>(first.xyz + second.xyz, 1.0)".into(),
+            VectorOperation::Sum => "vec4<f32>(first.xyz + second.xyz, 0.0)".into(),
+            VectorOperation::Difference => "vec4<f32>(first.xyz - second.xyz, 0.0)".into(),
+            VectorOperation::Scale { .. } => format!("vec4<f32>(({}) * second * first.xyz, 0.0)", factor),
+            VectorOperation::Dot => "dot(first.xyz, second.xyz)".into(),
+            VectorOperation::Cross => "vec4<f32>(cross(first.xyz, second.xyz), 0.0)".into(),
+            // the null vector has no direction, and it is left as it is
+            VectorOperation::Normalize => "select(vec4<f32>(0.0, 0.0, 0.0, 0.0), vec4<f32>(normalize(first.xyz), 0.0), length(first.xyz) > 1e-6)".into(),
+            VectorOperation::Length => "length(first.xyz)".into(),
+        }
+    }
+}
+
+// Computes the result of an operation on points and vectors. If any of the inputs is a family
+// (a curve or a 1D family of vectors), the operation is applied to each of its elements
+// and the result is a family too.
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    operation: VectorOperation,
+    inputs: [Option<DataID>; 2],
+) -> SingleDataResult {
+    let node_name = operation.node_name();
+    let operand_kinds = operation.operand_kinds();
+
+    let mut operands = Vec::<(&wgpu::Buffer, Option<&Parameter>, OperandKind)>::new();
+    for (i, kind) in operand_kinds.iter().enumerate() {
+        let ordinal = if i == 0 { "first" } else { "second" };
+        if inputs[i].is_none() && operation.is_optional(i) {
+            continue;
+        }
+        let data_id = inputs[i]
+            .ok_or_else(|| ProcessingError::InputMissing(format!(" This {} node \n is missing its {} input ", node_name, ordinal)))?;
+        let found_data = data_map
+            .get(&data_id)
+            .ok_or(ProcessingError::NoInputData)?;
+        let (buffer, param) = match (kind, found_data) {
+            (OperandKind::Point, Data::Geom0D { buffer }) => (buffer, None),
+            (OperandKind::Point, Data::Geom1D { buffer, param }) => (buffer, Some(param)),
+            (OperandKind::Vector, Data::Vector { buffer }) => (buffer, None),
+            (OperandKind::Vector, Data::Vector1D { buffer, param }) => (buffer, Some(param)),
+            (OperandKind::Scalar, Data::Scalar { buffer }) => (buffer, None),
+            (OperandKind::Scalar, Data::Scalar1D { buffer, param }) => (buffer, Some(param)),
+            (OperandKind::Point, _) => return Err(ProcessingError::IncorrectInput(format!(" {} {} input \n is not a point or a curve ", node_name, ordinal))),
+            (OperandKind::Vector, _) => return Err(ProcessingError::IncorrectInput(format!(" {} {} input \n is not a vector ", node_name, ordinal))),
+            (OperandKind::Scalar, _) => return Err(ProcessingError::IncorrectInput(format!(" {} {} input \n is not a scalar ", node_name, ordinal))),
+        };
+        operands.push((buffer, param, *kind));
+    }
+
+    // elements of two families are combined by index, so they must share the same parameter.
+    // Anonymous parameters cannot be compared by name, in that case we just require the same number of elements.
+    let mut family_param: Option<&Parameter> = None;
+    for param in operands.iter().filter_map(|(_, param, _)| *param) {
+        if let Some(other) = family_param {
+            let same_param = match (&param.name, &other.name) {
+                (Some(_), Some(_)) => param.is_equal(other)?,
+                _ => param.segments == other.segments,
+            };
+            if !same_param {
+                return Err(ProcessingError::IncorrectInput(" the two families \n must share the same parameter ".into()));
+            }
+        } else {
+            family_param = Some(param);
+        }
+    }
+
+    // the scale factor can depend on the parameter of the family, e.g. to scale the normal of a curve by t
+    let local_params: Vec<&str> = family_param.iter().filter_map(|param| param.name.as_deref()).collect();
+    let factor = match &operation {
+        VectorOperation::Scale { factor } => globals.sanitize_expression(&local_params, factor)?,
+        _ => String::new(),
+    };
+    let parameter_value = match family_param.and_then(|param| param.name.as_ref().map(|name| (name, param))) {
+        Some((name, param)) => format!(
+            "let {name} = {begin} + ({end} - {begin}) * f32(index) / f32({n_points}u - 1u);",
+            name=name, begin=param.begin, end=param.end, n_points=param.n_points()),
+        None => String::new(),
+    };
+
+    let mut bindings = String::new();
+    let mut operand_values = String::new();
+    for (i, (_, param, kind)) in operands.iter().enumerate() {
+        let name = if i == 0 { "first" } else { "second" };
+        let buffer_type = if *kind == OperandKind::Scalar { "ScalarInputBuffer" } else { "InputBuffer" };
+        bindings += &format!("[[group(0), binding({binding})]] var<storage, read> in_{name}: {buffer_type};\n", binding=i + 1, name=name, buffer_type=buffer_type);
+        // single points and vectors are used as they are by all the elements of the family
+        let idx = if param.is_some() { "index" } else { "0u" };
+        operand_values += &format!("    let {name} = in_{name}.values[{idx}];\n", name=name, idx=idx);
+    }
+    if operands.len() < operand_kinds.len() {
+        operand_values += "    let second = 1.0;\n";
+    }
+
+    let output_kind = operation.output_kind();
+    let output_type = if output_kind == OperandKind::Scalar { "f32" } else { "vec4<f32>" };
+    let (n_elements, workgroup_size, dim) = match family_param {
+        Some(param) => (param.n_points(), Parameter::POINTS_PER_SEGMENT, param.segments),
+        None => (1, 1, 1),
+    };
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct InputBuffer {{
+    values: array<vec4<f32>>;
+}};
+
+struct ScalarInputBuffer {{
+    values: array<f32>;
+}};
+
+struct OutputBuffer {{
+    values: array<{output_type}>;
+}};
+
+{bindings}[[group(0), binding({output_binding})]] var<storage, read_write> output: OutputBuffer;
+
+[[stage(compute), workgroup_size({workgroup_size})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.x;
+    {parameter_value}
+{operand_values}    output.values[index] = {expression};
+}}
+"##, wgsl_header=globals.get_wgsl_header(), output_type=output_type, bindings=bindings,
+output_binding=operands.len() + 1, workgroup_size=workgroup_size, parameter_value=parameter_value,
+operand_values=operand_values, expression=operation.wgsl_expression(&factor),
+);
+
+    //println!("vector algebra shader source:\n {}", &wgsl_source);
+
+    let element_size = if output_kind == OperandKind::Scalar {
+        std::mem::size_of::<f32>()
+    } else {
+        std::mem::size_of::<glam::Vec4>()
+    };
+    let output_buffer = util::create_storage_buffer(device, element_size * n_elements);
+
+    let mut bind_info = vec![globals.get_bind_info()];
+    for (buffer, _, _) in operands.iter() {
+        bind_info.push(BindInfo {
+            buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        });
+    }
+    bind_info.push(BindInfo {
+        buffer: &output_buffer,
+        ty: wgpu::BufferBindingType::Storage { read_only: false },
+    });
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [dim, 1, 1],
+    };
+    let buffer = output_buffer;
+    let new_data = match (output_kind, family_param.cloned()) {
+        (OperandKind::Point, None) => Data::Geom0D { buffer },
+        (OperandKind::Point, Some(param)) => Data::Geom1D { buffer, param },
+        (OperandKind::Vector, None) => Data::Vector { buffer },
+        (OperandKind::Vector, Some(param)) => Data::Vector1D { buffer, param },
+        (OperandKind::Scalar, None) => Data::Scalar { buffer },
+        (OperandKind::Scalar, Some(param)) => Data::Scalar1D { buffer, param },
+    };
+
+    Ok((new_data, operation))
+}
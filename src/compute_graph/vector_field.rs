@@ -0,0 +1,278 @@
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{MatcapData, ProcessingError};
+use super::vector_render::create_arrow_buffers;
+use crate::node_graph::{AVAILABLE_SIZES, FieldGrid};
+use crate::rendering::InstanceData;
+use crate::rendering::model::MODEL_CHUNK_VERTICES;
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+pub type InstancedMatcapResult = Result<(MatcapData, Vec<Operation>), ProcessingError>;
+
+// Evaluates the field on a regular grid centered in the origin, and places an arrow on each
+// grid point. The same arrow mesh is drawn once per grid point, each instance
+// is scaled and colored according to the magnitude of the field.
+// The computation is split in three passes: evaluation of the field, search of the
+// maximum magnitude and computation of the transform and color of each arrow.
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    expressions: [String; 3],
+    size: String,
+    grid: FieldGrid,
+    points: usize,
+    scale: String,
+    thickness: usize,
+    material_id: usize,
+) -> InstancedMatcapResult {
+    let [fx, fy, fz] = expressions;
+    let sanitized_fx = globals.sanitize_expression(&["x", "y", "z"], &fx)?;
+    let sanitized_fy = globals.sanitize_expression(&["x", "y", "z"], &fy)?;
+    let sanitized_fz = globals.sanitize_expression(&["x", "y", "z"], &fz)?;
+    let sanitized_size = globals.sanitize_expression(&[], &size)?;
+    let sanitized_scale = globals.sanitize_expression(&[], &scale)?;
+
+    // planar grids have a single layer of points, the coordinate orthogonal to the plane is zero
+    let (n_layers, coordinates) = match grid {
+        FieldGrid::Space => (points, "c.x, c.y, c.z"),
+        FieldGrid::PlaneXY => (1, "c.x, c.y, 0.0"),
+        FieldGrid::PlaneXZ => (1, "c.x, 0.0, c.y"),
+        FieldGrid::PlaneYZ => (1, "0.0, c.x, c.y"),
+    };
+    let n_arrows = points * points * n_layers;
+
+    let field_source = format!(r##"
+{wgsl_header}
+
+struct FieldPoint {{
+    position: vec4<f32>;
+    value: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    field_points: array<FieldPoint>;
+}};
+
+[[group(0), binding(1)]] var<storage, read_write> out: OutputBuffer;
+
+[[stage(compute), workgroup_size(1)]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.z * {n}u * {n}u + global_id.y * {n}u + global_id.x;
+    let size: f32 = {size};
+    let c = -0.5 * size + size * vec3<f32>(global_id) / f32({n}u - 1u);
+    let position = vec3<f32>({coordinates});
+    let x = position.x;
+    let y = position.y;
+    let z = position.z;
+    out.field_points[index].position = vec4<f32>(position, 1.0);
+    out.field_points[index].value = vec4<f32>({fx}, {fy}, {fz}, 0.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), n=points, size=sanitized_size,
+coordinates=coordinates, fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz,
+);
+
+    //println!("vector field shader source:\n {}", &field_source);
+
+    let field_buffer = util::create_storage_buffer(device, 2 * std::mem::size_of::<glam::Vec4>() * n_arrows);
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &field_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &field_source, &bind_info);
+    let field_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [points as u32, points as u32, n_layers as u32],
+    };
+
+    // the maximum is searched by a single invocation, the number of grid points is small anyway
+    let max_source = format!(r##"
+struct FieldPoint {{
+    position: vec4<f32>;
+    value: vec4<f32>;
+}};
+
+struct InputBuffer {{
+    field_points: array<FieldPoint>;
+}};
+
+struct OutputBuffer {{
+    max_length: f32;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_field: InputBuffer;
+[[group(0), binding(1)]] var<storage, read_write> out: OutputBuffer;
+
+[[stage(compute), workgroup_size(1)]]
+fn main() {{
+    var max_length: f32 = 0.0;
+    for (var i: u32 = 0u; i < {n_arrows}u; i = i + 1u) {{
+        max_length = max(max_length, length(in_field.field_points[i].value.xyz));
+    }}
+    out.max_length = max_length;
+}}
+"##, n_arrows=n_arrows,
+);
+
+    //println!("vector field max shader source:\n {}", &max_source);
+
+    let max_buffer = util::create_storage_buffer(device, std::mem::size_of::<f32>());
+    let bind_info = vec![
+        BindInfo {
+            buffer: &field_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &max_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &max_source, &bind_info);
+    let max_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [1, 1, 1],
+    };
+
+    // the longest arrow is as long as the grid spacing multiplied by the scale factor.
+    // The rotation that brings the z axis onto the field direction is the same used by the Vector Rendering node.
+    let instances_source = format!(r##"
+{wgsl_header}
+
+struct FieldPoint {{
+    position: vec4<f32>;
+    value: vec4<f32>;
+}};
+
+struct InputBuffer {{
+    field_points: array<FieldPoint>;
+}};
+
+struct MaxBuffer {{
+    max_length: f32;
+}};
+
+struct Instance {{
+    transform: mat4x4<f32>;
+    color: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    instances: array<Instance>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> in_field: InputBuffer;
+[[group(0), binding(2)]] var<storage, read> in_max: MaxBuffer;
+[[group(0), binding(3)]] var<storage, read_write> out: OutputBuffer;
+
+// maps values in [0, 1] to colors going from blue to red
+fn colormap(t: f32) -> vec3<f32> {{
+    let r = 1.5 - abs(4.0 * t - 3.0);
+    let g = 1.5 - abs(4.0 * t - 2.0);
+    let b = 1.5 - abs(4.0 * t - 1.0);
+    return clamp(vec3<f32>(r, g, b), vec3<f32>(0.0, 0.0, 0.0), vec3<f32>(1.0, 1.0, 1.0));
+}}
+
+[[stage(compute), workgroup_size(1)]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.z * {n}u * {n}u + global_id.y * {n}u + global_id.x;
+    let field_point = in_field.field_points[index];
+    let size: f32 = {size};
+    let spacing = size / f32({n}u - 1u);
+    let max_length = in_max.max_length;
+    let field_length = length(field_point.value.xyz);
+
+    // null vectors have no direction, we hide their arrows by collapsing them onto a single point
+    if (max_length == 0.0 || field_length < 1e-6 * max_length) {{
+        out.instances[index].transform = mat4x4<f32>(
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            field_point.position,
+        );
+        out.instances[index].color = vec4<f32>(colormap(0.0), 1.0);
+        return;
+    }}
+
+    let relative_length = field_length / max_length;
+    let s_z = ({scale}) * spacing * relative_length;
+    let direction = field_point.value.xyz / field_length;
+
+    var angle_z: f32;
+    // workaround MacOS bug: atan2 seems to give the wrong result
+    if (direction.y == 0.0) {{
+        if (direction.x > 0.0) {{
+            angle_z = -0.5*3.14159265;
+        }} else {{
+            angle_z =  0.5*3.14159265;
+        }}
+    }} else {{
+        angle_z = -1.0 * atan2(direction.x, direction.y);
+    }}
+
+    let angle_x = -1.0 * acos(clamp(direction.z, -1.0, 1.0));
+    let cos_t = cos(angle_z);
+    let sin_t = sin(angle_z);
+    let cos_p = cos(angle_x);
+    let sin_p = sin(angle_x);
+
+    out.instances[index].transform = mat4x4<f32>(
+        vec4<f32>(         cos_t,          sin_t,   0.0, 0.0), // first column
+        vec4<f32>(-cos_p * sin_t,  cos_p * cos_t, sin_p, 0.0), // 2nd column
+        s_z * vec4<f32>( sin_p * sin_t, -sin_p * cos_t, cos_p, 0.0), // 3rd column, scaled by the arrow length
+        field_point.position, // 4th column
+    );
+    out.instances[index].color = vec4<f32>(colormap(relative_length), 1.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), n=points, size=sanitized_size, scale=sanitized_scale,
+);
+
+    //println!("vector field instances shader source:\n {}", &instances_source);
+
+    let instance_buffer = util::create_storage_buffer(device, std::mem::size_of::<InstanceData>() * n_arrows);
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: &field_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &max_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &instance_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &instances_source, &bind_info);
+    let instances_operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [points as u32, points as u32, n_layers as u32],
+    };
+
+    // the arrow mesh points upwards and has length 1.0, it is used as it is by all the instances
+    let radius = AVAILABLE_SIZES[thickness];
+    let n_circle_points = (thickness + 3)*2;
+    let (index_buffer, index_count, vertex_buffer, chunks_count) = create_arrow_buffers(device, radius, n_circle_points);
+
+    let renderable = MatcapData {
+        vertex_buffer,
+        vertex_count: (chunks_count * MODEL_CHUNK_VERTICES) as u32,
+        index_buffer: Rc::new(index_buffer),
+        index_count,
+        mask_id: 0,
+        material_id,
+        instance_buffer: Some(instance_buffer),
+        instance_count: n_arrows as u32,
+    };
+
+    Ok((renderable, vec![field_operation, max_operation, instances_operation]))
+}
@@ -3,10 +3,10 @@ use std::rc::Rc;
 
 use crate::rendering::model::MODEL_CHUNK_VERTICES;
 use super::Operation;
-use crate::rendering::StandardVertexData;
+use crate::rendering::{StandardVertexData, InstanceData};
 use crate::node_graph::AVAILABLE_SIZES;
 use super::{MatcapData, ProcessingError};
-use super::{DataID, Data};
+use super::{DataID, Data, Parameter};
 use crate::util;
 use crate::shader_processing::{naga_compute_pipeline, BindInfo};
 
@@ -26,17 +26,30 @@ pub fn create(
     let found_appl_point = data_map
         .get(&data_id)
         .ok_or(ProcessingError::NoInputData)?;
-    let appl_point_buffer = if let Data::Geom0D{ buffer } = found_appl_point {
-        buffer
-    } else {
-        return Err(ProcessingError::IncorrectInput(" the first input provided \n is not a point ".into()));
-    };
 
     let data_id = vector_id
         .ok_or_else(|| ProcessingError::InputMissing(" This Vector Rendering node \n is missing its second input ".into()))?;
     let found_vector = data_map
         .get(&data_id)
         .ok_or(ProcessingError::NoInputData)?;
+
+    // a family of vectors is drawn as one arrow for each point of a curve or of a point set
+    if let Data::Vector1D{ buffer: vectors_buffer, param } = found_vector {
+        let (points_buffer, points_param) = match found_appl_point {
+            Data::Geom1D{ buffer, param } | Data::PointSet{ buffer, param } => (buffer, param),
+            _ => return Err(ProcessingError::IncorrectInput(" a family of vectors \n must be applied to a curve \n or to a point set ".into())),
+        };
+        if !points_param.is_equal(param)? {
+            return Err(ProcessingError::IncorrectInput(" the application points \n and the vectors must have \n the same parameter ".into()));
+        }
+        return create_family(device, points_buffer, vectors_buffer, param, thickness, material_id);
+    }
+
+    let appl_point_buffer = if let Data::Geom0D{ buffer } = found_appl_point {
+        buffer
+    } else {
+        return Err(ProcessingError::IncorrectInput(" the first input provided \n is not a point ".into()));
+    };
     let vector_buffer = if let Data::Vector{ buffer } = found_vector {
         buffer
     } else {
@@ -55,6 +68,7 @@ struct MatcapVertex {{
     normal: vec4<f32>;
     uv_coords: vec2<f32>;
     padding: vec2<f32>;
+    color: vec4<f32>;
 }};
 
 // input buffer will contain a single vertex, the actual point coords
@@ -123,6 +137,7 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     out.vertices[index].normal = matrix * vertex.normal;
     out.vertices[index].uv_coords = vertex.uv_coords;
     out.vertices[index].padding = vertex.padding;
+    out.vertices[index].color = vertex.color;
 }}
 "##, vertices_per_chunk=MODEL_CHUNK_VERTICES);
 
@@ -150,10 +165,13 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
 
     let renderable = MatcapData {
         vertex_buffer: output_buffer,
+        vertex_count: (MODEL_CHUNK_VERTICES * chunks_count) as u32,
         index_buffer: Rc::new(index_buffer),
         index_count,
         mask_id: 0,
         material_id,
+        instance_buffer: None,
+        instance_count: 1,
     };
     let operation = Operation {
         bind_group,
@@ -164,6 +182,129 @@ fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
     Ok((renderable, operation))
 }
 
+// Each vector of the family becomes an instance of the same arrow mesh, placed at the
+// point that has the same index. Null vectors and points that are not part of a point set
+// get a collapsed instance, just like the Point Set rendering does.
+fn create_family(
+    device: &wgpu::Device,
+    points_buffer: &wgpu::Buffer,
+    vectors_buffer: &wgpu::Buffer,
+    param: &Parameter,
+    thickness: usize,
+    material_id: usize,
+) -> MatcapResult {
+    let wgsl_source = format!(r##"
+struct PointsBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+struct VectorsBuffer {{
+    directions: array<vec4<f32>>;
+}};
+
+struct Instance {{
+    transform: mat4x4<f32>;
+    color: vec4<f32>;
+}};
+
+struct OutputBuffer {{
+    instances: array<Instance>;
+}};
+
+[[group(0), binding(0)]] var<storage, read> in_points: PointsBuffer;
+[[group(0), binding(1)]] var<storage, read> in_vectors: VectorsBuffer;
+[[group(0), binding(2)]] var<storage, read_write> out: OutputBuffer;
+
+[[stage(compute), workgroup_size({pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let index = global_id.x;
+    let point = in_points.positions[index];
+    let vector = in_vectors.directions[index].xyz;
+    let s_z = length(vector);
+    // a null color means that the material is used as it is
+    out.instances[index].color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+
+    if (s_z == 0.0 || point.w == 0.0) {{
+        out.instances[index].transform = mat4x4<f32>(
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 0.0),
+            vec4<f32>(point.xyz, 1.0),
+        );
+        return;
+    }}
+    let direction = vector / s_z;
+
+    var angle_z: f32;
+    // workaround MacOS bug: atan2 seems to give the wrong result
+    if (direction.y == 0.0) {{
+        if (direction.x > 0.0) {{
+            angle_z = -0.5*3.14159265;
+        }} else {{
+            angle_z =  0.5*3.14159265;
+        }}
+    }} else {{
+        angle_z = -1.0 * atan2(direction.x, direction.y);
+    }}
+
+    let angle_x = -1.0 * acos(clamp(direction.z, -1.0, 1.0));
+    let cos_t = cos(angle_z);
+    let sin_t = sin(angle_z);
+    let cos_p = cos(angle_x);
+    let sin_p = sin(angle_x);
+
+    out.instances[index].transform = mat4x4<f32>(
+        vec4<f32>(         cos_t,          sin_t,   0.0, 0.0), // first column
+        vec4<f32>(-cos_p * sin_t,  cos_p * cos_t, sin_p, 0.0), // 2nd column
+        s_z * vec4<f32>( sin_p * sin_t, -sin_p * cos_t, cos_p, 0.0), // 3rd column, scaled by the arrow length
+        vec4<f32>(point.xyz, 1.0), // 4th column
+    );
+}}
+"##, pps=Parameter::POINTS_PER_SEGMENT);
+
+    //println!("vector family instances shader source:\n {}", &wgsl_source);
+
+    let instance_buffer = util::create_storage_buffer(device, std::mem::size_of::<InstanceData>() * param.n_points());
+    let bind_info = vec![
+        BindInfo {
+            buffer: points_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: vectors_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &instance_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    // the arrow mesh points upwards and has length 1.0, it is used as it is by all the instances
+    let radius = AVAILABLE_SIZES[thickness];
+    let n_circle_points = (thickness + 3)*2;
+    let (index_buffer, index_count, vertex_buffer, chunks_count) = create_arrow_buffers(device, radius, n_circle_points);
+
+    let renderable = MatcapData {
+        vertex_buffer,
+        vertex_count: (chunks_count * MODEL_CHUNK_VERTICES) as u32,
+        index_buffer: Rc::new(index_buffer),
+        index_count,
+        mask_id: 0,
+        material_id,
+        instance_buffer: Some(instance_buffer),
+        instance_count: param.n_points() as u32,
+    };
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param.segments, 1, 1],
+    };
+
+    Ok((renderable, operation))
+}
+
 // TODO: DRY!!! We should take the following code, the code from geometry_rendering.rs
 // and find a new place for both. This function in particular could be shared
 fn create_arrow_segment(segment: (usize, usize), circle_points: usize) -> Vec::<u32> {
@@ -207,7 +348,7 @@ fn create_arrow_cap(segment: usize, cap_point_index: u32, circle_points: usize)
     indices
 }
 
-fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize) -> (wgpu::Buffer, u32, wgpu::Buffer, usize) {
+pub fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize) -> (wgpu::Buffer, u32, wgpu::Buffer, usize) {
     assert!(circle_points > 3);
     let head_z_start: f32 = 1.0 - 1.5*radius;
     let mut index_vector = Vec::<u32>::new();
@@ -230,6 +371,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
                 normal,
                 uv_coords: [0.0, 0.0],
                 _padding: [0.123, 0.456],
+                color: [0.0, 0.0, 0.0, 0.0],
             });
         }
         // add the second circle: same normals, different z for the points
@@ -242,6 +384,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
                 normal,
                 uv_coords: [0.0, 0.0],
                 _padding: [0.123, 0.456],
+                color: [0.0, 0.0, 0.0, 0.0],
             });
         }
     }
@@ -261,6 +404,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
                 normal,
                 uv_coords: [0.0, 0.0],
                 _padding: [0.123, 0.456],
+                color: [0.0, 0.0, 0.0, 0.0],
             });
         }
         // This time we change and use the entire radius, instead of only half,
@@ -274,6 +418,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
                 normal,
                 uv_coords: [0.0, 0.0],
                 _padding: [0.123, 0.456],
+                color: [0.0, 0.0, 0.0, 0.0],
             });
         }
     }
@@ -292,6 +437,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
                 normal,
                 uv_coords: [0.0, 0.0],
                 _padding: [0.123, 0.456],
+                color: [0.0, 0.0, 0.0, 0.0],
             });
         }
         // now, add the final point for the cap.
@@ -300,6 +446,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
             normal: [0.0, 0.0, 1.0, 0.0],
             uv_coords: [0.0, 0.0],
             _padding: [0.123, 0.456],
+            color: [0.0, 0.0, 0.0, 0.0],
         });
         // and create the indices for it
         let circle = 4;
@@ -314,6 +461,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
             normal: [0.0, 0.0, -1.0, 0.0],
             uv_coords: [0.0, 0.0],
             _padding: [0.123, 0.456],
+            color: [0.0, 0.0, 0.0, 0.0],
         });
         // and create the indices for it
         let circle = 0;
@@ -336,7 +484,7 @@ fn create_arrow_buffers(device: &wgpu::Device, radius: f32, circle_points: usize
         &wgpu::util::BufferInitDescriptor {
             label: None,
             contents: bytemuck::cast_slice(&index_vector),
-            usage: wgpu::BufferUsages::INDEX,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
     });
     let vertex_buffer = device.create_buffer_init(
         &wgpu::util::BufferInitDescriptor {
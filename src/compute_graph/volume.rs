@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use super::Operation;
+use super::globals::Globals;
+use super::{SingleDataResult, ProcessingError};
+use super::Parameter;
+use super::{DataID, Data};
+use crate::util;
+use crate::shader_processing::{naga_compute_pipeline, BindInfo};
+
+// the points of a volume are stored in a single storage buffer, which must fit in a binding:
+// with three intervals at high quality, the volume can easily exceed the device limits
+pub fn check_points_count(device: &wgpu::Device, points_count: usize) -> Result<(), ProcessingError> {
+    let max_binding_size = device.limits().max_storage_buffer_binding_size as usize;
+    if points_count * std::mem::size_of::<glam::Vec4>() > max_binding_size {
+        Err(ProcessingError::IncorrectAttributes(" the volume has too many points, \n lower the interval quality ".into()))
+    } else {
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    device: &wgpu::Device,
+    globals: &Globals,
+    data_map: &BTreeMap<DataID, Data>,
+    interval_1: Option<DataID>,
+    interval_2: Option<DataID>,
+    interval_3: Option<DataID>,
+    fx: String,
+    fy: String,
+    fz: String,
+) -> SingleDataResult {
+    let interval_1_id = interval_1
+        .ok_or_else(|| ProcessingError::InputMissing(" This Volume node \n is missing its first input ".into()))?;
+    let interval_2_id = interval_2
+        .ok_or_else(|| ProcessingError::InputMissing(" This Volume node \n is missing its second input ".into()))?;
+    let interval_3_id = interval_3
+        .ok_or_else(|| ProcessingError::InputMissing(" This Volume node \n is missing its third input ".into()))?;
+    let interval_1_data = data_map.get(&interval_1_id).ok_or(ProcessingError::NoInputData)?;
+    let interval_2_data = data_map.get(&interval_2_id).ok_or(ProcessingError::NoInputData)?;
+    let interval_3_data = data_map.get(&interval_3_id).ok_or(ProcessingError::NoInputData)?;
+
+    let (buffer_1, param_1) = match interval_1_data {
+        Data::Interval{
+            buffer, param
+        } => (buffer, param.clone()),
+        _ => return Err(ProcessingError::InternalError("the first input provided to the Volume node is not an Interval".into()))
+    };
+
+    let (buffer_2, param_2) = match interval_2_data {
+        Data::Interval{
+            buffer, param
+        } => (buffer, param.clone()),
+        _ => return Err(ProcessingError::InternalError("the second input provided to the Volume node is not an Interval".into()))
+    };
+
+    let (buffer_3, param_3) = match interval_3_data {
+        Data::Interval{
+            buffer, param
+        } => (buffer, param.clone()),
+        _ => return Err(ProcessingError::InternalError("the third input provided to the Volume node is not an Interval".into()))
+    };
+
+    let param_1_name = param_1.name.as_ref().unwrap();
+    let param_2_name = param_2.name.as_ref().unwrap();
+    let param_3_name = param_3.name.as_ref().unwrap();
+
+    // Sanitize all input expressions
+    let local_params = vec![param_1_name.as_str(), param_2_name.as_str(), param_3_name.as_str()];
+    let sanitized_fx = globals.sanitize_expression(&local_params, &fx)?;
+    let sanitized_fy = globals.sanitize_expression(&local_params, &fy)?;
+    let sanitized_fz = globals.sanitize_expression(&local_params, &fz)?;
+
+    let wgsl_source = format!(r##"
+{wgsl_header}
+
+struct InputBuffer {{
+    values: array<f32>;
+}};
+
+struct OutputBuffer {{
+    positions: array<vec4<f32>>;
+}};
+
+[[group(0), binding(1)]] var<storage, read> interval_1: InputBuffer;
+[[group(0), binding(2)]] var<storage, read> interval_2: InputBuffer;
+[[group(0), binding(3)]] var<storage, read> interval_3: InputBuffer;
+[[group(0), binding(4)]] var<storage, read_write> output: OutputBuffer;
+
+// the third parameter is not split into workgroups, since the workgroup
+// would exceed the maximum number of invocations
+[[stage(compute), workgroup_size({pps}, {pps})]]
+fn main([[builtin(global_invocation_id)]] global_id: vec3<u32>) {{
+    let par1_idx = global_id.x;
+    let par2_idx = global_id.y;
+    let par3_idx = global_id.z;
+    let index = par1_idx + {size_x}u * (par2_idx + {size_y}u * par3_idx);
+
+    let {par1} = interval_1.values[par1_idx];
+    let {par2} = interval_2.values[par2_idx];
+    let {par3} = interval_3.values[par3_idx];
+    let fx = {fx};
+    let fy = {fy};
+    let fz = {fz};
+    output.positions[index] = vec4<f32>(fx, fy, fz, 1.0);
+}}
+"##, wgsl_header=globals.get_wgsl_header(), pps=Parameter::POINTS_PER_SEGMENT,
+par1=param_1_name, par2=param_2_name, par3=param_3_name,
+fx=sanitized_fx, fy=sanitized_fy, fz=sanitized_fz,
+size_x=param_1.n_points(), size_y=param_2.n_points(),
+);
+
+    //println!("volume shader source:\n {}", &wgsl_source);
+
+    check_points_count(device, param_1.n_points() * param_2.n_points() * param_3.n_points())?;
+    let output_buffer = util::create_storage_buffer(device, std::mem::size_of::<glam::Vec4>() * param_1.n_points() * param_2.n_points() * param_3.n_points());
+
+    let bind_info = vec![
+        globals.get_bind_info(),
+        BindInfo {
+            buffer: buffer_1,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: buffer_2,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: buffer_3,
+            ty: wgpu::BufferBindingType::Storage { read_only: true },
+        },
+        BindInfo {
+            buffer: &output_buffer,
+            ty: wgpu::BufferBindingType::Storage { read_only: false },
+        },
+    ];
+    let (pipeline, bind_group) = naga_compute_pipeline(device, &wgsl_source, &bind_info);
+
+    let operation = Operation {
+        bind_group,
+        pipeline: Rc::new(pipeline),
+        dim: [param_1.segments, param_2.segments, param_3.n_points() as u32],
+    };
+    let new_data = Data::Geom3D {
+        param1: param_1,
+        param2: param_2,
+        param3: param_3,
+        buffer: output_buffer,
+    };
+
+    Ok((new_data, operation))
+}
+
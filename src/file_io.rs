@@ -32,6 +32,34 @@ pub fn async_pick_png(event_loop_proxy: EventLoopProxy<CustomEvent>, executor: &
     });
 }
 
+pub fn async_pick_mesh(event_loop_proxy: EventLoopProxy<CustomEvent>, executor: &Executor) {
+    let dialog = rfd::AsyncFileDialog::new()
+        .add_filter("Wavefront obj", &["obj"])
+        .add_filter("Stl mesh", &["stl"])
+        .add_filter("Ply mesh", &["ply"])
+        .save_file();
+
+    executor.execut(async move {
+        let file = dialog.await;
+        if let Some(handle) = file {
+            event_loop_proxy.send_event(CustomEvent::ExportMesh(handle.path().into())).unwrap();
+        }
+    });
+}
+
+pub fn async_pick_gltf(event_loop_proxy: EventLoopProxy<CustomEvent>, executor: &Executor) {
+    let dialog = rfd::AsyncFileDialog::new()
+        .add_filter("Binary glTF", &["glb"])
+        .save_file();
+
+    executor.execut(async move {
+        let file = dialog.await;
+        if let Some(handle) = file {
+            event_loop_proxy.send_event(CustomEvent::ExportGltf(handle.path().into())).unwrap();
+        }
+    });
+}
+
 pub fn async_confirm_load(event_loop_proxy: EventLoopProxy<CustomEvent>, executor: &Executor, file_path: std::path::PathBuf) {
     let confirm_load = rfd::AsyncMessageDialog::new()
         .set_level(rfd::MessageLevel::Warning)
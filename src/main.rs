@@ -36,6 +36,8 @@ pub enum CustomEvent {
     SaveFile(std::path::PathBuf),
     ExportGraphPng(std::path::PathBuf),
     ExportScenePng(std::path::PathBuf),
+    ExportMesh(std::path::PathBuf),
+    ExportGltf(std::path::PathBuf),
     RequestExit,
     MouseFreeze,
     MouseThaw,
@@ -94,6 +96,19 @@ fn main() -> Result<(), &'static str>{
              .long("export")
              .value_name("EXPORT")
              .takes_value(true))
+        .arg(clap::Arg::with_name("export-mesh")
+             .help("Instead of running the program normally, FranzPlot will only export the meshes of the scene.
+                   The format of the output file (obj, stl or ply) is chosen from its extension")
+             .short("m")
+             .long("export-mesh")
+             .value_name("MESH_FILE")
+             .takes_value(true))
+        .arg(clap::Arg::with_name("export-gltf")
+             .help("Instead of running the program normally, FranzPlot will only export the scene, including its camera, to a glb file")
+             .short("g")
+             .long("export-gltf")
+             .value_name("GLB_FILE")
+             .takes_value(true))
         .arg(clap::Arg::with_name("tracing")
             .short("t")
             .long("tracing")
@@ -125,7 +140,9 @@ fn main() -> Result<(), &'static str>{
     let maybe_input_file = matches.value_of("INPUT");
 
     let maybe_export_path = matches.value_of("export");
-    let exporting_hang_workaround = maybe_export_path.is_some();
+    let maybe_export_mesh_path = matches.value_of("export-mesh");
+    let maybe_export_gltf_path = matches.value_of("export-gltf");
+    let exporting_hang_workaround = maybe_export_path.is_some() || maybe_export_mesh_path.is_some() || maybe_export_gltf_path.is_some();
 
     let maybe_backend = matches.value_of("backend").map(|name| {
         match name.to_lowercase().as_str()  {
@@ -165,7 +182,7 @@ fn main() -> Result<(), &'static str>{
         .with_title("Franzplot")
         .with_window_icon(Some(icon))
         .with_inner_size(window_size);
-    if maybe_export_path.is_some() {
+    if exporting_hang_workaround {
         builder = builder.with_visible(false);
     }
     let window = builder.build(&event_loop).unwrap();
@@ -287,6 +304,7 @@ fn main() -> Result<(), &'static str>{
 
     let masks = util::load_masks(&device_manager, &mask_files);
     let materials = util::load_materials(&device_manager, &material_files);
+    let material_colors = util::load_material_colors(&material_files);
     assert!(!materials.is_empty(), "Error while loading resources: could not load any material.");
 
     // do the same for models
@@ -319,6 +337,7 @@ fn main() -> Result<(), &'static str>{
 
     let assets = state::Assets {
         materials,
+        material_colors,
         models,
         masks,
     };
@@ -350,6 +369,17 @@ fn main() -> Result<(), &'static str>{
         state.app.camera.set_x1_y1_z1_wide();
         event_loop_proxy.send_event(CustomEvent::ExportGraphPng(graph_file_path)).unwrap();
         event_loop_proxy.send_event(CustomEvent::ExportScenePng(scene_file_path)).unwrap();
+    }
+
+    if let Some(path) = maybe_export_mesh_path {
+        event_loop_proxy.send_event(CustomEvent::ExportMesh(std::path::PathBuf::from(path))).unwrap();
+    }
+
+    if let Some(path) = maybe_export_gltf_path {
+        event_loop_proxy.send_event(CustomEvent::ExportGltf(std::path::PathBuf::from(path))).unwrap();
+    }
+
+    if exporting_hang_workaround {
         event_loop_proxy.send_event(CustomEvent::RequestExit).unwrap();
     }
 
@@ -535,6 +565,32 @@ fn main() -> Result<(), &'static str>{
                         println!("Exporting scene: {:?}", &path_buf);
                         util::create_scene_png(&mut state, &path_buf);
                     },
+                    CustomEvent::ExportMesh(path_buf) => {
+                        println!("Exporting mesh: {:?}", &path_buf);
+                        // when exporting from the command line, the scene was never processed
+                        if state.app.comp_graph.is_none() {
+                            if let Err(error) = state.process(Action::ProcessUserState()) {
+                                println!("Warning: errors detected in the scene: {}", error);
+                            }
+                        }
+                        if let Err(error) = state.process(Action::ExportMesh(path_buf)) {
+                            println!("Mesh export failed: {}", error);
+                            file_io::async_dialog_failure(&executor, error);
+                        }
+                    },
+                    CustomEvent::ExportGltf(path_buf) => {
+                        println!("Exporting glTF scene: {:?}", &path_buf);
+                        // when exporting from the command line, the scene was never processed
+                        if state.app.comp_graph.is_none() {
+                            if let Err(error) = state.process(Action::ProcessUserState()) {
+                                println!("Warning: errors detected in the scene: {}", error);
+                            }
+                        }
+                        if let Err(error) = state.process(Action::ExportGltf(path_buf)) {
+                            println!("glTF export failed: {}", error);
+                            file_io::async_dialog_failure(&executor, error);
+                        }
+                    },
                     CustomEvent::MouseFreeze => {
                         // set mouse as frozen
                         mouse_frozen = true;
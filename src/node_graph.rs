@@ -1,10 +1,12 @@
 use std::collections::HashMap;
+use std::collections::BTreeMap;
 use crate::compute_graph::ProcessingError;
 use crate::compute_graph::RecoverableError;
 use crate::compute_graph::UnrecoverableError;
 use crate::cpp_gui::imnodes;
 use crate::cpp_gui::PinShape;
 use crate::rust_gui::Availables;
+use crate::rendering::mesh_file::MeshData;
 use serde::{Serialize, Deserialize};
 use imgui::*;
 
@@ -17,6 +19,7 @@ pub enum DataKind {
     Geometry,
     Matrix,
     Vector,
+    Scalar,
 }
 
 pub const ZOOM_LEVELS: [f32; 6] = [1.0, 0.8, 0.64, 0.512, 0.41, 0.32];
@@ -43,6 +46,7 @@ impl DataKind {
             DataKind::Geometry => PinShape::CircleFilled,
             DataKind::Vector => PinShape::TriangleFilled,
             DataKind::Matrix => PinShape::Quad,
+            DataKind::Scalar => PinShape::Circle,
         };
         pin_shape as i32
     }
@@ -67,6 +71,93 @@ pub enum Axis {
     Z,
 }
 
+#[derive(Copy, Clone, Deserialize, Serialize, Debug,)]
+pub enum CoordinateSystem {
+    Cartesian,
+    Cylindrical,
+    Spherical,
+}
+
+impl CoordinateSystem {
+    // labels for the three expressions that define a point in this coordinate system
+    pub fn labels(&self) -> [&'static str; 3] {
+        match self {
+            CoordinateSystem::Cartesian => ["fx", "fy", "fz"],
+            CoordinateSystem::Cylindrical => ["r", "theta", "z"],
+            CoordinateSystem::Spherical => ["rho", "theta", "phi"],
+        }
+    }
+}
+
+#[derive(Copy, Clone, Deserialize, Serialize, Debug,)]
+pub enum CoordinatePlane {
+    XY,
+    XZ,
+    YZ,
+}
+
+#[derive(Copy, Clone, Deserialize, Serialize, Debug,)]
+pub enum SplineKind {
+    Bezier,
+    UniformBSpline,
+    ClampedBSpline,
+}
+
+#[derive(Copy, Clone, Deserialize, Serialize, Debug,)]
+pub enum FieldGrid {
+    Space,
+    PlaneXY,
+    PlaneXZ,
+    PlaneYZ,
+}
+
+#[derive(Copy, Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub enum Colormap {
+    Material,
+    Viridis,
+    Diverging,
+    Rainbow,
+}
+
+impl Colormap {
+    // maps values in [0, 1] to colors. This is only used to draw the legend,
+    // it must match the colormap() functions used by the compute shaders.
+    pub fn color(&self, t: f32) -> [f32; 3] {
+        let t = t.clamp(0.0, 1.0);
+        let color = match self {
+            Colormap::Viridis => {
+                let c: [[f32; 3]; 7] = [
+                    [0.2777273, 0.0054073, 0.3340998],
+                    [0.105093, 1.404613, 1.38459],
+                    [-0.3308618, 0.2148476, 0.0950952],
+                    [-4.634231, -5.799101, -19.33244],
+                    [6.22827, 14.17993, 56.69055],
+                    [4.776385, -13.74515, -65.35303],
+                    [-5.435456, 4.645853, 26.31244],
+                ];
+                let mut color = [0.0; 3];
+                for (channel, value) in color.iter_mut().enumerate() {
+                    *value = c.iter().rev().fold(0.0, |acc, coeffs| acc * t + coeffs[channel]);
+                }
+                color
+            },
+            Colormap::Diverging => {
+                let blue = [0.230, 0.299, 0.754];
+                let white = [0.865, 0.865, 0.865];
+                let red = [0.706, 0.016, 0.150];
+                let (from, to, alpha) = if t < 0.5 { (blue, white, 2.0 * t) } else { (white, red, 2.0 * t - 1.0) };
+                [0, 1, 2].map(|i| from[i] + alpha * (to[i] - from[i]))
+            },
+            Colormap::Rainbow => {
+                let hue = (1.0 - t) * 2.0 / 3.0;
+                [0.0, 2.0 / 3.0, 1.0 / 3.0].map(|offset: f32| ((hue + offset).fract() * 6.0 - 3.0).abs() - 1.0)
+            },
+            Colormap::Material => [t, t, t],
+        };
+        color.map(|value| value.clamp(0.0, 1.0))
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug,)]
 pub enum AttributeContents {
     InputPin {
@@ -95,6 +186,21 @@ pub enum AttributeContents {
     AxisSelect {
         axis: Axis,
     },
+    PlaneSelect {
+        plane: CoordinatePlane,
+    },
+    SplineKindSelect {
+        kind: SplineKind,
+    },
+    GridSelect {
+        grid: FieldGrid,
+    },
+    ColormapSelect {
+        colormap: Colormap,
+    },
+    Coordinates {
+        system: CoordinateSystem,
+    },
     Color {
         label: String,
         color: [f32; 3],
@@ -108,6 +214,14 @@ pub enum AttributeContents {
     PrimitiveKind {
         selected: usize,
     },
+    MeshFile {
+        // relative to the directory of the scene file, unless the mesh is somewhere else
+        path: String,
+        embed: bool,
+        // the mesh is stored in the scene file only if the user chose to embed it
+        #[serde(default)]
+        embedded: Option<MeshData>,
+    },
     Unknown {
         label: String,
     }
@@ -117,7 +231,7 @@ pub const AVAILABLE_SIZES: [f32; 9] = [0.04, 0.08, 0.12, 0.16, 0.20, 0.24, 0.32,
 
 impl Attribute {
     // the render function shall return bool if anything has changed.
-    pub fn render(&mut self, ui: &imgui::Ui<'_>, availables: &Availables, id: AttributeID) -> bool {
+    pub fn render(&mut self, ui: &imgui::Ui<'_>, availables: &Availables, scalar_values: &BTreeMap<AttributeID, f32>, color_ranges: &BTreeMap<AttributeID, [f32; 2]>, id: AttributeID) -> bool {
         // TODO: maybe we can push the style var at the begin of the editor rendering,
         // just like we push the imnodes style vars
         let font_size = ui.current_font_size();
@@ -136,7 +250,12 @@ impl Attribute {
                 label, kind,
             } => {
                 imnodes::BeginOutputAttribute(id, kind.to_pin_shape());
-                ui.text(label);
+                // scalar outputs also show their current value
+                if let Some(value) = scalar_values.get(&id) {
+                    ui.text(format!("{} = {:.4}", label, value));
+                } else {
+                    ui.text(label);
+                }
                 imnodes::EndOutputAttribute();
                 false
             },
@@ -181,6 +300,147 @@ impl Attribute {
                 imnodes::EndStaticAttribute();
                 value_changed
             },
+            AttributeContents::PlaneSelect {
+                plane
+            } => {
+                let widget_width = 8.5 * char_w;
+
+                imnodes::BeginStaticAttribute(id);
+
+                ui.text("plane");
+                ui.same_line();
+                ui.set_next_item_width(widget_width);
+                let choices = vec!("XY", "XZ", "YZ");
+                let mut selected = match plane {
+                    CoordinatePlane::XY => 0,
+                    CoordinatePlane::XZ => 1,
+                    CoordinatePlane::YZ => 2,
+                };
+                let value_changed = ui.combo_simple_string("##plane", &mut selected, &choices);
+                *plane = match selected {
+                    0 => CoordinatePlane::XY,
+                    1 => CoordinatePlane::XZ,
+                    2 => CoordinatePlane::YZ,
+                    _ => panic!("Something went wrong when selecting a Plane from a combo box")
+                };
+                imnodes::EndStaticAttribute();
+                value_changed
+            },
+            AttributeContents::SplineKindSelect {
+                kind
+            } => {
+                let widget_width = 12.0 * char_w;
+
+                imnodes::BeginStaticAttribute(id);
+
+                ui.text(" kind");
+                ui.same_line();
+                ui.set_next_item_width(widget_width);
+                let choices = vec!("Bézier", "B-spline", "clamped B-spline");
+                let mut selected = match kind {
+                    SplineKind::Bezier => 0,
+                    SplineKind::UniformBSpline => 1,
+                    SplineKind::ClampedBSpline => 2,
+                };
+                let value_changed = ui.combo_simple_string("##spline_kind", &mut selected, &choices);
+                *kind = match selected {
+                    0 => SplineKind::Bezier,
+                    1 => SplineKind::UniformBSpline,
+                    2 => SplineKind::ClampedBSpline,
+                    _ => panic!("Something went wrong when selecting a spline kind from a combo box")
+                };
+                imnodes::EndStaticAttribute();
+                value_changed
+            },
+            AttributeContents::GridSelect {
+                grid
+            } => {
+                let widget_width = 10.0 * char_w;
+
+                imnodes::BeginStaticAttribute(id);
+
+                ui.text(" grid");
+                ui.same_line();
+                ui.set_next_item_width(widget_width);
+                let choices = vec!("3D", "XY plane", "XZ plane", "YZ plane");
+                let mut selected = match grid {
+                    FieldGrid::Space => 0,
+                    FieldGrid::PlaneXY => 1,
+                    FieldGrid::PlaneXZ => 2,
+                    FieldGrid::PlaneYZ => 3,
+                };
+                let value_changed = ui.combo_simple_string("##field_grid", &mut selected, &choices);
+                *grid = match selected {
+                    0 => FieldGrid::Space,
+                    1 => FieldGrid::PlaneXY,
+                    2 => FieldGrid::PlaneXZ,
+                    3 => FieldGrid::PlaneYZ,
+                    _ => panic!("Something went wrong when selecting a grid from a combo box")
+                };
+                imnodes::EndStaticAttribute();
+                value_changed
+            },
+            AttributeContents::ColormapSelect {
+                colormap
+            } => {
+                let widget_width = 10.0 * char_w;
+
+                imnodes::BeginStaticAttribute(id);
+
+                ui.text("colors:");
+                ui.same_line();
+                ui.set_next_item_width(widget_width);
+                let choices = vec!("material", "viridis", "diverging", "rainbow");
+                let mut selected = match colormap {
+                    Colormap::Material => 0,
+                    Colormap::Viridis => 1,
+                    Colormap::Diverging => 2,
+                    Colormap::Rainbow => 3,
+                };
+                let value_changed = ui.combo_simple_string("##colormap", &mut selected, &choices);
+                *colormap = match selected {
+                    0 => Colormap::Material,
+                    1 => Colormap::Viridis,
+                    2 => Colormap::Diverging,
+                    3 => Colormap::Rainbow,
+                    _ => panic!("Something went wrong when selecting a colormap from a combo box")
+                };
+                // the legend shows the colors together with the range of values they span
+                if let (false, Some([low, high])) = (*colormap == Colormap::Material, color_ranges.get(&id)) {
+                    let bar_width = 18.0 * char_w;
+                    let bar_height = 0.8 * ui.current_font_size();
+                    let steps = 32;
+                    let [left, top] = ui.cursor_screen_pos();
+                    let draw_list = ui.get_window_draw_list();
+                    for i in 0..steps {
+                        let [r0, g0, b0] = colormap.color(i as f32 / steps as f32);
+                        let [r1, g1, b1] = colormap.color((i + 1) as f32 / steps as f32);
+                        let x0 = left + bar_width * i as f32 / steps as f32;
+                        let x1 = left + bar_width * (i + 1) as f32 / steps as f32;
+                        draw_list.add_rect_filled_multicolor(
+                            [x0, top], [x1, top + bar_height],
+                            [r0, g0, b0, 1.0], [r1, g1, b1, 1.0], [r1, g1, b1, 1.0], [r0, g0, b0, 1.0]);
+                    }
+                    ui.dummy([bar_width, bar_height]);
+                    ui.text(format!("{:.4} ... {:.4}", low, high));
+                }
+                imnodes::EndStaticAttribute();
+                value_changed
+            },
+            AttributeContents::Coordinates {
+                system
+            } => {
+                // the coordinate system cannot be changed after the node is created,
+                // since the labels of the expressions depend on it
+                imnodes::BeginStaticAttribute(id);
+                match system {
+                    CoordinateSystem::Cartesian => ui.text("cartesian coordinates"),
+                    CoordinateSystem::Cylindrical => ui.text("cylindrical coordinates"),
+                    CoordinateSystem::Spherical => ui.text("spherical coordinates"),
+                }
+                imnodes::EndStaticAttribute();
+                false
+            },
             AttributeContents::IntSlider {
                 label, value, mode,
             } => {
@@ -372,6 +632,30 @@ impl Attribute {
                 *selected = index;
                 value_changed
             },
+            AttributeContents::MeshFile {
+                path, embed, embedded,
+            } => {
+                imnodes::BeginStaticAttribute(id);
+                let file_name = std::path::Path::new(path.as_str())
+                    .file_name()
+                    .map_or(String::from("no file"), |name| name.to_string_lossy().into());
+                ui.text(format!("file: {}", file_name));
+                let mut value_changed = false;
+                if ui.small_button("choose...") {
+                    let picked = rfd::FileDialog::new()
+                        .add_filter("Mesh", &["obj", "stl", "ply"])
+                        .pick_file();
+                    if let Some(picked_path) = picked {
+                        *path = picked_path.to_string_lossy().into();
+                        // the embedded mesh is updated when the scene is saved
+                        *embedded = None;
+                        value_changed = true;
+                    }
+                }
+                value_changed |= ui.checkbox("embed in scene file", embed);
+                imnodes::EndStaticAttribute();
+                value_changed
+            },
             AttributeContents::Unknown {
                 ..
             } => {
@@ -382,11 +666,11 @@ impl Attribute {
         value_changed
     }
 
-    pub fn render_list(ui: &imgui::Ui<'_>, availables: &Availables, attributes: &mut Vec<Option<Attribute>>, attribute_id_list: Vec<AttributeID>) -> bool {
+    pub fn render_list(ui: &imgui::Ui<'_>, availables: &Availables, scalar_values: &BTreeMap<AttributeID, f32>, color_ranges: &BTreeMap<AttributeID, [f32; 2]>, attributes: &mut Vec<Option<Attribute>>, attribute_id_list: Vec<AttributeID>) -> bool {
         let mut value_changed = false;
         for id in attribute_id_list.into_iter() {
             if let Some(Some(attribute)) = attributes.get_mut(id as usize) {
-                value_changed |= attribute.render(ui, availables, id);
+                value_changed |= attribute.render(ui, availables, scalar_values, color_ranges, id);
             }
         }
         value_changed
@@ -403,9 +687,84 @@ pub enum NodeContents {
         output: AttributeID,
     },
     Sample {
+        geometry: AttributeID,
+        // the interval input was added later on, older files do not contain it
+        #[serde(default)]
+        interval: Option<AttributeID>,
+        parameter: AttributeID,
+        value: AttributeID,
+        // same for the attributes used to sample a family at several values
+        #[serde(default)]
+        samples: Option<AttributeID>,
+        #[serde(default)]
+        last_value: Option<AttributeID>,
+        output: AttributeID,
+    },
+    NameParameters {
+        geometry: AttributeID,
+        name_1: AttributeID,
+        name_2: AttributeID,
+        name_3: AttributeID,
+        output: AttributeID,
+    },
+    FrenetFrame {
+        geometry: AttributeID,
+        parameter: AttributeID,
+        value: AttributeID,
+        point: AttributeID,
+        tangent: AttributeID,
+        normal: AttributeID,
+        binormal: AttributeID,
+    },
+    Curvature {
         geometry: AttributeID,
         parameter: AttributeID,
         value: AttributeID,
+        curvature: AttributeID,
+        torsion: AttributeID,
+        circle: AttributeID,
+    },
+    SurfaceFrame {
+        geometry: AttributeID,
+        parameter_1: AttributeID,
+        value_1: AttributeID,
+        parameter_2: AttributeID,
+        value_2: AttributeID,
+        size: AttributeID,
+        point: AttributeID,
+        u_tangent: AttributeID,
+        v_tangent: AttributeID,
+        normal: AttributeID,
+        plane: AttributeID,
+        e: AttributeID,
+        f: AttributeID,
+        g: AttributeID,
+        gaussian: AttributeID,
+        mean: AttributeID,
+    },
+    ArcLength {
+        geometry: AttributeID,
+        output: AttributeID,
+        length: AttributeID,
+    },
+    Trajectory {
+        point: AttributeID,
+        variable: AttributeID,
+        begin: AttributeID,
+        end: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+    },
+    CurvePlaneIntersection {
+        geometry: AttributeID,
+        point: AttributeID,
+        normal: AttributeID,
+        output: AttributeID,
+    },
+    LineSurfaceIntersection {
+        geometry: AttributeID,
+        point: AttributeID,
+        direction: AttributeID,
         output: AttributeID,
     },
     Vector {
@@ -414,7 +773,54 @@ pub enum NodeContents {
         z: AttributeID,
         output: AttributeID,
     },
+    VectorFromPoints {
+        from: AttributeID,
+        to: AttributeID,
+        output: AttributeID,
+    },
+    TranslatePoint {
+        point: AttributeID,
+        vector: AttributeID,
+        output: AttributeID,
+    },
+    VectorSum {
+        first: AttributeID,
+        second: AttributeID,
+        output: AttributeID,
+    },
+    VectorDifference {
+        first: AttributeID,
+        second: AttributeID,
+        output: AttributeID,
+    },
+    ScaleVector {
+        vector: AttributeID,
+        scalar: AttributeID,
+        factor: AttributeID,
+        output: AttributeID,
+    },
+    DotProduct {
+        first: AttributeID,
+        second: AttributeID,
+        output: AttributeID,
+    },
+    CrossProduct {
+        first: AttributeID,
+        second: AttributeID,
+        output: AttributeID,
+    },
+    NormalizeVector {
+        vector: AttributeID,
+        output: AttributeID,
+    },
+    VectorLength {
+        vector: AttributeID,
+        output: AttributeID,
+    },
     Point {
+        // the interval input was added later on, older files do not contain it
+        #[serde(default)]
+        interval: Option<AttributeID>,
         x: AttributeID,
         y: AttributeID,
         z: AttributeID,
@@ -428,6 +834,35 @@ pub enum NodeContents {
         quality: AttributeID,
         output: AttributeID,
     },
+    Spline {
+        p0: AttributeID,
+        p1: AttributeID,
+        p2: AttributeID,
+        p3: AttributeID,
+        p4: AttributeID,
+        p5: AttributeID,
+        p6: AttributeID,
+        p7: AttributeID,
+        kind: AttributeID,
+        degree: AttributeID,
+        weights: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+        polygon: AttributeID,
+    },
+    SplineSurface {
+        kind: AttributeID,
+        degree: AttributeID,
+        row_1: AttributeID,
+        row_2: AttributeID,
+        row_3: AttributeID,
+        row_4: AttributeID,
+        row_5: AttributeID,
+        row_6: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+        control_net: AttributeID,
+    },
     Curve {
         interval: AttributeID,
         fx: AttributeID,
@@ -435,6 +870,14 @@ pub enum NodeContents {
         fz: AttributeID,
         output: AttributeID,
     },
+    CoordinateCurve {
+        interval: AttributeID,
+        coordinates: AttributeID,
+        f1: AttributeID,
+        f2: AttributeID,
+        f3: AttributeID,
+        output: AttributeID,
+    },
     Surface {
         interval_1: AttributeID,
         interval_2: AttributeID,
@@ -443,12 +886,82 @@ pub enum NodeContents {
         fz: AttributeID,
         output: AttributeID,
     },
+    CoordinateSurface {
+        interval_1: AttributeID,
+        interval_2: AttributeID,
+        coordinates: AttributeID,
+        f1: AttributeID,
+        f2: AttributeID,
+        f3: AttributeID,
+        output: AttributeID,
+    },
+    Volume {
+        interval_1: AttributeID,
+        interval_2: AttributeID,
+        interval_3: AttributeID,
+        fx: AttributeID,
+        fy: AttributeID,
+        fz: AttributeID,
+        output: AttributeID,
+    },
+    Revolution {
+        geometry: AttributeID,
+        axis: AttributeID,
+        axis_point: AttributeID,
+        axis_direction: AttributeID,
+        // the name of the angle parameter, missing in files saved before it was introduced
+        #[serde(default)]
+        name: Option<AttributeID>,
+        begin: AttributeID,
+        end: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+    },
+    Sweep {
+        path: AttributeID,
+        profile: AttributeID,
+        radius: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+    },
+    RuledSurface {
+        curve_1: AttributeID,
+        curve_2: AttributeID,
+        // the name of the rulings parameter, missing in files saved before it was introduced
+        #[serde(default)]
+        name: Option<AttributeID>,
+        quality: AttributeID,
+        output: AttributeID,
+    },
     Plane {
         center: AttributeID,
         normal: AttributeID,
         size: AttributeID,
         output: AttributeID,
     },
+    ImplicitSurface {
+        function: AttributeID,
+        x_min: AttributeID,
+        x_max: AttributeID,
+        y_min: AttributeID,
+        y_max: AttributeID,
+        z_min: AttributeID,
+        z_max: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+    },
+    ImplicitCurve {
+        function: AttributeID,
+        x_min: AttributeID,
+        x_max: AttributeID,
+        y_min: AttributeID,
+        y_max: AttributeID,
+        plane: AttributeID,
+        center: AttributeID,
+        normal: AttributeID,
+        quality: AttributeID,
+        output: AttributeID,
+    },
     Transform {
         geometry: AttributeID,
         matrix: AttributeID,
@@ -470,11 +983,45 @@ pub enum NodeContents {
         vector: AttributeID,
         output: AttributeID,
     },
+    ScaleMatrix {
+        x: AttributeID,
+        y: AttributeID,
+        z: AttributeID,
+        output: AttributeID,
+    },
+    AxisRotationMatrix {
+        axis: AttributeID,
+        angle: AttributeID,
+        output: AttributeID,
+    },
+    MatrixProduct {
+        first: AttributeID,
+        second: AttributeID,
+        output: AttributeID,
+    },
+    InverseMatrix {
+        matrix: AttributeID,
+        output: AttributeID,
+    },
+    TransposeMatrix {
+        matrix: AttributeID,
+        output: AttributeID,
+    },
     Rendering {
         geometry: AttributeID,
         thickness: AttributeID,
         mask: AttributeID,
         material: AttributeID,
+        // u-v lines were added later on, older files do not contain them
+        #[serde(default)]
+        lines: Option<AttributeID>,
+        #[serde(default)]
+        lines_material: Option<AttributeID>,
+        // same for the colormap
+        #[serde(default)]
+        colormap: Option<AttributeID>,
+        #[serde(default)]
+        color_function: Option<AttributeID>,
     },
     VectorRendering {
         application_point: AttributeID,
@@ -482,11 +1029,27 @@ pub enum NodeContents {
         thickness: AttributeID,
         material: AttributeID,
     },
+    VectorField {
+        fx: AttributeID,
+        fy: AttributeID,
+        fz: AttributeID,
+        size: AttributeID,
+        grid: AttributeID,
+        points: AttributeID,
+        scale: AttributeID,
+        thickness: AttributeID,
+        material: AttributeID,
+    },
     Primitive {
         primitive: AttributeID,
         size: AttributeID,
         output: AttributeID,
     },
+    MeshFile {
+        file: AttributeID,
+        size: AttributeID,
+        output: AttributeID,
+    },
     Group
 }
 
@@ -495,19 +1058,53 @@ impl NodeContents {
         match self {
             NodeContents::Interval {..} => Self::default_interval(),
             NodeContents::Sample {..} => Self::default_sample(),
+            NodeContents::NameParameters {..} => Self::default_name_parameters(),
+            NodeContents::FrenetFrame {..} => Self::default_frenet_frame(),
+            NodeContents::Curvature {..} => Self::default_curvature(),
+            NodeContents::SurfaceFrame {..} => Self::default_surface_frame(),
+            NodeContents::ArcLength {..} => Self::default_arc_length(),
+            NodeContents::Trajectory {..} => Self::default_trajectory(),
+            NodeContents::CurvePlaneIntersection {..} => Self::default_curve_plane_intersection(),
+            NodeContents::LineSurfaceIntersection {..} => Self::default_line_surface_intersection(),
             NodeContents::Vector {..} => Self::default_vector(),
+            NodeContents::VectorFromPoints {..} => Self::default_vector_from_points(),
+            NodeContents::TranslatePoint {..} => Self::default_translate_point(),
+            NodeContents::VectorSum {..} => Self::default_vector_sum(),
+            NodeContents::VectorDifference {..} => Self::default_vector_difference(),
+            NodeContents::ScaleVector {..} => Self::default_scale_vector(),
+            NodeContents::DotProduct {..} => Self::default_dot_product(),
+            NodeContents::CrossProduct {..} => Self::default_cross_product(),
+            NodeContents::NormalizeVector {..} => Self::default_normalize_vector(),
+            NodeContents::VectorLength {..} => Self::default_vector_length(),
             NodeContents::Point {..} => Self::default_point(),
             NodeContents::Bezier {..} => Self::default_bezier(),
+            NodeContents::Spline {..} => Self::default_spline(),
+            NodeContents::SplineSurface {..} => Self::default_spline_surface(),
             NodeContents::Curve {..} => Self::default_curve(),
+            NodeContents::CoordinateCurve {..} => Self::default_coordinate_curve(),
             NodeContents::Surface {..} => Self::default_surface(),
+            NodeContents::CoordinateSurface {..} => Self::default_coordinate_surface(),
+            NodeContents::Volume {..} => Self::default_volume(),
+            NodeContents::Revolution {..} => Self::default_revolution(),
+            NodeContents::Sweep {..} => Self::default_sweep(),
+            NodeContents::RuledSurface {..} => Self::default_ruled_surface(),
             NodeContents::Plane {..} => Self::default_plane(),
+            NodeContents::ImplicitSurface {..} => Self::default_implicit_surface(),
+            NodeContents::ImplicitCurve {..} => Self::default_implicit_curve(),
             NodeContents::Matrix {..} => Self::default_matrix(),
             NodeContents::RotationMatrix {..} => Self::default_rotation_matrix(),
             NodeContents::TranslationMatrix {..} => Self::default_translation_matrix(),
+            NodeContents::ScaleMatrix {..} => Self::default_scale_matrix(),
+            NodeContents::AxisRotationMatrix {..} => Self::default_axis_rotation_matrix(),
+            NodeContents::MatrixProduct {..} => Self::default_matrix_product(),
+            NodeContents::InverseMatrix {..} => Self::default_inverse_matrix(),
+            NodeContents::TransposeMatrix {..} => Self::default_transpose_matrix(),
             NodeContents::Transform {..} => Self::default_transform(),
             NodeContents::Rendering {..} => Self::default_rendering(),
             NodeContents::VectorRendering {..} => Self::default_vector_rendering(),
+            NodeContents::VectorField {..} => Self::default_vector_field(),
             NodeContents::Primitive {..} => Self::default_primitive(),
+            NodeContents::MeshFile {..} => Self::default_mesh_file(),
             NodeContents::Group => unimplemented!(),
         }
     }
@@ -522,52 +1119,201 @@ impl NodeContents {
                 vec![variable, begin, end, quality, output]
             },
             NodeContents::Sample {
-                geometry, parameter, value, output,
+                geometry, interval, parameter, value, samples, last_value, output,
             } => {
-                vec![geometry, parameter, value, output]
+                let mut list = vec![geometry];
+                list.extend(interval.as_mut());
+                list.extend([parameter, value]);
+                list.extend(samples.as_mut());
+                list.extend(last_value.as_mut());
+                list.push(output);
+                list
             },
-            NodeContents::Vector {
-                x, y, z, output
+            NodeContents::NameParameters {
+                geometry, name_1, name_2, name_3, output
             } => {
-                vec![x, y, z, output]
+                vec![geometry, name_1, name_2, name_3, output]
             },
-            NodeContents::Point {
-                x, y, z, output
+            NodeContents::FrenetFrame {
+                geometry, parameter, value, point, tangent, normal, binormal
             } => {
-                vec![x, y, z, output]
+                vec![geometry, parameter, value, point, tangent, normal, binormal]
             },
-            NodeContents::Bezier {
-                p0, p1, p2, p3, quality, output
+            NodeContents::Curvature {
+                geometry, parameter, value, curvature, torsion, circle
             } => {
-                vec![p0, p1, p2, p3, quality, output]
+                vec![geometry, parameter, value, curvature, torsion, circle]
             },
-            NodeContents::Curve {
-                interval, fx, fy, fz, output
+            NodeContents::SurfaceFrame {
+                geometry, parameter_1, value_1, parameter_2, value_2, size, point, u_tangent, v_tangent, normal, plane, e, f, g, gaussian, mean
             } => {
-                vec![interval, fx, fy, fz, output]
+                vec![geometry, parameter_1, value_1, parameter_2, value_2, size, point, u_tangent, v_tangent, normal, plane, e, f, g, gaussian, mean]
             },
-            NodeContents::Surface {
-                interval_1, interval_2, fx, fy, fz, output
+            NodeContents::ArcLength {
+                geometry, output, length
             } => {
-                vec![interval_1, interval_2, fx, fy, fz, output]
+                vec![geometry, output, length]
             },
-            NodeContents::Plane {
-                center, normal, size, output
+            NodeContents::Trajectory {
+                point, variable, begin, end, quality, output
             } => {
-                vec![center, normal, size, output]
+                vec![point, variable, begin, end, quality, output]
             },
-            NodeContents::Transform {
-                geometry, matrix, output
+            NodeContents::CurvePlaneIntersection {
+                geometry, point, normal, output
             } => {
-                vec![geometry, matrix, output]
+                vec![geometry, point, normal, output]
             },
-            NodeContents::Matrix {
-                interval, row_1, row_2, row_3, output,
+            NodeContents::LineSurfaceIntersection {
+                geometry, point, direction, output
             } => {
-                vec![interval, row_1, row_2, row_3, output,]
+                vec![geometry, point, direction, output]
             },
-            NodeContents::RotationMatrix {
-                axis, angle, output,
+            NodeContents::Vector {
+                x, y, z, output
+            } => {
+                vec![x, y, z, output]
+            },
+            NodeContents::VectorFromPoints {
+                from, to, output
+            } => {
+                vec![from, to, output]
+            },
+            NodeContents::TranslatePoint {
+                point, vector, output
+            } => {
+                vec![point, vector, output]
+            },
+            NodeContents::VectorSum {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::VectorDifference {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::ScaleVector {
+                vector, scalar, factor, output
+            } => {
+                vec![vector, scalar, factor, output]
+            },
+            NodeContents::DotProduct {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::CrossProduct {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::NormalizeVector {
+                vector, output
+            } => {
+                vec![vector, output]
+            },
+            NodeContents::VectorLength {
+                vector, output
+            } => {
+                vec![vector, output]
+            },
+            NodeContents::Point {
+                interval, x, y, z, output
+            } => {
+                let mut list: Vec<&mut AttributeID> = interval.as_mut().into_iter().collect();
+                list.extend([x, y, z, output]);
+                list
+            },
+            NodeContents::Bezier {
+                p0, p1, p2, p3, quality, output
+            } => {
+                vec![p0, p1, p2, p3, quality, output]
+            },
+            NodeContents::Spline {
+                p0, p1, p2, p3, p4, p5, p6, p7, kind, degree, weights, quality, output, polygon
+            } => {
+                vec![p0, p1, p2, p3, p4, p5, p6, p7, kind, degree, weights, quality, output, polygon]
+            },
+            NodeContents::SplineSurface {
+                kind, degree, row_1, row_2, row_3, row_4, row_5, row_6, quality, output, control_net
+            } => {
+                vec![kind, degree, row_1, row_2, row_3, row_4, row_5, row_6, quality, output, control_net]
+            },
+            NodeContents::Curve {
+                interval, fx, fy, fz, output
+            } => {
+                vec![interval, fx, fy, fz, output]
+            },
+            NodeContents::CoordinateCurve {
+                interval, coordinates, f1, f2, f3, output
+            } => {
+                vec![interval, coordinates, f1, f2, f3, output]
+            },
+            NodeContents::Surface {
+                interval_1, interval_2, fx, fy, fz, output
+            } => {
+                vec![interval_1, interval_2, fx, fy, fz, output]
+            },
+            NodeContents::CoordinateSurface {
+                interval_1, interval_2, coordinates, f1, f2, f3, output
+            } => {
+                vec![interval_1, interval_2, coordinates, f1, f2, f3, output]
+            },
+            NodeContents::Volume {
+                interval_1, interval_2, interval_3, fx, fy, fz, output
+            } => {
+                vec![interval_1, interval_2, interval_3, fx, fy, fz, output]
+            },
+            NodeContents::Revolution {
+                geometry, axis, axis_point, axis_direction, name, begin, end, quality, output
+            } => {
+                let mut list = vec![geometry, axis, axis_point, axis_direction];
+                list.extend(name.as_mut());
+                list.extend([begin, end, quality, output]);
+                list
+            },
+            NodeContents::Sweep {
+                path, profile, radius, quality, output
+            } => {
+                vec![path, profile, radius, quality, output]
+            },
+            NodeContents::RuledSurface {
+                curve_1, curve_2, name, quality, output
+            } => {
+                let mut list = vec![curve_1, curve_2];
+                list.extend(name.as_mut());
+                list.extend([quality, output]);
+                list
+            },
+            NodeContents::Plane {
+                center, normal, size, output
+            } => {
+                vec![center, normal, size, output]
+            },
+            NodeContents::ImplicitSurface {
+                function, x_min, x_max, y_min, y_max, z_min, z_max, quality, output
+            } => {
+                vec![function, x_min, x_max, y_min, y_max, z_min, z_max, quality, output]
+            },
+            NodeContents::ImplicitCurve {
+                function, x_min, x_max, y_min, y_max, plane, center, normal, quality, output
+            } => {
+                vec![function, x_min, x_max, y_min, y_max, plane, center, normal, quality, output]
+            },
+            NodeContents::Transform {
+                geometry, matrix, output
+            } => {
+                vec![geometry, matrix, output]
+            },
+            NodeContents::Matrix {
+                interval, row_1, row_2, row_3, output,
+            } => {
+                vec![interval, row_1, row_2, row_3, output,]
+            },
+            NodeContents::RotationMatrix {
+                axis, angle, output,
             } => {
                 vec![axis, angle, output,]
             },
@@ -576,21 +1322,61 @@ impl NodeContents {
             } => {
                 vec![vector, output,]
             },
+            NodeContents::ScaleMatrix {
+                x, y, z, output
+            } => {
+                vec![x, y, z, output]
+            },
+            NodeContents::AxisRotationMatrix {
+                axis, angle, output
+            } => {
+                vec![axis, angle, output]
+            },
+            NodeContents::MatrixProduct {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::InverseMatrix {
+                matrix, output
+            } => {
+                vec![matrix, output]
+            },
+            NodeContents::TransposeMatrix {
+                matrix, output
+            } => {
+                vec![matrix, output]
+            },
             NodeContents::Rendering {
-                geometry, thickness, mask, material,
+                geometry, thickness, mask, material, lines, lines_material, colormap, color_function,
             } => {
-                vec![geometry, thickness, mask, material,]
+                let mut list = vec![geometry, thickness, mask, material,];
+                list.extend(lines.as_mut());
+                list.extend(lines_material.as_mut());
+                list.extend(colormap.as_mut());
+                list.extend(color_function.as_mut());
+                list
             },
             NodeContents::VectorRendering {
                 application_point, vector, thickness, material,
             } => {
                 vec![application_point, vector, thickness, material,]
             },
+            NodeContents::VectorField {
+                fx, fy, fz, size, grid, points, scale, thickness, material
+            } => {
+                vec![fx, fy, fz, size, grid, points, scale, thickness, material]
+            },
             NodeContents::Primitive {
                 primitive, size, output,
             } => {
                 vec![primitive, size, output,]
             },
+            NodeContents::MeshFile {
+                file, size, output
+            } => {
+                vec![file, size, output]
+            },
             NodeContents::Group => {
                 unimplemented!()
             }
@@ -607,40 +1393,189 @@ impl NodeContents {
                 vec![variable, begin, end, quality, output]
             },
             NodeContents::Sample {
-                geometry, parameter, value, output,
+                geometry, interval, parameter, value, samples, last_value, output,
+            } => {
+                let mut list = vec![geometry];
+                list.extend(interval);
+                list.extend([parameter, value]);
+                list.extend(samples);
+                list.extend(last_value);
+                list.push(output);
+                list
+            },
+            NodeContents::NameParameters {
+                geometry, name_1, name_2, name_3, output
+            } => {
+                vec![geometry, name_1, name_2, name_3, output]
+            },
+            NodeContents::FrenetFrame {
+                geometry, parameter, value, point, tangent, normal, binormal
             } => {
-                vec![geometry, parameter, value, output]
+                vec![geometry, parameter, value, point, tangent, normal, binormal]
+            },
+            NodeContents::Curvature {
+                geometry, parameter, value, curvature, torsion, circle
+            } => {
+                vec![geometry, parameter, value, curvature, torsion, circle]
+            },
+            NodeContents::SurfaceFrame {
+                geometry, parameter_1, value_1, parameter_2, value_2, size, point, u_tangent, v_tangent, normal, plane, e, f, g, gaussian, mean
+            } => {
+                vec![geometry, parameter_1, value_1, parameter_2, value_2, size, point, u_tangent, v_tangent, normal, plane, e, f, g, gaussian, mean]
+            },
+            NodeContents::ArcLength {
+                geometry, output, length
+            } => {
+                vec![geometry, output, length]
+            },
+            NodeContents::Trajectory {
+                point, variable, begin, end, quality, output
+            } => {
+                vec![point, variable, begin, end, quality, output]
+            },
+            NodeContents::CurvePlaneIntersection {
+                geometry, point, normal, output
+            } => {
+                vec![geometry, point, normal, output]
+            },
+            NodeContents::LineSurfaceIntersection {
+                geometry, point, direction, output
+            } => {
+                vec![geometry, point, direction, output]
             },
             NodeContents::Vector {
                 x, y, z, output
             } => {
                 vec![x, y, z, output]
             },
+            NodeContents::VectorFromPoints {
+                from, to, output
+            } => {
+                vec![from, to, output]
+            },
+            NodeContents::TranslatePoint {
+                point, vector, output
+            } => {
+                vec![point, vector, output]
+            },
+            NodeContents::VectorSum {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::VectorDifference {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::ScaleVector {
+                vector, scalar, factor, output
+            } => {
+                vec![vector, scalar, factor, output]
+            },
+            NodeContents::DotProduct {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::CrossProduct {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::NormalizeVector {
+                vector, output
+            } => {
+                vec![vector, output]
+            },
+            NodeContents::VectorLength {
+                vector, output
+            } => {
+                vec![vector, output]
+            },
             NodeContents::Point {
-                x, y, z, output
+                interval, x, y, z, output
             } => {
-                vec![x, y, z, output]
+                let mut list: Vec<AttributeID> = interval.into_iter().collect();
+                list.extend([x, y, z, output]);
+                list
             },
             NodeContents::Bezier {
                 p0, p1, p2, p3, quality, output
             } => {
                 vec![p0, p1, p2, p3, quality, output]
             },
+            NodeContents::Spline {
+                p0, p1, p2, p3, p4, p5, p6, p7, kind, degree, weights, quality, output, polygon
+            } => {
+                vec![p0, p1, p2, p3, p4, p5, p6, p7, kind, degree, weights, quality, output, polygon]
+            },
+            NodeContents::SplineSurface {
+                kind, degree, row_1, row_2, row_3, row_4, row_5, row_6, quality, output, control_net
+            } => {
+                vec![kind, degree, row_1, row_2, row_3, row_4, row_5, row_6, quality, output, control_net]
+            },
             NodeContents::Curve {
                 interval, fx, fy, fz, output
             } => {
                 vec![interval, fx, fy, fz, output]
             },
+            NodeContents::CoordinateCurve {
+                interval, coordinates, f1, f2, f3, output
+            } => {
+                vec![interval, coordinates, f1, f2, f3, output]
+            },
             NodeContents::Surface {
                 interval_1, interval_2, fx, fy, fz, output
             } => {
                 vec![interval_1, interval_2, fx, fy, fz, output]
             },
+            NodeContents::CoordinateSurface {
+                interval_1, interval_2, coordinates, f1, f2, f3, output
+            } => {
+                vec![interval_1, interval_2, coordinates, f1, f2, f3, output]
+            },
+            NodeContents::Volume {
+                interval_1, interval_2, interval_3, fx, fy, fz, output
+            } => {
+                vec![interval_1, interval_2, interval_3, fx, fy, fz, output]
+            },
+            NodeContents::Revolution {
+                geometry, axis, axis_point, axis_direction, name, begin, end, quality, output
+            } => {
+                let mut list = vec![geometry, axis, axis_point, axis_direction];
+                list.extend(name);
+                list.extend([begin, end, quality, output]);
+                list
+            },
+            NodeContents::Sweep {
+                path, profile, radius, quality, output
+            } => {
+                vec![path, profile, radius, quality, output]
+            },
+            NodeContents::RuledSurface {
+                curve_1, curve_2, name, quality, output
+            } => {
+                let mut list = vec![curve_1, curve_2];
+                list.extend(name);
+                list.extend([quality, output]);
+                list
+            },
             NodeContents::Plane {
                 center, normal, size, output
             } => {
                 vec![center, normal, size, output]
             },
+            NodeContents::ImplicitSurface {
+                function, x_min, x_max, y_min, y_max, z_min, z_max, quality, output
+            } => {
+                vec![function, x_min, x_max, y_min, y_max, z_min, z_max, quality, output]
+            },
+            NodeContents::ImplicitCurve {
+                function, x_min, x_max, y_min, y_max, plane, center, normal, quality, output
+            } => {
+                vec![function, x_min, x_max, y_min, y_max, plane, center, normal, quality, output]
+            },
             NodeContents::Transform {
                 geometry, matrix, output
             } => {
@@ -661,21 +1596,61 @@ impl NodeContents {
             } => {
                 vec![vector, output,]
             },
+            NodeContents::ScaleMatrix {
+                x, y, z, output
+            } => {
+                vec![x, y, z, output]
+            },
+            NodeContents::AxisRotationMatrix {
+                axis, angle, output
+            } => {
+                vec![axis, angle, output]
+            },
+            NodeContents::MatrixProduct {
+                first, second, output
+            } => {
+                vec![first, second, output]
+            },
+            NodeContents::InverseMatrix {
+                matrix, output
+            } => {
+                vec![matrix, output]
+            },
+            NodeContents::TransposeMatrix {
+                matrix, output
+            } => {
+                vec![matrix, output]
+            },
             NodeContents::Rendering {
-                geometry, thickness, mask, material,
+                geometry, thickness, mask, material, lines, lines_material, colormap, color_function,
             } => {
-                vec![geometry, thickness, mask, material]
+                let mut list = vec![geometry, thickness, mask, material];
+                list.extend(lines);
+                list.extend(lines_material);
+                list.extend(colormap);
+                list.extend(color_function);
+                list
             },
             NodeContents::VectorRendering {
                 application_point, vector, thickness, material,
             } => {
                 vec![application_point, vector, thickness, material,]
             },
+            NodeContents::VectorField {
+                fx, fy, fz, size, grid, points, scale, thickness, material
+            } => {
+                vec![fx, fy, fz, size, grid, points, scale, thickness, material]
+            },
             NodeContents::Primitive {
                 primitive, size, output,
             } => {
                 vec![primitive, size, output,]
             },
+            NodeContents::MeshFile {
+                file, size, output
+            } => {
+                vec![file, size, output]
+            },
             NodeContents::Group => {
                 unimplemented!()
             }
@@ -699,132 +1674,538 @@ impl NodeContents {
     pub fn default_sample() -> Self {
         NodeContents::Sample {
             geometry: 0,
-            parameter: 1,
-            value: 2,
-            output: 3,
+            interval: Some(1),
+            parameter: 2,
+            value: 3,
+            samples: Some(5),
+            last_value: Some(6),
+            output: 4,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_vector() -> Self {
-        NodeContents::Vector {
-            x: 0,
-            y: 1,
-            z: 2,
-            output: 3,
+    pub fn default_name_parameters() -> Self {
+        NodeContents::NameParameters {
+            geometry: 0,
+            name_1: 1,
+            name_2: 2,
+            name_3: 3,
+            output: 4,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_point() -> Self {
-        NodeContents::Point {
-            x: 0,
-            y: 1,
-            z: 2,
-            output: 3,
+    pub fn default_frenet_frame() -> Self {
+        NodeContents::FrenetFrame {
+            geometry: 0,
+            parameter: 1,
+            value: 2,
+            point: 3,
+            tangent: 4,
+            normal: 5,
+            binormal: 6,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_bezier() -> Self {
-        NodeContents::Bezier {
-            p0: 0,
-            p1: 1,
-            p2: 2,
-            p3: 3,
-            quality: 4,
-            output: 5,
+    pub fn default_curvature() -> Self {
+        NodeContents::Curvature {
+            geometry: 0,
+            parameter: 1,
+            value: 2,
+            curvature: 3,
+            torsion: 4,
+            circle: 5,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_curve() -> Self {
-        NodeContents::Curve {
-            interval: 0,
-            fx: 1,
-            fy: 2,
-            fz: 3,
-            output: 4,
+    pub fn default_surface_frame() -> Self {
+        NodeContents::SurfaceFrame {
+            geometry: 0,
+            parameter_1: 1,
+            value_1: 2,
+            parameter_2: 3,
+            value_2: 4,
+            size: 5,
+            point: 6,
+            u_tangent: 7,
+            v_tangent: 8,
+            normal: 9,
+            plane: 10,
+            e: 11,
+            f: 12,
+            g: 13,
+            gaussian: 14,
+            mean: 15,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_surface() -> Self {
-        NodeContents::Surface {
-            interval_1: 0,
-            interval_2: 1,
-            fx: 2,
-            fy: 3,
-            fz: 4,
+    pub fn default_arc_length() -> Self {
+        NodeContents::ArcLength {
+            geometry: 0,
+            output: 1,
+            length: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_trajectory() -> Self {
+        NodeContents::Trajectory {
+            point: 0,
+            variable: 1,
+            begin: 2,
+            end: 3,
+            quality: 4,
             output: 5,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_plane() -> Self {
-        NodeContents::Plane {
-            center: 0,
-            normal: 1,
-            size: 2,
+    pub fn default_curve_plane_intersection() -> Self {
+        NodeContents::CurvePlaneIntersection {
+            geometry: 0,
+            point: 1,
+            normal: 2,
             output: 3,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_matrix() -> Self {
-        NodeContents::Matrix {
-            interval: 0,
-            row_1: 1,
-            row_2: 2,
-            row_3: 3,
-            output: 4,
+    pub fn default_line_surface_intersection() -> Self {
+        NodeContents::LineSurfaceIntersection {
+            geometry: 0,
+            point: 1,
+            direction: 2,
+            output: 3,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_rotation_matrix() -> Self {
-        NodeContents::RotationMatrix {
-            axis: 0,
-            angle: 1,
-            output: 2,
+    pub fn default_vector() -> Self {
+        NodeContents::Vector {
+            x: 0,
+            y: 1,
+            z: 2,
+            output: 3,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_translation_matrix() -> Self {
-        NodeContents::TranslationMatrix {
-            vector: 0,
-            output: 1,
+    pub fn default_vector_from_points() -> Self {
+        NodeContents::VectorFromPoints {
+            from: 0,
+            to: 1,
+            output: 2,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_transform() -> Self {
-        NodeContents::Transform {
-            geometry: 0,
-            matrix: 1,
+    pub fn default_translate_point() -> Self {
+        NodeContents::TranslatePoint {
+            point: 0,
+            vector: 1,
             output: 2,
         }
     }
 
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
-    pub fn default_rendering() -> Self {
+    pub fn default_vector_sum() -> Self {
+        NodeContents::VectorSum {
+            first: 0,
+            second: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_vector_difference() -> Self {
+        NodeContents::VectorDifference {
+            first: 0,
+            second: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_scale_vector() -> Self {
+        NodeContents::ScaleVector {
+            vector: 0,
+            scalar: 1,
+            factor: 2,
+            output: 3,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_dot_product() -> Self {
+        NodeContents::DotProduct {
+            first: 0,
+            second: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_cross_product() -> Self {
+        NodeContents::CrossProduct {
+            first: 0,
+            second: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_normalize_vector() -> Self {
+        NodeContents::NormalizeVector {
+            vector: 0,
+            output: 1,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_vector_length() -> Self {
+        NodeContents::VectorLength {
+            vector: 0,
+            output: 1,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_point() -> Self {
+        NodeContents::Point {
+            interval: Some(0),
+            x: 1,
+            y: 2,
+            z: 3,
+            output: 4,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_bezier() -> Self {
+        NodeContents::Bezier {
+            p0: 0,
+            p1: 1,
+            p2: 2,
+            p3: 3,
+            quality: 4,
+            output: 5,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_spline() -> Self {
+        NodeContents::Spline {
+            p0: 0,
+            p1: 1,
+            p2: 2,
+            p3: 3,
+            p4: 4,
+            p5: 5,
+            p6: 6,
+            p7: 7,
+            kind: 8,
+            degree: 9,
+            weights: 10,
+            quality: 11,
+            output: 12,
+            polygon: 13,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_spline_surface() -> Self {
+        NodeContents::SplineSurface {
+            kind: 0,
+            degree: 1,
+            row_1: 2,
+            row_2: 3,
+            row_3: 4,
+            row_4: 5,
+            row_5: 6,
+            row_6: 7,
+            quality: 8,
+            output: 9,
+            control_net: 10,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_curve() -> Self {
+        NodeContents::Curve {
+            interval: 0,
+            fx: 1,
+            fy: 2,
+            fz: 3,
+            output: 4,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_coordinate_curve() -> Self {
+        NodeContents::CoordinateCurve {
+            interval: 0,
+            coordinates: 1,
+            f1: 2,
+            f2: 3,
+            f3: 4,
+            output: 5,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_surface() -> Self {
+        NodeContents::Surface {
+            interval_1: 0,
+            interval_2: 1,
+            fx: 2,
+            fy: 3,
+            fz: 4,
+            output: 5,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_coordinate_surface() -> Self {
+        NodeContents::CoordinateSurface {
+            interval_1: 0,
+            interval_2: 1,
+            coordinates: 2,
+            f1: 3,
+            f2: 4,
+            f3: 5,
+            output: 6,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_volume() -> Self {
+        NodeContents::Volume {
+            interval_1: 0,
+            interval_2: 1,
+            interval_3: 2,
+            fx: 3,
+            fy: 4,
+            fz: 5,
+            output: 6,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_revolution() -> Self {
+        NodeContents::Revolution {
+            geometry: 0,
+            axis: 1,
+            axis_point: 2,
+            axis_direction: 3,
+            name: Some(8),
+            begin: 4,
+            end: 5,
+            quality: 6,
+            output: 7,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_sweep() -> Self {
+        NodeContents::Sweep {
+            path: 0,
+            profile: 1,
+            radius: 2,
+            quality: 3,
+            output: 4,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_ruled_surface() -> Self {
+        NodeContents::RuledSurface {
+            curve_1: 0,
+            curve_2: 1,
+            name: Some(4),
+            quality: 2,
+            output: 3,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_plane() -> Self {
+        NodeContents::Plane {
+            center: 0,
+            normal: 1,
+            size: 2,
+            output: 3,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_implicit_surface() -> Self {
+        NodeContents::ImplicitSurface {
+            function: 0,
+            x_min: 1,
+            x_max: 2,
+            y_min: 3,
+            y_max: 4,
+            z_min: 5,
+            z_max: 6,
+            quality: 7,
+            output: 8,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_implicit_curve() -> Self {
+        NodeContents::ImplicitCurve {
+            function: 0,
+            x_min: 1,
+            x_max: 2,
+            y_min: 3,
+            y_max: 4,
+            plane: 5,
+            center: 6,
+            normal: 7,
+            quality: 8,
+            output: 9,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_matrix() -> Self {
+        NodeContents::Matrix {
+            interval: 0,
+            row_1: 1,
+            row_2: 2,
+            row_3: 3,
+            output: 4,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_rotation_matrix() -> Self {
+        NodeContents::RotationMatrix {
+            axis: 0,
+            angle: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_translation_matrix() -> Self {
+        NodeContents::TranslationMatrix {
+            vector: 0,
+            output: 1,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_scale_matrix() -> Self {
+        NodeContents::ScaleMatrix {
+            x: 0,
+            y: 1,
+            z: 2,
+            output: 3,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_axis_rotation_matrix() -> Self {
+        NodeContents::AxisRotationMatrix {
+            axis: 0,
+            angle: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_matrix_product() -> Self {
+        NodeContents::MatrixProduct {
+            first: 0,
+            second: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_inverse_matrix() -> Self {
+        NodeContents::InverseMatrix {
+            matrix: 0,
+            output: 1,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_transpose_matrix() -> Self {
+        NodeContents::TransposeMatrix {
+            matrix: 0,
+            output: 1,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_transform() -> Self {
+        NodeContents::Transform {
+            geometry: 0,
+            matrix: 1,
+            output: 2,
+        }
+    }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_rendering() -> Self {
         NodeContents::Rendering {
             geometry: 0,
             thickness: 1,
             mask: 2,
             material: 3,
+            lines: Some(4),
+            lines_material: Some(5),
+            colormap: Some(6),
+            color_function: Some(7),
         }
     }
 
@@ -839,6 +2220,22 @@ impl NodeContents {
         }
     }
 
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_vector_field() -> Self {
+        NodeContents::VectorField {
+            fx: 0,
+            fy: 1,
+            fz: 2,
+            size: 3,
+            grid: 4,
+            points: 5,
+            scale: 6,
+            thickness: 7,
+            material: 8,
+        }
+    }
+
     // NOTE: if you modify this function, also modify the order in which we return
     // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
     pub fn default_primitive() -> Self {
@@ -848,6 +2245,16 @@ impl NodeContents {
             output: 2,
         }
     }
+
+    // NOTE: if you modify this function, also modify the order in which we return
+    // attributes in the get_attribute_list_mut() and get_attribute_list() functions!
+    pub fn default_mesh_file() -> Self {
+        NodeContents::MeshFile {
+            file: 0,
+            size: 1,
+            output: 2,
+        }
+    }
 }
 
 
@@ -864,7 +2271,7 @@ impl Node {
         &self.contents
     }
 
-    pub fn render(&mut self, ui: &imgui::Ui<'_>, availables: &Availables, attributes: &mut Vec<Option<Attribute>>) -> bool {
+    pub fn render(&mut self, ui: &imgui::Ui<'_>, availables: &Availables, scalar_values: &BTreeMap<AttributeID, f32>, color_ranges: &BTreeMap<AttributeID, [f32; 2]>, attributes: &mut Vec<Option<Attribute>>) -> bool {
         imnodes::BeginNodeTitleBar();
             ui.text(&self.title);
             // handle error reporting
@@ -885,7 +2292,7 @@ impl Node {
         imnodes::EndNodeTitleBar();
         // TODO: not sure if we will be able to use the get_attribute_list()
         // when we introduce the Group kind node in the future...
-        Attribute::render_list(ui, availables, attributes, self.contents.get_attribute_list())
+        Attribute::render_list(ui, availables, scalar_values, color_ranges, attributes, self.contents.get_attribute_list())
     }
 
     pub fn get_input_nodes(&self, graph: &NodeGraph) -> Vec::<NodeID> {
@@ -1014,7 +2421,13 @@ pub struct NodeGraph {
     editing_node: Option<NodeID>,
     #[serde(skip)]
     last_edit_timestamp: f64,
-}
+    // the values of the scalar outputs, as read back after the last compute
+    #[serde(skip)]
+    scalar_values: BTreeMap<AttributeID, f32>,
+    // the ranges of values shown by the legends of the colormaps
+    #[serde(skip)]
+    color_ranges: BTreeMap<AttributeID, [f32; 2]>,
+}
 
 enum PairInfo {
     FirstInputSecondOutput,
@@ -1035,6 +2448,8 @@ impl Default for NodeGraph {
             editing_node: None,
             zoom_level: 0,
             last_edit_timestamp: 0.0,
+            scalar_values: BTreeMap::new(),
+            color_ranges: BTreeMap::new(),
         }
     }
 }
@@ -1205,7 +2620,7 @@ impl NodeGraph {
         for (idx, maybe_node) in self.nodes.iter_mut().enumerate() {
             if let Some(node) = maybe_node.as_mut() {
                 imnodes::BeginNode(idx as NodeID);
-                if node.render(ui, availables, &mut self.attributes) {
+                if node.render(ui, availables, &self.scalar_values, &self.color_ranges, &mut self.attributes) {
                     self.last_edit_timestamp = ui.time();
                 }
                 imnodes::EndNode();
@@ -1334,22 +2749,77 @@ impl NodeGraph {
                     self.add_curve_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Cylindrical Curve").build(ui) {
+                    self.add_coordinate_curve_node(node_pos, CoordinateSystem::Cylindrical);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Spherical Curve").build(ui) {
+                    self.add_coordinate_curve_node(node_pos, CoordinateSystem::Spherical);
+                    request_savestate = Some(ui.time());
+                }
                 if MenuItem::new("Bezier Curve").build(ui) {
                     self.add_bezier_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Spline Curve").build(ui) {
+                    self.add_spline_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("up to 8 control points, linked to the pins P0 to P7.\nUnlinked pins are skipped, and the optional weights\nare one for each linked point, separated by commas.");
+                }
+                if MenuItem::new("Spline Surface").build(ui) {
+                    self.add_spline_surface_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
                 if MenuItem::new("Surface").build(ui) {
                     self.add_surface_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Cylindrical Surface").build(ui) {
+                    self.add_coordinate_surface_node(node_pos, CoordinateSystem::Cylindrical);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Spherical Surface").build(ui) {
+                    self.add_coordinate_surface_node(node_pos, CoordinateSystem::Spherical);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Surface of Revolution").build(ui) {
+                    self.add_revolution_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Sweep").build(ui) {
+                    self.add_sweep_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Ruled Surface").build(ui) {
+                    self.add_ruled_surface_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Volume").build(ui) {
+                    self.add_volume_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
                 if MenuItem::new("Plane").build(ui) {
                     self.add_plane_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Implicit Surface").build(ui) {
+                    self.add_implicit_surface_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Implicit Curve").build(ui) {
+                    self.add_implicit_curve_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
                 if MenuItem::new("Primitive").build(ui) {
                     self.add_primitive_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Mesh File").build(ui) {
+                    self.add_mesh_file_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
             }); // Geometries menu ends here
 
             ui.menu("Parameters", || {
@@ -1361,8 +2831,41 @@ impl NodeGraph {
                     self.add_parameter_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Name Parameters").build(ui) {
+                    self.add_name_parameters_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Trajectory").build(ui) {
+                    self.add_trajectory_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
             }); // Geometries menu ends here
 
+            ui.menu("Differential Geometry", || {
+                if MenuItem::new("Frenet Frame").build(ui) {
+                    self.add_frenet_frame_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("the derivatives are exact for Curve, Cylindrical Curve\nand Spherical Curve nodes. Other curves, e.g. transformed\nor renamed ones, use differences between their points.");
+                }
+                if MenuItem::new("Curvature").build(ui) {
+                    self.add_curvature_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("the derivatives are exact for Curve, Cylindrical Curve\nand Spherical Curve nodes. Other curves, e.g. transformed\nor renamed ones, use differences between their points.");
+                }
+                if MenuItem::new("Surface Frame").build(ui) {
+                    self.add_surface_frame_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Arc Length").build(ui) {
+                    self.add_arc_length_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+            }); // Differential Geometry menu ends here
+
             ui.menu("Transformations", || {
                 if MenuItem::new("Generic Matrix").build(ui) {
                     self.add_matrix_node(node_pos);
@@ -1376,12 +2879,82 @@ impl NodeGraph {
                     self.add_translation_matrix_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
+                if MenuItem::new("Scale Matrix").build(ui) {
+                    self.add_scale_matrix_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Axis Rotation Matrix").build(ui) {
+                    self.add_axis_rotation_matrix_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Matrix Product").build(ui) {
+                    self.add_matrix_product_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Inverse Matrix").build(ui) {
+                    self.add_inverse_matrix_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Transpose Matrix").build(ui) {
+                    self.add_transpose_matrix_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
                 if MenuItem::new("Transform").build(ui) {
                     self.add_transform_node(node_pos);
                     request_savestate = Some(ui.time());
                 }
             }); // Transformations menu ends here
 
+            ui.menu("Vector Algebra", || {
+                if MenuItem::new("Vector from Points").build(ui) {
+                    self.add_vector_from_points_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Translate Point").build(ui) {
+                    self.add_translate_point_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Vector Sum").build(ui) {
+                    self.add_vector_sum_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Vector Difference").build(ui) {
+                    self.add_vector_difference_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Scale Vector").build(ui) {
+                    self.add_scale_vector_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Dot Product").build(ui) {
+                    self.add_dot_product_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Cross Product").build(ui) {
+                    self.add_cross_product_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Normalize").build(ui) {
+                    self.add_normalize_vector_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Length").build(ui) {
+                    self.add_vector_length_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+            }); // Vector Algebra menu ends here
+
+            ui.menu("Intersections", || {
+                if MenuItem::new("Curve-Plane Intersection").build(ui) {
+                    self.add_curve_plane_intersection_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+                if MenuItem::new("Line-Surface Intersection").build(ui) {
+                    self.add_line_surface_intersection_node(node_pos);
+                    request_savestate = Some(ui.time());
+                }
+            }); // Intersections menu ends here
+
             if MenuItem::new("Point").build(ui) {
                 self.add_point_node(node_pos);
                 request_savestate = Some(ui.time());
@@ -1398,6 +2971,10 @@ impl NodeGraph {
                 self.add_vector_rendering_node(node_pos);
                 request_savestate = Some(ui.time());
             }
+            if MenuItem::new("Vector Field").build(ui) {
+                self.add_vector_field_node(node_pos);
+                request_savestate = Some(ui.time());
+            }
         }); // "Add" closure ends here
         style_token.pop();
 
@@ -1494,6 +3071,14 @@ impl NodeGraph {
          }
     }
 
+    pub fn set_scalar_values(&mut self, scalar_values: BTreeMap<AttributeID, f32>) {
+        self.scalar_values = scalar_values;
+    }
+
+    pub fn set_color_ranges(&mut self, color_ranges: BTreeMap<AttributeID, [f32; 2]>) {
+        self.color_ranges = color_ranges;
+    }
+
     pub fn clear_all_errors(&mut self) {
         for node in self.nodes.iter_mut().flatten() {
             node.error = None;
@@ -1605,6 +3190,30 @@ impl NodeGraph {
         }
     }
 
+    pub fn get_attribute_as_mesh_file(&self, attribute_id: AttributeID) -> Option<(&str, Option<&MeshData>)> {
+        // first, we need to check if the attribute_id actually exists in our attributes map
+        let attribute_slot = self.attributes.get(attribute_id as usize)?;
+        // then, if the slot is here, we need to check if something is in the slot.
+        let attribute = attribute_slot.as_ref()?;
+        // finally, if the attribute exists, we need to check if it is a MeshFile.
+        // The embedded mesh is only used if the user still wants it to be embedded
+        if let AttributeContents::MeshFile{ path, embed, embedded } = &attribute.contents {
+            Some((path.as_str(), embedded.as_ref().filter(|_| *embed)))
+        } else {
+            None
+        }
+    }
+
+    // the files referenced by the Mesh File nodes, which are updated when the scene is saved
+    pub fn get_mesh_files_mut(&mut self) -> impl Iterator<Item = (&mut String, bool, &mut Option<MeshData>)> {
+        self.attributes.iter_mut()
+            .flatten()
+            .filter_map(|attribute| match &mut attribute.contents {
+                AttributeContents::MeshFile{ path, embed, embedded } => Some((path, *embed, embedded)),
+                _ => None,
+            })
+    }
+
     pub fn get_attribute_as_usize(&self, attribute_id: AttributeID) -> Option<usize> {
         // first, we need to check if the attribute_id actually exists in our attributes map
         let attribute_slot = self.attributes.get(attribute_id as usize)?;
@@ -1678,6 +3287,71 @@ impl NodeGraph {
         }
     }
 
+    pub fn get_attribute_as_plane(&self, attribute_id: AttributeID) -> Option<CoordinatePlane> {
+        // first, we need to check if the attribute_id actually exists in our attributes map
+        let attribute_slot = self.attributes.get(attribute_id as usize)?;
+        // then, if the slot is here, we need to check if something is in the slot.
+        let attribute = attribute_slot.as_ref()?;
+        // if it exists, then we need to check if it is a PlaneSelect attribute.
+        if let AttributeContents::PlaneSelect{ plane } = attribute.contents {
+            Some(plane)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_attribute_as_spline_kind(&self, attribute_id: AttributeID) -> Option<SplineKind> {
+        // first, we need to check if the attribute_id actually exists in our attributes map
+        let attribute_slot = self.attributes.get(attribute_id as usize)?;
+        // then, if the slot is here, we need to check if something is in the slot.
+        let attribute = attribute_slot.as_ref()?;
+        // if it exists, then we need to check if it is a SplineKindSelect attribute.
+        if let AttributeContents::SplineKindSelect{ kind } = attribute.contents {
+            Some(kind)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_attribute_as_field_grid(&self, attribute_id: AttributeID) -> Option<FieldGrid> {
+        // first, we need to check if the attribute_id actually exists in our attributes map
+        let attribute_slot = self.attributes.get(attribute_id as usize)?;
+        // then, if the slot is here, we need to check if something is in the slot.
+        let attribute = attribute_slot.as_ref()?;
+        // if it exists, then we need to check if it is a GridSelect attribute.
+        if let AttributeContents::GridSelect{ grid } = attribute.contents {
+            Some(grid)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_attribute_as_colormap(&self, attribute_id: AttributeID) -> Option<Colormap> {
+        // first, we need to check if the attribute_id actually exists in our attributes map
+        let attribute_slot = self.attributes.get(attribute_id as usize)?;
+        // then, if the slot is here, we need to check if something is in the slot.
+        let attribute = attribute_slot.as_ref()?;
+        // if it exists, then we need to check if it is a ColormapSelect attribute.
+        if let AttributeContents::ColormapSelect{ colormap } = attribute.contents {
+            Some(colormap)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_attribute_as_coordinate_system(&self, attribute_id: AttributeID) -> Option<CoordinateSystem> {
+        // first, we need to check if the attribute_id actually exists in our attributes map
+        let attribute_slot = self.attributes.get(attribute_id as usize)?;
+        // then, if the slot is here, we need to check if something is in the slot.
+        let attribute = attribute_slot.as_ref()?;
+        // if it exists, then we need to check if it is a Coordinates attribute.
+        if let AttributeContents::Coordinates{ system } = attribute.contents {
+            Some(system)
+        } else {
+            None
+        }
+    }
+
     pub fn get_attribute_as_linked_output(&self, attribute_id: AttributeID) -> Option<AttributeID> {
         // first, we need to check if the attribute_id actually exists in our attributes map
         let attribute_slot = self.attributes.get(attribute_id as usize)?;
@@ -1799,6 +3473,10 @@ impl NodeGraph {
                 label: String::from("geometry"),
                 kind: DataKind::Geometry,
             },
+            AttributeContents::InputPin {
+                label: String::from("interval"),
+                kind: DataKind::Interval,
+            },
             AttributeContents::Text {
                 label: String::from("param:"),
                 string: String::from(""),
@@ -1810,162 +3488,1075 @@ impl NodeGraph {
             AttributeContents::OutputPin {
                 label: String::from("output"),
                 kind: DataKind::Geometry,
-            }
+            },
+            AttributeContents::IntSlider {
+                label: String::from("samples:"),
+                value: 1,
+                mode: SliderMode::IntRange(1, 32),
+            },
+            AttributeContents::Text {
+                label: String::from("to:"),
+                string: String::from(""),
+            },
         ];
         let node_contents = NodeContents::default_sample();
         self.insert_node("Sample Parameter".into(), position, node_contents, attributes_contents)
     }
 
-    pub fn add_vector_node(&mut self, position: [f32; 2]) -> NodeID {
+    pub fn add_name_parameters_node(&mut self, position: [f32; 2]) -> NodeID {
         // NOTE: the order here is important: the attributes here
-        // must appear in the same order as they do in the default_vector() function!
+        // must appear in the same order as they do in the default_name_parameters() function!
         let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            },
             AttributeContents::Text {
-                label: String::from("x"),
-                string: String::from("0.0"),
+                label: String::from("param 1:"),
+                string: String::from(""),
             },
             AttributeContents::Text {
-                label: String::from("y"),
-                string: String::from("0.0"),
+                label: String::from("param 2:"),
+                string: String::from(""),
             },
             AttributeContents::Text {
-                label: String::from("z"),
-                string: String::from("0.0"),
+                label: String::from("param 3:"),
+                string: String::from(""),
             },
             AttributeContents::OutputPin {
-                label: String::from("vector"),
+                label: String::from("output"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_name_parameters();
+        self.insert_node("Name Parameters".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_frenet_frame_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_frenet_frame() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::Text {
+                label: String::from("param:"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("value:"),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("tangent"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("normal"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("binormal"),
                 kind: DataKind::Vector,
             }
         ];
-        let node_contents = NodeContents::default_vector();
-        self.insert_node("Vector".into(), position, node_contents, attributes_contents)
+        let node_contents = NodeContents::default_frenet_frame();
+        self.insert_node("Frenet Frame".into(), position, node_contents, attributes_contents)
     }
 
-    pub fn add_point_node(&mut self, position: [f32; 2]) -> NodeID {
+    pub fn add_curvature_node(&mut self, position: [f32; 2]) -> NodeID {
         // NOTE: the order here is important: the attributes here
-        // must appear in the same order as they do in the default_point() function!
+        // must appear in the same order as they do in the default_curvature() function!
         let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            },
             AttributeContents::Text {
-                label: String::from("x"),
-                string: String::from("0.0"),
+                label: String::from("param:"),
+                string: String::from(""),
             },
             AttributeContents::Text {
-                label: String::from("y"),
-                string: String::from("0.0"),
+                label: String::from("value:"),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("curvature"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("torsion"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("osculating circle"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_curvature();
+        self.insert_node("Curvature".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_surface_frame_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_surface_frame() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
             },
             AttributeContents::Text {
-                label: String::from("z"),
-                string: String::from("0.0"),
+                label: String::from("param 1:"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("value 1:"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("param 2:"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("value 2:"),
+                string: String::from(""),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("plane size:"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("u tangent"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("v tangent"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("normal"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("tangent plane"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("E"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("F"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("G"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("gaussian curvature"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("mean curvature"),
+                kind: DataKind::Scalar,
+            }
+        ];
+        let node_contents = NodeContents::default_surface_frame();
+        self.insert_node("Surface Frame".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_arc_length_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_arc_length() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("curve"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("length"),
+                kind: DataKind::Scalar,
+            }
+        ];
+        let node_contents = NodeContents::default_arc_length();
+        self.insert_node("Arc Length".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_trajectory_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_trajectory() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::Text {
+                label: String::from("global:"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("begin:"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("end:"),
+                string: String::from(""),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("trajectory"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_trajectory();
+        self.insert_node("Trajectory".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_curve_plane_intersection_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_curve_plane_intersection() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("curve"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("plane point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("plane normal"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("intersections"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_curve_plane_intersection();
+        self.insert_node("Curve-Plane Intersection".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_line_surface_intersection_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_line_surface_intersection() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("surface"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("line point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("line direction"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("intersections"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_line_surface_intersection();
+        self.insert_node("Line-Surface Intersection".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_vector_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_vector() function!
+        let attributes_contents = vec![
+            AttributeContents::Text {
+                label: String::from("x"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("y"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("z"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("vector"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_vector();
+        self.insert_node("Vector".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_vector_from_points_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_vector_from_points() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("from"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("to"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_vector_from_points();
+        self.insert_node("Vector from Points".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_translate_point_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_translate_point() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("vector"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_translate_point();
+        self.insert_node("Translate Point".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_vector_sum_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_vector_sum() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("first"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::InputPin {
+                label: String::from("second"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_vector_sum();
+        self.insert_node("Vector Sum".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_vector_difference_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_vector_difference() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("first"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::InputPin {
+                label: String::from("second"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_vector_difference();
+        self.insert_node("Vector Difference".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_scale_vector_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_scale_vector() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("vector"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::InputPin {
+                label: String::from("scalar"),
+                kind: DataKind::Scalar,
+            },
+            AttributeContents::Text {
+                label: String::from("factor:"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_scale_vector();
+        self.insert_node("Scale Vector".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_dot_product_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_dot_product() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("first"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::InputPin {
+                label: String::from("second"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Scalar,
+            }
+        ];
+        let node_contents = NodeContents::default_dot_product();
+        self.insert_node("Dot Product".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_cross_product_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_cross_product() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("first"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::InputPin {
+                label: String::from("second"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_cross_product();
+        self.insert_node("Cross Product".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_normalize_vector_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_normalize_vector() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("vector"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Vector,
+            }
+        ];
+        let node_contents = NodeContents::default_normalize_vector();
+        self.insert_node("Normalize".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_vector_length_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_vector_length() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("vector"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Scalar,
+            }
+        ];
+        let node_contents = NodeContents::default_vector_length();
+        self.insert_node("Length".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_point_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_point() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("interval"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::Text {
+                label: String::from("x"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("y"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("z"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_point();
+        self.insert_node("Point".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_bezier_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_bezier() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("P0"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P1"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P2"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P3"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_bezier();
+        self.insert_node("Bézier".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_spline_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_spline() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("P0"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P1"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P2"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P3"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P4"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P5"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P6"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("P7"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::SplineKindSelect {
+                kind: SplineKind::Bezier,
+            },
+            AttributeContents::IntSlider {
+                label: String::from("degree"),
+                value: 3,
+                mode: SliderMode::IntRange(1, 7),
+            },
+            AttributeContents::Text {
+                label: String::from("weights"),
+                string: String::from(""),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("polygon"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_spline();
+        self.insert_node("Spline".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_spline_surface_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_spline_surface() function!
+        let attributes_contents = vec![
+            AttributeContents::SplineKindSelect {
+                kind: SplineKind::Bezier,
+            },
+            AttributeContents::IntSlider {
+                label: String::from("degree"),
+                value: 3,
+                mode: SliderMode::IntRange(1, 7),
+            },
+            AttributeContents::Text {
+                label: String::from("row 1"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("row 2"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("row 3"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("row 4"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("row 5"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("row 6"),
+                string: String::from(""),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("control net"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_spline_surface();
+        self.insert_node("Spline Surface".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_curve_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_curve() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("interval"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::Text {
+                label: String::from("fx"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("fy"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("fz"),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_curve();
+        self.insert_node("Curve".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_coordinate_curve_node(&mut self, position: [f32; 2], system: CoordinateSystem) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_coordinate_curve() function!
+        let [label_1, label_2, label_3] = system.labels();
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("interval"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::Coordinates {
+                system,
+            },
+            AttributeContents::Text {
+                label: String::from(label_1),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from(label_2),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from(label_3),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let title = match system {
+            CoordinateSystem::Cartesian => "Curve",
+            CoordinateSystem::Cylindrical => "Cylindrical Curve",
+            CoordinateSystem::Spherical => "Spherical Curve",
+        };
+        let node_contents = NodeContents::default_coordinate_curve();
+        self.insert_node(title.into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_surface_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_surface function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("interval 1"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::InputPin {
+                label: String::from("interval 2"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::Text {
+                label: String::from("fx"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("fy"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("fz"),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_surface();
+        self.insert_node("Surface".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_coordinate_surface_node(&mut self, position: [f32; 2], system: CoordinateSystem) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_coordinate_surface() function!
+        let [label_1, label_2, label_3] = system.labels();
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("interval 1"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::InputPin {
+                label: String::from("interval 2"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::Coordinates {
+                system,
+            },
+            AttributeContents::Text {
+                label: String::from(label_1),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from(label_2),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from(label_3),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let title = match system {
+            CoordinateSystem::Cartesian => "Surface",
+            CoordinateSystem::Cylindrical => "Cylindrical Surface",
+            CoordinateSystem::Spherical => "Spherical Surface",
+        };
+        let node_contents = NodeContents::default_coordinate_surface();
+        self.insert_node(title.into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_volume_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_volume function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("interval 1"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::InputPin {
+                label: String::from("interval 2"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::InputPin {
+                label: String::from("interval 3"),
+                kind: DataKind::Interval,
+            },
+            AttributeContents::Text {
+                label: String::from("fx"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("fy"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("fz"),
+                string: String::from(""),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_volume();
+        self.insert_node("Volume".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_revolution_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_revolution() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("curve"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::AxisSelect {
+                axis: Axis::Z,
+            },
+            AttributeContents::InputPin {
+                label: String::from("axis point"),
+                kind: DataKind::Geometry,
+            },
+            AttributeContents::InputPin {
+                label: String::from("axis direction"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::Text {
+                label: String::from("begin"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("end"),
+                string: String::from("2*pi"),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
             },
             AttributeContents::OutputPin {
                 label: String::from("geometry"),
                 kind: DataKind::Geometry,
+            },
+            AttributeContents::Text {
+                label: String::from(" name"),
+                string: String::from("theta"),
             }
         ];
-        let node_contents = NodeContents::default_point();
-        self.insert_node("Point".into(), position, node_contents, attributes_contents)
+        let node_contents = NodeContents::default_revolution();
+        self.insert_node("Surface of Revolution".into(), position, node_contents, attributes_contents)
     }
 
-    pub fn add_bezier_node(&mut self, position: [f32; 2]) -> NodeID {
+    pub fn add_sweep_node(&mut self, position: [f32; 2]) -> NodeID {
         // NOTE: the order here is important: the attributes here
-        // must appear in the same order as they do in the default_bezier() function!
+        // must appear in the same order as they do in the default_sweep() function!
         let attributes_contents = vec![
             AttributeContents::InputPin {
-                label: String::from("P0"),
+                label: String::from("path"),
                 kind: DataKind::Geometry,
             },
             AttributeContents::InputPin {
-                label: String::from("P1"),
+                label: String::from("profile"),
                 kind: DataKind::Geometry,
             },
+            AttributeContents::Text {
+                label: String::from("radius"),
+                string: String::from("0.2"),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 2,
+                mode: SliderMode::IntRange(1, 16),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_sweep();
+        self.insert_node("Sweep".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_ruled_surface_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_ruled_surface() function!
+        let attributes_contents = vec![
             AttributeContents::InputPin {
-                label: String::from("P2"),
+                label: String::from("curve 1"),
                 kind: DataKind::Geometry,
             },
             AttributeContents::InputPin {
-                label: String::from("P3"),
+                label: String::from("curve 2"),
                 kind: DataKind::Geometry,
             },
             AttributeContents::IntSlider {
                 label: String::from("quality"),
-                value: 4,
+                value: 1,
                 mode: SliderMode::IntRange(1, 16),
             },
             AttributeContents::OutputPin {
                 label: String::from("geometry"),
                 kind: DataKind::Geometry,
+            },
+            AttributeContents::Text {
+                label: String::from(" name"),
+                string: String::from("s"),
             }
         ];
-        let node_contents = NodeContents::default_bezier();
-        self.insert_node("Bézier".into(), position, node_contents, attributes_contents)
+        let node_contents = NodeContents::default_ruled_surface();
+        self.insert_node("Ruled Surface".into(), position, node_contents, attributes_contents)
     }
 
-    pub fn add_curve_node(&mut self, position: [f32; 2]) -> NodeID {
-        // NOTE: the order here is important: the attributes here
-        // must appear in the same order as they do in the default_curve() function!
+    pub fn add_plane_node(&mut self, position: [f32; 2]) -> NodeID {
         let attributes_contents = vec![
             AttributeContents::InputPin {
-                label: String::from("interval"),
-                kind: DataKind::Interval,
-            },
-            AttributeContents::Text {
-                label: String::from("fx"),
-                string: String::from(""),
+                label: String::from("point"),
+                kind: DataKind::Geometry,
             },
-            AttributeContents::Text {
-                label: String::from("fy"),
-                string: String::from(""),
+            AttributeContents::InputPin {
+                label: String::from("normal"),
+                kind: DataKind::Vector,
             },
-            AttributeContents::Text {
-                label: String::from("fz"),
-                string: String::from(""),
+            AttributeContents::IntSlider {
+                label: String::from("size:"),
+                value: 4,
+                mode: SliderMode::IntRange(1, 16),
             },
             AttributeContents::OutputPin {
-                label: String::from("geometry"),
+                label: String::from("output"),
                 kind: DataKind::Geometry,
             }
         ];
-        let node_contents = NodeContents::default_curve();
-        self.insert_node("Curve".into(), position, node_contents, attributes_contents)
+        let node_contents = NodeContents::default_plane();
+        self.insert_node("Plane".into(), position, node_contents, attributes_contents)
     }
 
-    pub fn add_surface_node(&mut self, position: [f32; 2]) -> NodeID {
+    pub fn add_implicit_surface_node(&mut self, position: [f32; 2]) -> NodeID {
         // NOTE: the order here is important: the attributes here
-        // must appear in the same order as they do in the default_surface function!
+        // must appear in the same order as they do in the default_implicit_surface() function!
         let attributes_contents = vec![
-            AttributeContents::InputPin {
-                label: String::from("interval 1"),
-                kind: DataKind::Interval,
+            AttributeContents::Text {
+                label: String::from("F(x,y,z)"),
+                string: String::from(""),
             },
-            AttributeContents::InputPin {
-                label: String::from("interval 2"),
-                kind: DataKind::Interval,
+            AttributeContents::Text {
+                label: String::from("x min"),
+                string: String::from("-1.0"),
             },
             AttributeContents::Text {
-                label: String::from("fx"),
-                string: String::from(""),
+                label: String::from("x max"),
+                string: String::from("1.0"),
             },
             AttributeContents::Text {
-                label: String::from("fy"),
-                string: String::from(""),
+                label: String::from("y min"),
+                string: String::from("-1.0"),
             },
             AttributeContents::Text {
-                label: String::from("fz"),
-                string: String::from(""),
+                label: String::from("y max"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("z min"),
+                string: String::from("-1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("z max"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("quality"),
+                value: 3,
+                mode: SliderMode::IntRange(1, 6),
             },
             AttributeContents::OutputPin {
                 label: String::from("geometry"),
                 kind: DataKind::Geometry,
             }
         ];
-        let node_contents = NodeContents::default_surface();
-        self.insert_node("Surface".into(), position, node_contents, attributes_contents)
+        let node_contents = NodeContents::default_implicit_surface();
+        self.insert_node("Implicit Surface".into(), position, node_contents, attributes_contents)
     }
 
-    pub fn add_plane_node(&mut self, position: [f32; 2]) -> NodeID {
+    pub fn add_implicit_curve_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_implicit_curve() function!
         let attributes_contents = vec![
+            AttributeContents::Text {
+                label: String::from("F(x,y)"),
+                string: String::from(""),
+            },
+            AttributeContents::Text {
+                label: String::from("x min"),
+                string: String::from("-1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("x max"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("y min"),
+                string: String::from("-1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("y max"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::PlaneSelect {
+                plane: CoordinatePlane::XY,
+            },
             AttributeContents::InputPin {
-                label: String::from("point"),
+                label: String::from("center"),
                 kind: DataKind::Geometry,
             },
             AttributeContents::InputPin {
@@ -1973,17 +4564,17 @@ impl NodeGraph {
                 kind: DataKind::Vector,
             },
             AttributeContents::IntSlider {
-                label: String::from("size:"),
-                value: 4,
-                mode: SliderMode::IntRange(1, 16),
+                label: String::from("quality"),
+                value: 3,
+                mode: SliderMode::IntRange(1, 6),
             },
             AttributeContents::OutputPin {
-                label: String::from("output"),
+                label: String::from("geometry"),
                 kind: DataKind::Geometry,
             }
         ];
-        let node_contents = NodeContents::default_plane();
-        self.insert_node("Plane".into(), position, node_contents, attributes_contents)
+        let node_contents = NodeContents::default_implicit_curve();
+        self.insert_node("Implicit Curve".into(), position, node_contents, attributes_contents)
     }
 
     pub fn add_rendering_node(&mut self, position: [f32; 2]) -> NodeID {
@@ -2003,6 +4594,21 @@ impl NodeGraph {
             AttributeContents::Material {
                 selected: 0,
             },
+            AttributeContents::IntSlider {
+                label: String::from("u-v lines:"),
+                value: 0,
+                mode: SliderMode::IntRange(0, 16),
+            },
+            AttributeContents::Material {
+                selected: 0,
+            },
+            AttributeContents::ColormapSelect {
+                colormap: Colormap::Material,
+            },
+            AttributeContents::Text {
+                label: String::from("function:"),
+                string: String::from("z"),
+            },
         ];
         let node_contents = NodeContents::default_rendering();
         self.insert_node("Rendering".into(), position, node_contents, attributes_contents)
@@ -2031,6 +4637,51 @@ impl NodeGraph {
         self.insert_node("Vector Rendering".into(), position, node_contents, attributes_contents)
     }
 
+    pub fn add_vector_field_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_vector_field() function!
+        let attributes_contents = vec![
+            AttributeContents::Text {
+                label: String::from("fx"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("fy"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("fz"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("size:"),
+                string: String::from("2.0"),
+            },
+            AttributeContents::GridSelect {
+                grid: FieldGrid::Space,
+            },
+            AttributeContents::IntSlider {
+                label: String::from("points:"),
+                value: 5,
+                mode: SliderMode::IntRange(2, 16),
+            },
+            AttributeContents::Text {
+                label: String::from("scale:"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::IntSlider {
+                label: String::from("thickness:"),
+                value: 3,
+                mode: SliderMode::SizeLabels,
+            },
+            AttributeContents::Material {
+                selected: 0,
+            }
+        ];
+        let node_contents = NodeContents::default_vector_field();
+        self.insert_node("Vector Field".into(), position, node_contents, attributes_contents)
+    }
+
     pub fn add_primitive_node(&mut self, position: [f32; 2]) -> NodeID {
         let attributes_contents = vec![
             AttributeContents::PrimitiveKind {
@@ -2050,6 +4701,28 @@ impl NodeGraph {
         self.insert_node("Primitive".into(), position, node_contents, attributes_contents)
     }
 
+    pub fn add_mesh_file_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_mesh_file() function!
+        let attributes_contents = vec![
+            AttributeContents::MeshFile {
+                path: String::new(),
+                embed: false,
+                embedded: None,
+            },
+            AttributeContents::Text {
+                label: String::from("size:"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("geometry"),
+                kind: DataKind::Geometry,
+            }
+        ];
+        let node_contents = NodeContents::default_mesh_file();
+        self.insert_node("Mesh File".into(), position, node_contents, attributes_contents)
+    }
+
     pub fn add_transform_node(&mut self, position: [f32; 2]) -> NodeID {
         let attributes_contents = vec![
             AttributeContents::InputPin {
@@ -2135,4 +4808,105 @@ impl NodeGraph {
         self.insert_node("Translation Matrix".into(), position, node_contents, attributes_contents)
     }
 
+    pub fn add_scale_matrix_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_scale_matrix() function!
+        let attributes_contents = vec![
+            AttributeContents::Text {
+                label: String::from("x"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("y"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::Text {
+                label: String::from("z"),
+                string: String::from("1.0"),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Matrix,
+            }
+        ];
+        let node_contents = NodeContents::default_scale_matrix();
+        self.insert_node("Scale Matrix".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_axis_rotation_matrix_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_axis_rotation_matrix() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("axis"),
+                kind: DataKind::Vector,
+            },
+            AttributeContents::Text {
+                label: String::from("angle"),
+                string: String::from("0.0"),
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Matrix,
+            }
+        ];
+        let node_contents = NodeContents::default_axis_rotation_matrix();
+        self.insert_node("Axis Rotation Matrix".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_matrix_product_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_matrix_product() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("first"),
+                kind: DataKind::Matrix,
+            },
+            AttributeContents::InputPin {
+                label: String::from("second"),
+                kind: DataKind::Matrix,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Matrix,
+            }
+        ];
+        let node_contents = NodeContents::default_matrix_product();
+        self.insert_node("Matrix Product".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_inverse_matrix_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_inverse_matrix() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("matrix"),
+                kind: DataKind::Matrix,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Matrix,
+            }
+        ];
+        let node_contents = NodeContents::default_inverse_matrix();
+        self.insert_node("Inverse Matrix".into(), position, node_contents, attributes_contents)
+    }
+
+    pub fn add_transpose_matrix_node(&mut self, position: [f32; 2]) -> NodeID {
+        // NOTE: the order here is important: the attributes here
+        // must appear in the same order as they do in the default_transpose_matrix() function!
+        let attributes_contents = vec![
+            AttributeContents::InputPin {
+                label: String::from("matrix"),
+                kind: DataKind::Matrix,
+            },
+            AttributeContents::OutputPin {
+                label: String::from("output"),
+                kind: DataKind::Matrix,
+            }
+        ];
+        let node_contents = NodeContents::default_transpose_matrix();
+        self.insert_node("Transpose Matrix".into(), position, node_contents, attributes_contents)
+    }
+
 }
@@ -175,6 +175,175 @@ impl AstNode {
             AstNode::Func{ arg, .. } => { arg.find_all_idents() },
         }
     }
+
+    // Computes the derivative of the expression with respect to the given variable.
+    // Every other identifier (global variables, constants, other parameters) is treated as a constant.
+    // The result is only simplified when one of the operands is 0 or 1, which is enough to keep
+    // the expressions of higher order derivatives reasonably short.
+    pub fn derivative(&self, variable: &str) -> AstNode {
+        match self {
+            AstNode::Number(_) => AstNode::Number(0.0),
+            AstNode::Ident(ident) => {
+                if ident == variable {
+                    AstNode::Number(1.0)
+                } else {
+                    AstNode::Number(0.0)
+                }
+            },
+            AstNode::UnaryOp{ operator, arg } => {
+                match operator {
+                    Operator::Minus => negate(arg.derivative(variable)),
+                    _ => arg.derivative(variable),
+                }
+            },
+            AstNode::PowOp{ base, exp } => {
+                pow_derivative(base, base.derivative(variable), exp, exp.derivative(variable), variable)
+            },
+            AstNode::BinOp{ lhs, repeated_rhs } => {
+                // the operations are applied from left to right, so we keep track of both the value
+                // and the derivative of the partial result
+                let mut value = (**lhs).clone();
+                let mut derivative = lhs.derivative(variable);
+                for (operator, rhs) in repeated_rhs.iter() {
+                    let rhs_derivative = rhs.derivative(variable);
+                    derivative = match operator {
+                        Operator::Plus => add(derivative, rhs_derivative),
+                        Operator::Minus => sub(derivative, rhs_derivative),
+                        Operator::Times => add(mul(derivative, (**rhs).clone()), mul(value.clone(), rhs_derivative)),
+                        Operator::Div => div(
+                            sub(mul(derivative, (**rhs).clone()), mul(value.clone(), rhs_derivative)),
+                            mul((**rhs).clone(), (**rhs).clone()),
+                        ),
+                        Operator::Pow => pow_derivative(&value, derivative, rhs, rhs_derivative, variable),
+                    };
+                    value = AstNode::BinOp {
+                        lhs: Box::new(value),
+                        repeated_rhs: vec![(operator.clone(), rhs.clone())],
+                    };
+                }
+                derivative
+            },
+            AstNode::Func{ func, arg } => {
+                let u = (**arg).clone();
+                let outer = match func {
+                    MathFunc::Sin => function(MathFunc::Cos, u),
+                    MathFunc::Cos => negate(function(MathFunc::Sin, u)),
+                    MathFunc::Tan => div(AstNode::Number(1.0), mul(function(MathFunc::Cos, u.clone()), function(MathFunc::Cos, u))),
+                    MathFunc::Asin => div(AstNode::Number(1.0), function(MathFunc::Sqrt, sub(AstNode::Number(1.0), mul(u.clone(), u)))),
+                    MathFunc::Acos => negate(div(AstNode::Number(1.0), function(MathFunc::Sqrt, sub(AstNode::Number(1.0), mul(u.clone(), u))))),
+                    MathFunc::Atan => div(AstNode::Number(1.0), add(AstNode::Number(1.0), mul(u.clone(), u))),
+                    MathFunc::Sqrt => div(AstNode::Number(0.5), function(MathFunc::Sqrt, u)),
+                    MathFunc::Exp => function(MathFunc::Exp, u),
+                    MathFunc::Log => div(AstNode::Number(1.0), u),
+                    MathFunc::Abs => div(u.clone(), function(MathFunc::Abs, u)),
+                };
+                mul(outer, arg.derivative(variable))
+            },
+        }
+    }
+}
+
+fn is_number(node: &AstNode, number: f32) -> bool {
+    matches!(node, AstNode::Number(value) if *value == number)
+}
+
+fn binary(lhs: AstNode, operator: Operator, rhs: AstNode) -> AstNode {
+    AstNode::BinOp {
+        lhs: Box::new(lhs),
+        repeated_rhs: vec![(operator, Box::new(rhs))],
+    }
+}
+
+fn add(lhs: AstNode, rhs: AstNode) -> AstNode {
+    if is_number(&lhs, 0.0) {
+        rhs
+    } else if is_number(&rhs, 0.0) {
+        lhs
+    } else {
+        binary(lhs, Operator::Plus, rhs)
+    }
+}
+
+fn sub(lhs: AstNode, rhs: AstNode) -> AstNode {
+    if is_number(&rhs, 0.0) {
+        lhs
+    } else if is_number(&lhs, 0.0) {
+        negate(rhs)
+    } else {
+        binary(lhs, Operator::Minus, rhs)
+    }
+}
+
+fn mul(lhs: AstNode, rhs: AstNode) -> AstNode {
+    if is_number(&lhs, 0.0) || is_number(&rhs, 0.0) {
+        AstNode::Number(0.0)
+    } else if is_number(&lhs, 1.0) {
+        rhs
+    } else if is_number(&rhs, 1.0) {
+        lhs
+    } else {
+        binary(lhs, Operator::Times, rhs)
+    }
+}
+
+fn div(lhs: AstNode, rhs: AstNode) -> AstNode {
+    if is_number(&lhs, 0.0) {
+        AstNode::Number(0.0)
+    } else if is_number(&rhs, 1.0) {
+        lhs
+    } else {
+        binary(lhs, Operator::Div, rhs)
+    }
+}
+
+fn negate(arg: AstNode) -> AstNode {
+    match arg {
+        AstNode::Number(value) => AstNode::Number(-value),
+        _ => AstNode::UnaryOp {
+            operator: Operator::Minus,
+            arg: Box::new(arg),
+        },
+    }
+}
+
+fn function(func: MathFunc, arg: AstNode) -> AstNode {
+    AstNode::Func {
+        func,
+        arg: Box::new(arg),
+    }
+}
+
+fn power(base: AstNode, exp: AstNode) -> AstNode {
+    if is_number(&exp, 1.0) {
+        base
+    } else if is_number(&exp, 0.0) {
+        AstNode::Number(1.0)
+    } else {
+        AstNode::PowOp {
+            base: Box::new(base),
+            exp: Box::new(exp),
+        }
+    }
+}
+
+fn pow_derivative(base: &AstNode, base_derivative: AstNode, exp: &AstNode, exp_derivative: AstNode, variable: &str) -> AstNode {
+    if exp.find_all_idents().iter().any(|ident| ident == variable) {
+        // general case: d(u^v) = u^v * (v' * log(u) + v * u' / u)
+        mul(
+            power(base.clone(), exp.clone()),
+            add(
+                mul(exp_derivative, function(MathFunc::Log, base.clone())),
+                div(mul(exp.clone(), base_derivative), base.clone()),
+            ),
+        )
+    } else {
+        // constant exponent: d(u^n) = n * u^(n-1) * u'
+        let reduced_exp = match exp {
+            AstNode::Number(value) => AstNode::Number(value - 1.0),
+            _ => sub(exp.clone(), AstNode::Number(1.0)),
+        };
+        mul(mul(exp.clone(), power(base.clone(), reduced_exp)), base_derivative)
+    }
 }
 
 #[derive(Debug)]
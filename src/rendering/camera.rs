@@ -44,13 +44,18 @@ impl Camera {
     }
 
     pub fn build_ortho_matrix(&self, aspect: f32) -> Mat4 {
+        let half_h = self.ortho_half_height();
+        let half_w = half_h * aspect;
+        Mat4::orthographic_lh(-half_w, half_w, -half_h, half_h, 128.0, -128.0)
+    }
+
+    // half of the height of the region that is visible with an orthographic projection
+    pub fn ortho_half_height(&self) -> f32 {
         let relative_pos = self.eye - self.target;
         let distance = relative_pos.length();
         // the constant that premultiplies the distance was chosen because on my monitor
         // it is the one that makes the switch between orthographic and perspective almost seamless
-        let half_h = 0.25 * distance;
-        let half_w = half_h * aspect;
-        Mat4::orthographic_lh(-half_w, half_w, -half_h, half_h, 128.0, -128.0)
+        0.25 * distance
     }
 
     pub fn set_xz_plane(&mut self) {
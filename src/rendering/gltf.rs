@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde_json::{json, Value};
+use super::camera::Camera;
+use super::mesh_file::MeshData;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+// FranzPlot uses the z axis as the up direction, while glTF uses the y axis:
+// all the nodes are children of a root node which rotates the scene by -90 degrees around x
+const Z_UP_TO_Y_UP: [f32; 4] = [-std::f32::consts::FRAC_1_SQRT_2, 0.0, 0.0, std::f32::consts::FRAC_1_SQRT_2];
+
+// The camera of a scene, as it is seen in the scene window
+pub struct SceneCamera<'a> {
+    pub camera: &'a Camera,
+    pub ortho: bool,
+    pub aspect_ratio: f32,
+}
+
+// Writes a binary glTF (.glb) file. Each entry of the list becomes a named node,
+// whose mesh has a primitive for each of the meshes of the entry. The material indices
+// are turned into glTF materials using the given base colors, and the camera is added as a node too.
+pub fn save(path: &Path, nodes: &[(String, Vec<(usize, MeshData)>)], material_colors: &[[f32; 4]], scene_camera: &SceneCamera) -> Result<(), String> {
+    if nodes.iter().flat_map(|(_, meshes)| meshes).all(|(_, mesh)| mesh.indices.is_empty()) {
+        return Err("the scene does not\n contain any triangle".into());
+    }
+
+    let mut binary: Vec<u8> = Vec::new();
+    let mut buffer_views: Vec<Value> = Vec::new();
+    let mut accessors: Vec<Value> = Vec::new();
+    // adds a buffer view and an accessor for the given data, returning the index of the accessor
+    let mut push_accessor = |bytes: Vec<u8>, target: u32, component_type: u32, count: usize, accessor_type: &str, bounds: Option<([f32; 3], [f32; 3])>| {
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": binary.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        binary.extend(bytes);
+        let mut accessor = json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": accessor_type,
+        });
+        if let Some((min, max)) = bounds {
+            accessor["min"] = json!(min);
+            accessor["max"] = json!(max);
+        }
+        accessors.push(accessor);
+        accessors.len() - 1
+    };
+
+    let mut materials: Vec<Value> = Vec::new();
+    let mut material_indices: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut meshes: Vec<Value> = Vec::new();
+    let mut scene_nodes: Vec<Value> = Vec::new();
+    for (name, node_meshes) in nodes.iter() {
+        let mut primitives: Vec<Value> = Vec::new();
+        for (material_id, mesh) in node_meshes.iter() {
+            if mesh.indices.is_empty() {
+                continue;
+            }
+            let mut min = [f32::MAX; 3];
+            let mut max = [f32::MIN; 3];
+            for position in mesh.positions.iter() {
+                for i in 0..3 {
+                    min[i] = min[i].min(position[i]);
+                    max[i] = max[i].max(position[i]);
+                }
+            }
+            let to_bytes = |values: &mut dyn Iterator<Item = f32>| values.flat_map(f32::to_le_bytes).collect::<Vec<u8>>();
+            let positions = push_accessor(to_bytes(&mut mesh.positions.iter().flatten().copied()), ARRAY_BUFFER, FLOAT, mesh.positions.len(), "VEC3", Some((min, max)));
+            let normals = push_accessor(to_bytes(&mut mesh.normals.iter().flatten().copied()), ARRAY_BUFFER, FLOAT, mesh.normals.len(), "VEC3", None);
+            let uv_coords = push_accessor(to_bytes(&mut mesh.uv_coords.iter().flatten().copied()), ARRAY_BUFFER, FLOAT, mesh.uv_coords.len(), "VEC2", None);
+            let index_bytes = mesh.indices.iter().flat_map(|index| index.to_le_bytes()).collect();
+            let indices = push_accessor(index_bytes, ELEMENT_ARRAY_BUFFER, UNSIGNED_INT, mesh.indices.len(), "SCALAR", None);
+
+            // each material of the palette is added only once, the first time it is used
+            let material = *material_indices.entry(*material_id).or_insert_with(|| {
+                let base_color = material_colors.get(*material_id).copied().unwrap_or([1.0, 1.0, 1.0, 1.0]);
+                materials.push(json!({
+                    "name": format!("material_{}", material_id),
+                    "pbrMetallicRoughness": {
+                        "baseColorFactor": base_color,
+                        "metallicFactor": 0.0,
+                        "roughnessFactor": 0.5,
+                    },
+                    "doubleSided": true,
+                }));
+                materials.len() - 1
+            });
+            primitives.push(json!({
+                "attributes": {
+                    "POSITION": positions,
+                    "NORMAL": normals,
+                    "TEXCOORD_0": uv_coords,
+                },
+                "indices": indices,
+                "material": material,
+            }));
+        }
+        if primitives.is_empty() {
+            continue;
+        }
+        meshes.push(json!({
+            "name": name,
+            "primitives": primitives,
+        }));
+        scene_nodes.push(json!({
+            "name": name,
+            "mesh": meshes.len() - 1,
+        }));
+    }
+
+    // glTF cameras look towards their -z axis, with their y axis pointing up,
+    // so the camera node is placed using the inverse of the view matrix
+    let camera = scene_camera.camera;
+    let camera_json = if scene_camera.ortho {
+        let half_h = camera.ortho_half_height();
+        json!({
+            "name": "Camera",
+            "type": "orthographic",
+            "orthographic": {
+                "xmag": half_h * scene_camera.aspect_ratio,
+                "ymag": half_h,
+                "znear": 0.0,
+                "zfar": camera.z_far,
+            },
+        })
+    } else {
+        json!({
+            "name": "Camera",
+            "type": "perspective",
+            "perspective": {
+                "aspectRatio": scene_camera.aspect_ratio,
+                "yfov": camera.fov_y,
+                "znear": camera.z_near,
+                "zfar": camera.z_far,
+            },
+        })
+    };
+    scene_nodes.push(json!({
+        "name": "Camera",
+        "camera": 0,
+        "matrix": camera.build_view_matrix().inverse().to_cols_array(),
+    }));
+
+    let children: Vec<usize> = (1 ..= scene_nodes.len()).collect();
+    let mut gltf_nodes = vec![json!({
+        "name": "FranzPlot scene",
+        "rotation": Z_UP_TO_Y_UP,
+        "children": children,
+    })];
+    gltf_nodes.extend(scene_nodes);
+
+    // the json chunk is padded with spaces, the binary chunk with zeros
+    while binary.len() % 4 != 0 {
+        binary.push(0);
+    }
+    let document = json!({
+        "asset": {
+            "version": "2.0",
+            "generator": "FranzPlot",
+        },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": gltf_nodes,
+        "meshes": meshes,
+        "materials": materials,
+        "cameras": [camera_json],
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{ "byteLength": binary.len() }],
+    });
+
+    let mut json_chunk = serde_json::to_vec(&document).unwrap();
+    while json_chunk.len() % 4 != 0 {
+        json_chunk.push(b' ');
+    }
+    let total_length = 12 + 8 + json_chunk.len() + 8 + binary.len();
+    let mut contents: Vec<u8> = Vec::with_capacity(total_length);
+    contents.extend(b"glTF");
+    contents.extend(2u32.to_le_bytes());
+    contents.extend((total_length as u32).to_le_bytes());
+    contents.extend((json_chunk.len() as u32).to_le_bytes());
+    contents.extend(b"JSON");
+    contents.extend(json_chunk);
+    contents.extend((binary.len() as u32).to_le_bytes());
+    contents.extend(b"BIN\0");
+    contents.extend(binary);
+    std::fs::write(path, contents)
+        .map_err(|error| format!("cannot write the file:\n {}", error))
+}
@@ -31,6 +31,8 @@ struct MatcapVertexOutput {
     [[location(0)]] uv_coords: vec2<f32>;
     [[location(1)]] normal: vec4<f32>;
     [[location(2)]] object_id: u32;
+    // a color with a non-zero alpha replaces the one of the matcap texture
+    [[location(3)]] color: vec4<f32>;
 };
 
 [[stage(vertex)]]
@@ -39,13 +41,51 @@ fn matcap_vs_main(
     [[location(0)]] position: vec4<f32>,
     [[location(1)]] normal: vec4<f32>,
     [[location(2)]] uv_coords: vec2<f32>,
-    [[location(3)]] padding: vec2<f32>
+    [[location(3)]] padding: vec2<f32>,
+    [[location(4)]] color: vec4<f32>
 ) -> MatcapVertexOutput {
     var out: MatcapVertexOutput;
     out.uv_coords = uv_coords;
     out.object_id = object_id;
     out.normal = normal;
     out.position = uniforms.proj * uniforms.view * position;
+    out.color = color;
+    return out;
+}
+
+// same as the matcap vertex shader, but each instance of the mesh
+// gets its own transform and color, which replaces the one of the vertex
+[[stage(vertex)]]
+fn matcap_instanced_vs_main(
+    [[location(0)]] position: vec4<f32>,
+    [[location(1)]] normal: vec4<f32>,
+    [[location(2)]] uv_coords: vec2<f32>,
+    [[location(3)]] padding: vec2<f32>,
+    [[location(4)]] vertex_color: vec4<f32>,
+    [[location(5)]] transform_0: vec4<f32>,
+    [[location(6)]] transform_1: vec4<f32>,
+    [[location(7)]] transform_2: vec4<f32>,
+    [[location(8)]] transform_3: vec4<f32>,
+    [[location(9)]] color: vec4<f32>
+) -> MatcapVertexOutput {
+    let transform = mat4x4<f32>(transform_0, transform_1, transform_2, transform_3);
+    var out: MatcapVertexOutput;
+    out.uv_coords = uv_coords;
+    out.object_id = 0u;
+    // the instance transform may contain a non-uniform scaling, so normals are transformed
+    // by the inverse transpose of its 3x3 part. The cofactor matrix is proportional to it:
+    // the sign of the determinant is kept, and the scale goes away when normalizing.
+    let c0 = transform_0.xyz;
+    let c1 = transform_1.xyz;
+    let c2 = transform_2.xyz;
+    let cofactor = mat3x3<f32>(cross(c1, c2), cross(c2, c0), cross(c0, c1));
+    let det_sign = select(1.0, -1.0, dot(c0, cross(c1, c2)) < 0.0);
+    let transformed_normal = det_sign * (cofactor * normal.xyz);
+    // collapsed instances have no area, any normal works for them
+    let normal_length = length(transformed_normal);
+    out.normal = select(vec4<f32>(0.0, 0.0, 1.0, 0.0), vec4<f32>(transformed_normal / normal_length, 0.0), normal_length > 0.0);
+    out.position = uniforms.proj * uniforms.view * transform * position;
+    out.color = color;
     return out;
 }
 
@@ -74,7 +114,10 @@ fn matcap_fs_main(in: MatcapVertexOutput) -> [[location(0)]] vec4<f32> {
     // final color
     let highlight_coeff: f32 = select(1.0, 1.4, in.object_id == uniforms.highlight_id);
     let z_light_coeff: f32 = 1.0 + in.normal.z * 0.2;
-    let final_color = vec4<f32>(z_light_coeff * highlight_coeff * darken_coeff * matcap_color.xyz, 1.0);
+    // when a color is provided, the matcap is only used for its shading
+    let matcap_luminance = dot(matcap_color.xyz, vec3<f32>(0.299, 0.587, 0.114));
+    let base_color = select(matcap_color.xyz, in.color.xyz * (0.3 + 0.9 * matcap_luminance), in.color.a > 0.0);
+    let final_color = vec4<f32>(z_light_coeff * highlight_coeff * darken_coeff * base_color, 1.0);
     return final_color;
 }
 
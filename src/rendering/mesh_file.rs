@@ -0,0 +1,479 @@
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+
+// A triangle mesh read from a file chosen by the user. It is kept on the CPU side
+// so that it can be embedded in a saved scene, and turned into a Model when the scene is processed.
+// Normals that are not provided by the file are computed from the triangles.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uv_coords: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    // the file format is chosen from the file extension
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read(path)
+            .map_err(|error| format!("cannot read the file:\n {}", error))?;
+        let extension = path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+        let mesh = match extension.as_deref() {
+            Some("obj") => {
+                let data = obj::ObjData::load_buf(contents.as_slice())
+                    .map_err(|error| format!("invalid obj file:\n {}", error))?;
+                Self::from_obj(&data)?
+            },
+            Some("stl") => Self::from_stl(&contents)?,
+            Some("ply") => Self::from_ply(&contents)?,
+            _ => return Err("only obj, stl and ply\n files are supported".into()),
+        };
+        mesh.validate()?;
+        Ok(mesh)
+    }
+
+    // each corner of a face becomes a vertex, faces with more than three corners are triangulated as a fan
+    pub fn from_obj(data: &obj::ObjData) -> Result<Self, String> {
+        let invalid = || "the obj file contains\n invalid vertex indices".to_string();
+        let mut mesh = Self::default();
+        let mut missing_normals = false;
+        for object in data.objects.iter() {
+            for group in object.groups.iter() {
+                for polygon in group.polys.iter() {
+                    let obj::SimplePolygon(face_vertices) = &polygon;
+                    if face_vertices.len() < 3 {
+                        continue;
+                    }
+                    let first_face_idx = mesh.positions.len() as u32;
+                    for i in 1 .. face_vertices.len() as u32 - 1 {
+                        mesh.indices.extend([first_face_idx, first_face_idx + i, first_face_idx + i + 1]);
+                    }
+                    for obj::IndexTuple(pos, uv, norm) in face_vertices.iter() {
+                        mesh.positions.push(*data.position.get(*pos).ok_or_else(invalid)?);
+                        mesh.normals.push(match norm {
+                            Some(x) => *data.normal.get(*x).ok_or_else(invalid)?,
+                            None => [0.0, 0.0, 0.0],
+                        });
+                        mesh.uv_coords.push(match uv {
+                            Some(x) => *data.texture.get(*x).ok_or_else(invalid)?,
+                            None => [0.0, 0.0],
+                        });
+                        missing_normals |= norm.is_none();
+                    }
+                }
+            }
+        }
+        if missing_normals {
+            mesh.compute_normals();
+        }
+        Ok(mesh)
+    }
+
+    // checks that every vertex has all of its attributes and that the triangles only use existing vertices,
+    // which is needed for meshes that do not come from a file, e.g. the ones embedded in a scene file
+    pub fn validate(&self) -> Result<(), String> {
+        if self.indices.is_empty() {
+            return Err("the mesh does not\n contain any triangle".into());
+        }
+        if self.normals.len() != self.positions.len() || self.uv_coords.len() != self.positions.len() {
+            return Err("the mesh vertices\n have missing attributes".into());
+        }
+        if self.indices.len() % 3 != 0 || self.indices.iter().any(|index| *index as usize >= self.positions.len()) {
+            return Err("the mesh contains\n invalid vertex indices".into());
+        }
+        Ok(())
+    }
+
+    // both the binary and the ascii variants are supported. Binary files are recognized
+    // by their size, since ascii and binary files can both begin with "solid".
+    pub fn from_stl(contents: &[u8]) -> Result<Self, String> {
+        let mut triangles = Vec::<[[f32; 3]; 4]>::new();
+        let binary_count = contents.get(80..84)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize);
+        match binary_count {
+            Some(count) if contents.len() == 84 + 50 * count => {
+                for chunk in contents[84..].chunks_exact(50) {
+                    let mut triangle = [[0.0; 3]; 4];
+                    for (i, value) in triangle.iter_mut().flatten().enumerate() {
+                        let bytes = &chunk[4 * i .. 4 * i + 4];
+                        *value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                    }
+                    triangles.push(triangle);
+                }
+            },
+            _ => {
+                let text = std::str::from_utf8(contents)
+                    .map_err(|_| "invalid stl file".to_string())?;
+                let mut triangle = [[0.0; 3]; 4];
+                let mut corner = 0;
+                for line in text.lines() {
+                    let mut tokens = line.split_whitespace();
+                    let coords = match tokens.next() {
+                        Some("facet") => {
+                            corner = 0;
+                            // skip the "normal" keyword
+                            tokens.next();
+                            &mut triangle[0]
+                        },
+                        Some("vertex") if corner < 3 => {
+                            corner += 1;
+                            &mut triangle[corner]
+                        },
+                        Some("endfacet") if corner == 3 => {
+                            triangles.push(triangle);
+                            continue;
+                        },
+                        _ => continue,
+                    };
+                    for value in coords.iter_mut() {
+                        *value = tokens.next()
+                            .and_then(|token| token.parse().ok())
+                            .ok_or_else(|| "invalid stl file".to_string())?;
+                    }
+                }
+            },
+        }
+
+        // triangles do not share vertices, so that each of them keeps its own normal
+        let mut mesh = Self::default();
+        let mut missing_normals = false;
+        for [normal, a, b, c] in triangles {
+            let first_idx = mesh.positions.len() as u32;
+            mesh.indices.extend([first_idx, first_idx + 1, first_idx + 2]);
+            mesh.positions.extend([a, b, c]);
+            mesh.normals.extend([normal; 3]);
+            mesh.uv_coords.extend([[0.0, 0.0]; 3]);
+            missing_normals |= normal == [0.0, 0.0, 0.0];
+        }
+        if missing_normals {
+            mesh.compute_normals();
+        }
+        Ok(mesh)
+    }
+
+    // supports the ascii and binary encodings. Only the positions, normals and texture coordinates
+    // of the vertices and the vertex indices of the faces are read, all other properties are skipped.
+    pub fn from_ply(contents: &[u8]) -> Result<Self, String> {
+        let invalid = || "invalid ply file".to_string();
+        let header_end = contents.windows(10)
+            .position(|window| window == b"end_header")
+            .ok_or_else(invalid)?;
+        let header = std::str::from_utf8(&contents[..header_end]).map_err(|_| invalid())?;
+        // the body starts after the newline that ends the header
+        let body_start = contents[header_end..].iter()
+            .position(|byte| *byte == b'\n')
+            .map(|offset| header_end + offset + 1)
+            .ok_or_else(invalid)?;
+
+        let mut encoding = PlyEncoding::Ascii;
+        let mut elements = Vec::<PlyElement>::new();
+        for line in header.lines() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                ["format", "ascii", ..] => encoding = PlyEncoding::Ascii,
+                ["format", "binary_little_endian", ..] => encoding = PlyEncoding::LittleEndian,
+                ["format", "binary_big_endian", ..] => encoding = PlyEncoding::BigEndian,
+                ["element", name, count] => elements.push(PlyElement {
+                    name: name.to_string(),
+                    count: count.parse().map_err(|_| invalid())?,
+                    properties: Vec::new(),
+                }),
+                ["property", "list", count_type, value_type, name] => elements.last_mut().ok_or_else(invalid)?.properties.push(PlyProperty {
+                    name: name.to_string(),
+                    list_count_type: Some(count_type.to_string()),
+                    value_type: value_type.to_string(),
+                }),
+                ["property", value_type, name] => elements.last_mut().ok_or_else(invalid)?.properties.push(PlyProperty {
+                    name: name.to_string(),
+                    list_count_type: None,
+                    value_type: value_type.to_string(),
+                }),
+                _ => {},
+            }
+        }
+
+        let mut reader = PlyReader {
+            encoding,
+            body: &contents[body_start..],
+            cursor: 0,
+        };
+        let mut mesh = Self::default();
+        let mut missing_normals = false;
+        for element in elements.iter() {
+            for _ in 0..element.count {
+                let mut values = Vec::<(&str, Vec<f64>)>::new();
+                for property in element.properties.iter() {
+                    let property_values = match &property.list_count_type {
+                        Some(count_type) => {
+                            let count = reader.read(count_type)? as usize;
+                            (0..count)
+                                .map(|_| reader.read(&property.value_type))
+                                .collect::<Result<Vec<f64>, String>>()?
+                        },
+                        None => vec![reader.read(&property.value_type)?],
+                    };
+                    values.push((property.name.as_str(), property_values));
+                }
+                let get = |names: &[&str]| values.iter()
+                    .find(|(name, _)| names.contains(name))
+                    .and_then(|(_, values)| values.first())
+                    .map(|value| *value as f32);
+                match element.name.as_str() {
+                    "vertex" => {
+                        let position = [get(&["x"]), get(&["y"]), get(&["z"])];
+                        let normal = [get(&["nx"]), get(&["ny"]), get(&["nz"])];
+                        let uv_coords = [get(&["u", "s", "texture_u"]), get(&["v", "t", "texture_v"])];
+                        mesh.positions.push(position.map(|value| value.unwrap_or(0.0)));
+                        mesh.normals.push(normal.map(|value| value.unwrap_or(0.0)));
+                        mesh.uv_coords.push(uv_coords.map(|value| value.unwrap_or(0.0)));
+                        missing_normals |= normal.contains(&None);
+                    },
+                    "face" => {
+                        let face_vertices = values.iter()
+                            .find(|(name, _)| *name == "vertex_indices" || *name == "vertex_index")
+                            .map(|(_, values)| values.as_slice())
+                            .unwrap_or_default();
+                        for i in 1 .. face_vertices.len().max(2) - 1 {
+                            mesh.indices.extend([face_vertices[0], face_vertices[i], face_vertices[i + 1]].map(|index| index as u32));
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+        if mesh.indices.iter().any(|index| *index as usize >= mesh.positions.len()) {
+            return Err("the ply file contains\n invalid vertex indices".into());
+        }
+        if missing_normals {
+            mesh.compute_normals();
+        }
+        Ok(mesh)
+    }
+
+    // the normal of each vertex is the average of the normals of the triangles around it, weighted by their areas
+    fn compute_normals(&mut self) {
+        let mut normals = vec![glam::Vec3::ZERO; self.positions.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| glam::Vec3::from(self.positions[triangle[i] as usize]));
+            let face_normal = (b - a).cross(c - a);
+            for index in triangle {
+                normals[*index as usize] += face_normal;
+            }
+        }
+        self.normals = normals.into_iter()
+            .map(|normal| normal.normalize_or_zero().into())
+            .collect();
+    }
+}
+
+enum PlyEncoding {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+struct PlyProperty {
+    name: String,
+    list_count_type: Option<String>,
+    value_type: String,
+}
+
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+struct PlyReader<'a> {
+    encoding: PlyEncoding,
+    body: &'a [u8],
+    cursor: usize,
+}
+
+impl PlyReader<'_> {
+    fn read(&mut self, value_type: &str) -> Result<f64, String> {
+        let invalid = || "invalid ply file".to_string();
+        if let PlyEncoding::Ascii = self.encoding {
+            // values are separated by any kind of white space, including newlines
+            let remaining = &self.body[self.cursor..];
+            let begin = remaining.iter().position(|byte| !byte.is_ascii_whitespace()).ok_or_else(invalid)?;
+            let length = remaining[begin..].iter().position(|byte| byte.is_ascii_whitespace()).unwrap_or(remaining.len() - begin);
+            self.cursor += begin + length;
+            return std::str::from_utf8(&remaining[begin .. begin + length]).ok()
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(invalid);
+        }
+        let size = match value_type {
+            "char" | "int8" | "uchar" | "uint8" => 1,
+            "short" | "int16" | "ushort" | "uint16" => 2,
+            "int" | "int32" | "uint" | "uint32" | "float" | "float32" => 4,
+            "double" | "float64" => 8,
+            _ => return Err(format!("unknown ply type:\n {}", value_type)),
+        };
+        let mut bytes = [0u8; 8];
+        bytes[..size].copy_from_slice(self.body.get(self.cursor .. self.cursor + size).ok_or_else(invalid)?);
+        self.cursor += size;
+        if let PlyEncoding::BigEndian = self.encoding {
+            bytes[..size].reverse();
+        }
+        let value = match value_type {
+            "char" | "int8" => bytes[0] as i8 as f64,
+            "uchar" | "uint8" => bytes[0] as f64,
+            "short" | "int16" => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            "ushort" | "uint16" => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+            "int" | "int32" => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            "uint" | "uint32" => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            "float" | "float32" => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+            _ => f64::from_le_bytes(bytes),
+        };
+        Ok(value)
+    }
+}
+
+// paths are relative to the directory of the scene file, if it was saved somewhere
+pub fn resolve_path(directory: Option<&Path>, path: &str) -> PathBuf {
+    match directory {
+        Some(directory) => directory.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+// Writes a list of named meshes to a single file, whose format is chosen from the file extension.
+// Each mesh also carries the index of its material: the obj format keeps the meshes in separate groups
+// and writes the material colors to a .mtl file next to it, while the stl and ply formats
+// merge all the triangles together.
+pub fn save(path: &Path, meshes: &[(String, usize, MeshData)], material_colors: &[[f32; 4]]) -> Result<(), String> {
+    if meshes.iter().all(|(_, _, mesh)| mesh.indices.is_empty()) {
+        return Err("the scene does not\n contain any triangle".into());
+    }
+    let extension = path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_lowercase());
+    let write = |path: &Path, contents: Vec<u8>| std::fs::write(path, contents)
+        .map_err(|error| format!("cannot write the file:\n {}", error));
+    let contents = match extension.as_deref() {
+        Some("obj") => {
+            let mtl_path = path.with_extension("mtl");
+            let mtl_name = mtl_path.file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| "invalid file name".to_string())?;
+            write(&mtl_path, to_mtl(meshes, material_colors))?;
+            to_obj(meshes, mtl_name)
+        },
+        Some("stl") => to_stl(meshes),
+        Some("ply") => to_ply(meshes),
+        _ => return Err("only obj, stl and ply\n files are supported".into()),
+    };
+    write(path, contents)
+}
+
+pub fn to_obj(meshes: &[(String, usize, MeshData)], mtl_name: &str) -> Vec<u8> {
+    use std::fmt::Write;
+    let mut contents = String::from("# exported by FranzPlot\n");
+    writeln!(contents, "mtllib {}", mtl_name).unwrap();
+    // obj indices start from 1, and they are shared by all the groups in the file
+    let mut offset = 1;
+    for (name, material_id, mesh) in meshes.iter() {
+        // group names cannot contain white space
+        let group_name: String = name.chars()
+            .map(|c| if c.is_whitespace() { '_' } else { c })
+            .collect();
+        writeln!(contents, "g {}", group_name).unwrap();
+        writeln!(contents, "usemtl material_{}", material_id).unwrap();
+        for [x, y, z] in mesh.positions.iter() {
+            writeln!(contents, "v {} {} {}", x, y, z).unwrap();
+        }
+        for [u, v] in mesh.uv_coords.iter() {
+            writeln!(contents, "vt {} {}", u, v).unwrap();
+        }
+        for [x, y, z] in mesh.normals.iter() {
+            writeln!(contents, "vn {} {} {}", x, y, z).unwrap();
+        }
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i] + offset);
+            writeln!(contents, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}", a = a, b = b, c = c).unwrap();
+        }
+        offset += mesh.positions.len() as u32;
+    }
+    contents.into_bytes()
+}
+
+// each material that is used by the meshes only gets a diffuse color, taken from the same palette used for rendering
+pub fn to_mtl(meshes: &[(String, usize, MeshData)], material_colors: &[[f32; 4]]) -> Vec<u8> {
+    use std::fmt::Write;
+    let material_ids: std::collections::BTreeSet<usize> = meshes.iter()
+        .map(|(_, material_id, _)| *material_id)
+        .collect();
+    let mut contents = String::from("# exported by FranzPlot\n");
+    for material_id in material_ids {
+        let [r, g, b, _] = material_colors.get(material_id).copied().unwrap_or([1.0, 1.0, 1.0, 1.0]);
+        writeln!(contents, "newmtl material_{}", material_id).unwrap();
+        writeln!(contents, "Kd {} {} {}", r, g, b).unwrap();
+    }
+    contents.into_bytes()
+}
+
+// binary stl: an 80 bytes header, the number of triangles and then,
+// for each triangle, its normal, its three corners and an unused attribute
+pub fn to_stl(meshes: &[(String, usize, MeshData)]) -> Vec<u8> {
+    let triangles_count: usize = meshes.iter().map(|(_, _, mesh)| mesh.indices.len() / 3).sum();
+    let mut contents = vec![0u8; 80];
+    contents[.. 22].copy_from_slice(b"exported by FranzPlot ");
+    contents.extend((triangles_count as u32).to_le_bytes());
+    for (_, _, mesh) in meshes.iter() {
+        for triangle in mesh.indices.chunks_exact(3) {
+            let corners = [0, 1, 2].map(|i| glam::Vec3::from(mesh.positions[triangle[i] as usize]));
+            let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]).normalize_or_zero();
+            for vector in std::iter::once(normal).chain(corners) {
+                for value in vector.to_array() {
+                    contents.extend(value.to_le_bytes());
+                }
+            }
+            contents.extend(0u16.to_le_bytes());
+        }
+    }
+    contents
+}
+
+// binary little endian ply, with positions, normals and texture coordinates for each vertex
+pub fn to_ply(meshes: &[(String, usize, MeshData)]) -> Vec<u8> {
+    let vertices_count: usize = meshes.iter().map(|(_, _, mesh)| mesh.positions.len()).sum();
+    let triangles_count: usize = meshes.iter().map(|(_, _, mesh)| mesh.indices.len() / 3).sum();
+    let header = format!(r##"ply
+format binary_little_endian 1.0
+comment exported by FranzPlot
+element vertex {}
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+property float s
+property float t
+element face {}
+property list uchar uint vertex_indices
+end_header
+"##, vertices_count, triangles_count);
+    let mut contents = header.into_bytes();
+    for (_, _, mesh) in meshes.iter() {
+        for ((position, normal), uv) in mesh.positions.iter().zip(mesh.normals.iter()).zip(mesh.uv_coords.iter()) {
+            for value in position.iter().chain(normal.iter()).chain(uv.iter()) {
+                contents.extend(value.to_le_bytes());
+            }
+        }
+    }
+    let mut offset = 0;
+    for (_, _, mesh) in meshes.iter() {
+        for triangle in mesh.indices.chunks_exact(3) {
+            contents.push(3);
+            for index in triangle {
+                contents.extend((index + offset).to_le_bytes());
+            }
+        }
+        offset += mesh.positions.len() as u32;
+    }
+    contents
+}
@@ -1,5 +1,7 @@
 pub mod camera;
 pub mod model;
+pub mod mesh_file;
+pub mod gltf;
 pub mod texture;
 pub mod scene_renderer;
 
@@ -11,14 +13,16 @@ pub struct StandardVertexData {
     pub position: [f32; 4],
     pub normal: [f32; 4],
     pub uv_coords: [f32; 2],
-    // maybe we could pack the color and the 2d indices in here!
-    // the color can be packed in [u8; 4] and passed in as Uchar4Norm,
-    // while the two indices could simply be [u16; 2]
+    // maybe we could pack the 2d indices in here!
+    // the two indices could simply be [u16; 2]
     pub _padding: [f32; 2],
+    // a color with a non-zero alpha replaces the one of the matcap texture,
+    // e.g. when the vertices are colored by a scalar function
+    pub color: [f32; 4],
 }
 
 impl StandardVertexData {
-    const VERTEX_ATTR_ARRAY: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x2, 3 => Float32x2];
+    const VERTEX_ATTR_ARRAY: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x2, 3 => Float32x2, 4 => Float32x4];
     fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
@@ -31,6 +35,29 @@ impl StandardVertexData {
 unsafe impl bytemuck::Pod for StandardVertexData {}
 unsafe impl bytemuck::Zeroable for StandardVertexData {}
 
+// Per-instance data used when the same mesh is drawn many times (e.g. the arrows
+// of a vector field): each instance has its own transform and its own color.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct InstanceData {
+    pub transform: [[f32; 4]; 4],
+    pub color: [f32; 4],
+}
+
+impl InstanceData {
+    const VERTEX_ATTR_ARRAY: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![5 => Float32x4, 6 => Float32x4, 7 => Float32x4, 8 => Float32x4, 9 => Float32x4];
+    fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::VERTEX_ATTR_ARRAY,
+        }
+    }
+}
+
+unsafe impl bytemuck::Pod for InstanceData {}
+unsafe impl bytemuck::Zeroable for InstanceData {}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct WireframeVertexData {
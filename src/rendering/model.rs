@@ -1,6 +1,7 @@
 use std::rc::Rc;
 
 use super::StandardVertexData;
+use super::mesh_file::MeshData;
 
 pub const MODEL_CHUNK_VERTICES: usize = 32;
 
@@ -13,48 +14,24 @@ pub struct Model {
 }
 
 impl Model {
-    pub fn from_obj(device: &wgpu::Device, data: &obj::ObjData) -> Self {
-        let mut indices = Vec::<u32>::new();
-        let mut vertices = Vec::<StandardVertexData>::new();
+    pub fn from_obj(device: &wgpu::Device, data: &obj::ObjData) -> Result<Self, String> {
+        Self::from_mesh(device, &MeshData::from_obj(data)?)
+    }
 
-        for object in data.objects.iter() {
-            for group in object.groups.iter() {
-                for polygon in group.polys.iter() {
-                    let obj::SimplePolygon(face_vertices) = &polygon;
-                    // triangulate the face
-                    assert!(face_vertices.len() >= 3);
-                    let first_face_idx = vertices.len() as u32;
-                    let last_face_idx = first_face_idx + face_vertices.len() as u32 - 1;
-                    let segment_indices: Vec<u32> = (first_face_idx + 1 ..= last_face_idx).collect();
-                    for segment in segment_indices.windows(2) {
-                        indices.push(first_face_idx);
-                        indices.push(segment[0]);
-                        indices.push(segment[1]);
-                    }
-                    for obj::IndexTuple(pos, uv, norm) in face_vertices.iter() {
-                        let p = data.position[*pos];
-                        let position = [p[0], p[1], p[2], 1.0];
-                        let normal = if let Some(n) = norm.map(|x| {data.normal[x]}) {
-                            [n[0], n[1], n[2], 0.0]
-                        } else {
-                            [0.0, 0.0, 0.0, 0.0]
-                        };
-                        let uv_coords = if let Some(coords) = uv.map(|x| {data.texture[x]}) {
-                            [coords[0], coords[1]]
-                        } else {
-                            [0.0, 0.0]
-                        };
-                        let vertex_data = StandardVertexData {
-                            position,
-                            normal,
-                            uv_coords,
-                            _padding: [2.22, 3.33],
-                        };
-                        vertices.push(vertex_data);
-                    }
-                }
-            }
-        }
+    pub fn from_mesh(device: &wgpu::Device, mesh: &MeshData) -> Result<Self, String> {
+        mesh.validate()?;
+        let indices = &mesh.indices;
+        let mut vertices: Vec<StandardVertexData> = mesh.positions.iter()
+            .zip(mesh.normals.iter())
+            .zip(mesh.uv_coords.iter())
+            .map(|((p, n), uv_coords)| StandardVertexData {
+                position: [p[0], p[1], p[2], 1.0],
+                normal: [n[0], n[1], n[2], 0.0],
+                uv_coords: *uv_coords,
+                _padding: [2.22, 3.33],
+                color: [0.0, 0.0, 0.0, 0.0],
+            })
+            .collect();
 
         let vertices_remainder = vertices.len() % MODEL_CHUNK_VERTICES;
         if vertices_remainder != 0 {
@@ -71,16 +48,16 @@ impl Model {
         });
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
             label: Some("model index buffer"),
-            contents: bytemuck::cast_slice(&indices),
-            usage: wgpu::BufferUsages::INDEX,
+            contents: bytemuck::cast_slice(indices),
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::MAP_READ,
         });
 
         assert!(vertices.len() % MODEL_CHUNK_VERTICES == 0);
-        Self {
+        Ok(Self {
             vertex_buffer,
             chunks_count: vertices.len() / MODEL_CHUNK_VERTICES,
             index_buffer: Rc::new(index_buffer),
             index_count: indices.len() as u32,
-        }
+        })
     }
 }
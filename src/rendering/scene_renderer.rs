@@ -58,6 +58,7 @@ pub struct SceneRenderer {
 
 struct Pipelines {
     matcap: wgpu::RenderPipeline,
+    matcap_instanced: wgpu::RenderPipeline,
     billboard: wgpu::RenderPipeline,
     wireframe: wgpu::RenderPipeline,
 }
@@ -128,6 +129,11 @@ impl SceneRenderer {
         }
     }
 
+    // the aspect ratio of the last rendered picture
+    pub fn aspect_ratio(&self) -> f32 {
+        self.texture_extent.width as f32 / self.texture_extent.height as f32
+    }
+
     pub fn clear_wireframe_axes(&mut self) {
         self.wireframe_axes = None;
     }
@@ -340,9 +346,12 @@ impl SceneRenderer {
             self.picking_bind_group = picking_bind_group;
         }
 
-        for (idx, (data_id, matcap)) in matcaps.enumerate() {
+        for (idx, (data_id, node_matcaps)) in matcaps.enumerate() {
             self.renderable_ids.push(*data_id);
-            self.add_matcap(manager, assets, matcap, idx as u32);
+            // all the renderables of a node share the same object id
+            for matcap in node_matcaps {
+                self.add_matcap(manager, assets, matcap, idx as u32);
+            }
         }
     }
 
@@ -361,9 +370,15 @@ impl SceneRenderer {
                 sample_count: manager.sample_count,
             }
         );
+        // instanced meshes use their own pipeline, which reads the per-instance data from a second vertex buffer
+        let pipeline = if matcap_data.instance_buffer.is_some() {
+            &self.pipelines.matcap_instanced
+        } else {
+            &self.pipelines.matcap
+        };
         // In order to create a correct uniforms bind group, we need to recover the layour from the correct pipeline
         let uniforms_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor{
-            layout: &self.pipelines.matcap.get_bind_group_layout(0),
+            layout: &pipeline.get_bind_group_layout(0),
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -372,8 +387,11 @@ impl SceneRenderer {
             ],
             label: Some("Uniforms bind group"),
         });
-        render_bundle_encoder.set_pipeline(&self.pipelines.matcap);
+        render_bundle_encoder.set_pipeline(pipeline);
         render_bundle_encoder.set_vertex_buffer(0, matcap_data.vertex_buffer.slice(..));
+        if let Some(instance_buffer) = &matcap_data.instance_buffer {
+            render_bundle_encoder.set_vertex_buffer(1, instance_buffer.slice(..));
+        }
         render_bundle_encoder.set_index_buffer(matcap_data.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         render_bundle_encoder.set_bind_group(0, &uniforms_bind_group, &[]);
         render_bundle_encoder.set_bind_group(1, &self.picking_bind_group, &[]);
@@ -383,7 +401,7 @@ impl SceneRenderer {
         // will be able to recover the id by reading the gl_InstanceIndex variable
         let instance_id = object_id;
         //render_bundle_encoder.draw_indexed(0..rendering_data.index_count, 0, instance_id..instance_id+1);
-        render_bundle_encoder.draw_indexed(0..matcap_data.index_count, 0, 0..1);
+        render_bundle_encoder.draw_indexed(0..matcap_data.index_count, 0, 0..matcap_data.instance_count);
         let render_bundle = render_bundle_encoder.finish(&wgpu::RenderBundleDescriptor {
             label: Some("Render bundle for a single scene object"),
         });
@@ -585,6 +603,25 @@ fn create_pipelines(manager: &device_manager::Manager) -> Pipelines {
         multiview: None,
     });
 
+    let matcap_instanced = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        vertex: wgpu::VertexState {
+            module: &wgsl_module,
+            entry_point: "matcap_instanced_vs_main",
+            buffers: &[StandardVertexData::vertex_buffer_layout(), InstanceData::vertex_buffer_layout()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &wgsl_module,
+            entry_point: "matcap_fs_main",
+            targets: &[color_target_state.clone()],
+        }),
+        layout: None,
+        label: None,
+        primitive: primitive_triangles,
+        depth_stencil: depth_stencil_state.clone(),
+        multisample: multisample_state,
+        multiview: None,
+    });
+
     let billboard = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
         vertex: wgpu::VertexState {
             module: &wgsl_module,
@@ -624,6 +661,7 @@ fn create_pipelines(manager: &device_manager::Manager) -> Pipelines {
     });
     Pipelines {
         matcap,
+        matcap_instanced,
         billboard,
         wireframe,
     }
@@ -174,6 +174,12 @@ impl Gui {
                     if MenuItem::new("Export scene").build(ui) {
                         file_io::async_pick_png(self.winit_proxy.clone(), executor);
                     }
+                    if MenuItem::new("Export mesh").build(ui) {
+                        file_io::async_pick_mesh(self.winit_proxy.clone(), executor);
+                    }
+                    if MenuItem::new("Export glTF").build(ui) {
+                        file_io::async_pick_gltf(self.winit_proxy.clone(), executor);
+                    }
                     ui.separator();
                     if MenuItem::new("Exit").build(ui) {
                         if self.graph_edited {
@@ -6,6 +6,8 @@ pub enum Action<'a> {
     RenderScene(wgpu::Extent3d, &'a wgpu::TextureView),
     WriteToFile(PathBuf),
     OpenFile(PathBuf),
+    ExportMesh(PathBuf),
+    ExportGltf(PathBuf),
     NewFile(),
     UpdateGlobals(Vec<NameValuePair>),
 }
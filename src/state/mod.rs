@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::compute_graph::ComputeGraph;
 use crate::device_manager::Manager;
@@ -6,7 +6,9 @@ use crate::rendering::camera;
 use crate::rendering::SceneRenderer;
 use crate::rendering::texture::{Texture, Masks};
 use crate::rendering::model::Model;
-use crate::node_graph;
+use crate::rendering::mesh_file::{self, MeshData};
+use crate::rendering::gltf;
+use crate::node_graph::{self, NodeID};
 use serde::{Serialize, Deserialize};
 
 pub mod action;
@@ -26,6 +28,9 @@ pub struct UserState {
     pub node_graph: node_graph::NodeGraph,
     pub globals_names: Vec<String>,
     pub globals_init_values: Vec<f32>,
+    // the directory of the scene file, used to find the files referenced by the scene
+    #[serde(skip)]
+    pub directory: Option<PathBuf>,
 }
 
 // This structure holds the timestamps that we add to the saved files
@@ -69,6 +74,8 @@ enum FileVersion {
 
 pub struct Assets {
     pub materials: Vec<Texture>,
+    // the base colors of the materials, used when exporting to formats which do not support matcaps
+    pub material_colors: Vec<[f32; 4]>,
     pub masks: Masks,
     pub models: Vec<Model>,
 }
@@ -175,6 +182,12 @@ impl State {
             Action::OpenFile(path) => {
                 self.read_from_frzp(&path)
             },
+            Action::ExportMesh(path) => {
+                self.write_mesh(&path)
+            },
+            Action::ExportGltf(path) => {
+                self.write_gltf(&path)
+            },
             Action::NewFile() => {
                 // reset the user state: this will zero out the node graph and its global vars
                 self.user = UserState::default();
@@ -213,6 +226,9 @@ impl State {
                     Ok((compute_graph, recoverable_errors)) => {
                         // run the first compute, and create the matcaps in the SceneRenderer
                         compute_graph.run_compute(&self.app.manager.device, &self.app.manager.queue);
+                        let (scalars, color_ranges) = compute_graph.read_values(&self.app.manager.device);
+                        self.user.node_graph.set_scalar_values(scalars);
+                        self.user.node_graph.set_color_ranges(color_ranges);
                         self.app.renderer.recreate_matcaps(&self.app.manager, &self.app.assets, compute_graph.matcaps());
                         self.app.comp_graph = Some(compute_graph);
                         if recoverable_errors.is_empty() {
@@ -234,7 +250,12 @@ impl State {
             Action::UpdateGlobals(pairs) => {
                 // if the compute graph exists, tell it to update the globals
                 if let Some(graph) = &mut self.app.comp_graph {
-                    graph.update_globals(&self.app.manager.device, &self.app.manager.queue, pairs);
+                    let values_changed = graph.update_globals(&self.app.manager.device, &self.app.manager.queue, pairs);
+                    if values_changed {
+                        let (scalars, color_ranges) = graph.read_values(&self.app.manager.device);
+                        self.user.node_graph.set_scalar_values(scalars);
+                        self.user.node_graph.set_color_ranges(color_ranges);
+                    }
                     Ok(())
                 } else {
                     dbg!("tried to update globals, but there is no graph!"); // TODO: better handling
@@ -244,7 +265,46 @@ impl State {
         }
     }
 
+    // exports the triangles of the scene that was last computed, grouped by Rendering node
+    fn write_mesh(&self, path: &Path) -> Result<(), String> {
+        let compute_graph = self.app.comp_graph.as_ref()
+            .ok_or_else(|| "there is no scene to export,\n please process the graph first".to_string())?;
+        let meshes: Vec<(String, usize, MeshData)> = compute_graph.read_meshes(&self.app.manager.device)
+            .into_iter()
+            .map(|(node_id, material_id, mesh)| (self.node_title(node_id), material_id, mesh))
+            .collect();
+        mesh_file::save(path, &meshes, &self.app.assets.material_colors)
+    }
+
+    // exports the scene that was last computed, together with the camera and the colors of the materials
+    fn write_gltf(&self, path: &Path) -> Result<(), String> {
+        let compute_graph = self.app.comp_graph.as_ref()
+            .ok_or_else(|| "there is no scene to export,\n please process the graph first".to_string())?;
+        // the meshes are sorted by node, so the meshes of each Rendering node are next to each other
+        let mut nodes: Vec<(String, Vec<(usize, MeshData)>)> = Vec::new();
+        let mut last_node_id = None;
+        for (node_id, material_id, mesh) in compute_graph.read_meshes(&self.app.manager.device) {
+            if last_node_id != Some(node_id) {
+                nodes.push((self.node_title(node_id), Vec::new()));
+                last_node_id = Some(node_id);
+            }
+            nodes.last_mut().unwrap().1.push((material_id, mesh));
+        }
+        let scene_camera = gltf::SceneCamera {
+            camera: &self.app.camera,
+            ortho: self.app.camera_ortho,
+            aspect_ratio: self.app.renderer.aspect_ratio(),
+        };
+        gltf::save(path, &nodes, &self.app.assets.material_colors, &scene_camera)
+    }
+
+    fn node_title(&self, node_id: NodeID) -> String {
+        self.user.node_graph.get_node(node_id)
+            .map_or_else(|| format!("node_{}", node_id), |node| node.title.clone())
+    }
+
     fn write_to_frzp(&mut self, path: &Path) {
+        self.update_mesh_files(path.parent());
         let mut file = std::fs::File::create(path).unwrap();
         let ser_config = ron::ser::PrettyConfig::new()
             .with_depth_limit(5)
@@ -287,7 +347,33 @@ impl State {
                 self.time_stamps = time_stamps;
             }
         }
+        self.user.directory = path.parent().map(Path::to_path_buf);
         self.user.node_graph.push_positions_to_imnodes();
         Ok(())
     }
+
+    // Makes the paths of the mesh files relative to the new directory of the scene file, when
+    // the meshes are inside it, and reads again the meshes that must be embedded in the scene file.
+    // If a mesh cannot be read, the previously embedded one is kept.
+    fn update_mesh_files(&mut self, new_directory: Option<&Path>) {
+        for (path, embed, embedded) in self.user.node_graph.get_mesh_files_mut() {
+            if path.is_empty() {
+                continue;
+            }
+            let full_path = mesh_file::resolve_path(self.user.directory.as_deref(), path);
+            if embed {
+                if let Ok(mesh) = MeshData::load(&full_path) {
+                    *embedded = Some(mesh);
+                }
+            } else {
+                *embedded = None;
+            }
+            *path = new_directory
+                .and_then(|directory| full_path.strip_prefix(directory).ok())
+                .unwrap_or(&full_path)
+                .to_string_lossy()
+                .into();
+        }
+        self.user.directory = new_directory.map(Path::to_path_buf);
+    }
 }
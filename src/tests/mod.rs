@@ -756,3 +756,262 @@ fn same_param2_surface_transform () {
     let transformed_data = copy_buffer_as_f32(transformed_block.get_buffer(), &device_manager.device);
     dbg!(transformed_data);
 }
+
+use crate::parser::{parse_expression, AstNode, Operator, MathFunc};
+
+// evaluates an expression in a single variable, which is enough to check derivatives numerically
+fn evaluate(node: &AstNode, variable: &str, x: f64) -> f64 {
+    let apply = |operator: &Operator, lhs: f64, rhs: f64| match operator {
+        Operator::Plus => lhs + rhs,
+        Operator::Minus => lhs - rhs,
+        Operator::Times => lhs * rhs,
+        Operator::Div => lhs / rhs,
+        Operator::Pow => lhs.powf(rhs),
+    };
+    match node {
+        AstNode::Number(value) => *value as f64,
+        AstNode::Ident(ident) => {
+            assert_eq!(ident, variable, "unexpected identifier in the test expression");
+            x
+        },
+        AstNode::UnaryOp{ operator: Operator::Minus, arg } => -evaluate(arg, variable, x),
+        AstNode::UnaryOp{ arg, .. } => evaluate(arg, variable, x),
+        AstNode::PowOp{ base, exp } => evaluate(base, variable, x).powf(evaluate(exp, variable, x)),
+        AstNode::BinOp{ lhs, repeated_rhs } => {
+            repeated_rhs.iter().fold(evaluate(lhs, variable, x), |value, (operator, rhs)| {
+                apply(operator, value, evaluate(rhs, variable, x))
+            })
+        },
+        AstNode::Func{ func, arg } => {
+            let value = evaluate(arg, variable, x);
+            match func {
+                MathFunc::Sin => value.sin(),
+                MathFunc::Cos => value.cos(),
+                MathFunc::Tan => value.tan(),
+                MathFunc::Asin => value.asin(),
+                MathFunc::Acos => value.acos(),
+                MathFunc::Atan => value.atan(),
+                MathFunc::Sqrt => value.sqrt(),
+                MathFunc::Exp => value.exp(),
+                MathFunc::Log => value.ln(),
+                MathFunc::Abs => value.abs(),
+            }
+        },
+    }
+}
+
+// compares the symbolic derivative with a central finite difference at a few points
+fn check_derivative(expression: &str, points: &[f64]) {
+    let ast = parse_expression(expression).unwrap();
+    let derivative = ast.derivative("t");
+    for &x in points {
+        let h = 1e-5;
+        let expected = (evaluate(&ast, "t", x + h) - evaluate(&ast, "t", x - h)) / (2.0 * h);
+        let computed = evaluate(&derivative, "t", x);
+        let tolerance = 1e-3 * (1.0 + expected.abs());
+        assert!((computed - expected).abs() < tolerance,
+            "derivative of {} at t = {}: computed {}, expected {}", expression, x, computed, expected);
+    }
+}
+
+#[test]
+fn derivative_of_constants_and_sums() {
+    check_derivative("3", &[0.0, 1.0]);
+    check_derivative("t + 2", &[0.0, 1.5]);
+    check_derivative("-t - 4*t + 1", &[-1.0, 2.0]);
+}
+
+#[test]
+fn derivative_product_rule() {
+    check_derivative("t * sin(t)", &[0.3, 1.2, -2.0]);
+    check_derivative("2 * t * t * t", &[0.5, -1.5]);
+}
+
+#[test]
+fn derivative_quotient_rule() {
+    check_derivative("sin(t) / t", &[0.4, 2.5]);
+    check_derivative("1 / (1 + t*t)", &[-1.0, 0.0, 0.7]);
+    check_derivative("t / 2 / (t + 3)", &[0.5, 4.0]);
+}
+
+#[test]
+fn derivative_power_rule() {
+    check_derivative("t^3", &[-1.2, 0.5, 2.0]);
+    check_derivative("(2*t + 1)^2", &[0.0, 1.5]);
+    check_derivative("t^t", &[0.5, 1.5]);
+    check_derivative("2^t", &[-1.0, 3.0]);
+}
+
+#[test]
+fn derivative_chain_rule() {
+    check_derivative("sin(t*t)", &[0.3, 1.1]);
+    check_derivative("exp(cos(2*t))", &[0.2, 2.0]);
+    check_derivative("sqrt(1 + t*t)", &[-0.5, 1.5]);
+    check_derivative("log(abs(t) + 1)", &[-2.0, 0.7]);
+    check_derivative("atan(t/2) + tan(t/3) + asin(t/4) + acos(t/5)", &[0.1, 1.0]);
+    // the cartesian expressions of the Cylindrical and Spherical Curve nodes
+    check_derivative("(1 + t)*sin((t))*cos((2*t))", &[0.4, 1.3]);
+}
+
+use crate::rendering::mesh_file::{self, MeshData};
+
+// a unit square in the z = 0 plane, made of two triangles
+fn square() -> MeshData {
+    MeshData {
+        positions: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+        normals: vec![[0.0, 0.0, 1.0]; 4],
+        uv_coords: vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    }
+}
+
+// the corners of each triangle, so that meshes that do not share vertices in the same way can be compared
+fn triangles(mesh: &MeshData) -> Vec<[[f32; 3]; 3]> {
+    mesh.indices.chunks_exact(3)
+        .map(|triangle| [0, 1, 2].map(|i| mesh.positions[triangle[i] as usize]))
+        .collect()
+}
+
+#[test]
+fn stl_round_trip() {
+    let meshes = vec![("Square".to_string(), 0, square())];
+    let mesh = MeshData::from_stl(&mesh_file::to_stl(&meshes)).unwrap();
+    mesh.validate().unwrap();
+    assert_eq!(triangles(&mesh), triangles(&square()));
+    assert!(mesh.normals.iter().all(|normal| *normal == [0.0, 0.0, 1.0]));
+}
+
+#[test]
+fn ply_round_trip() {
+    let meshes = vec![("Square".to_string(), 0, square()), ("Other square".to_string(), 1, square())];
+    let mesh = MeshData::from_ply(&mesh_file::to_ply(&meshes)).unwrap();
+    mesh.validate().unwrap();
+    assert_eq!(mesh.positions, [square().positions, square().positions].concat());
+    assert_eq!(mesh.normals, [square().normals, square().normals].concat());
+    assert_eq!(mesh.uv_coords, [square().uv_coords, square().uv_coords].concat());
+    assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+}
+
+#[test]
+fn obj_round_trip() {
+    let meshes = vec![("Square".to_string(), 0, square()), ("Other square".to_string(), 2, square())];
+    let contents = mesh_file::to_obj(&meshes, "scene.mtl");
+    let text = std::str::from_utf8(&contents).unwrap();
+    assert!(text.contains("mtllib scene.mtl\n"));
+    assert!(text.contains("g Other_square\nusemtl material_2\n"));
+    let data = obj::ObjData::load_buf(contents.as_slice()).unwrap();
+    let mesh = MeshData::from_obj(&data).unwrap();
+    mesh.validate().unwrap();
+    assert_eq!(triangles(&mesh), [triangles(&square()), triangles(&square())].concat());
+    assert!(mesh.normals.iter().all(|normal| *normal == [0.0, 0.0, 1.0]));
+}
+
+#[test]
+fn mtl_uses_the_material_colors() {
+    let meshes = vec![("Square".to_string(), 1, square()), ("Other square".to_string(), 5, square())];
+    let contents = String::from_utf8(mesh_file::to_mtl(&meshes, &[[0.0, 0.0, 0.0, 1.0], [0.5, 0.25, 1.0, 1.0]])).unwrap();
+    assert!(contents.contains("newmtl material_1\nKd 0.5 0.25 1\n"));
+    // materials that are missing from the palette are white
+    assert!(contents.contains("newmtl material_5\nKd 1 1 1\n"));
+    assert!(!contents.contains("material_0"));
+}
+
+#[test]
+fn ascii_mesh_files() {
+    let stl = "solid square
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 0 0
+vertex 1 1 0
+endloop
+endfacet
+endsolid square
+";
+    let mesh = MeshData::from_stl(stl.as_bytes()).unwrap();
+    assert_eq!(triangles(&mesh), vec![[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]]);
+
+    // the normals are computed, since the file does not provide them
+    let ply = "ply
+format ascii 1.0
+element vertex 4
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+4 0 1 2 3
+";
+    let mesh = MeshData::from_ply(ply.as_bytes()).unwrap();
+    mesh.validate().unwrap();
+    assert_eq!(triangles(&mesh), triangles(&square()));
+    assert!(mesh.normals.iter().all(|normal| (normal[2] - 1.0).abs() < 1e-6));
+}
+
+#[test]
+fn invalid_meshes() {
+    let data = obj::ObjData::load_buf("v 0 0 0\nv 1 0 0\nf 1 2 3\n".as_bytes()).unwrap();
+    assert!(MeshData::from_obj(&data).is_err());
+    let mut mesh = square();
+    mesh.indices[5] = 4;
+    assert!(mesh.validate().is_err());
+    let mut mesh = square();
+    mesh.uv_coords.pop();
+    assert!(mesh.validate().is_err());
+}
+
+use crate::rendering::gltf;
+use crate::rendering::camera::Camera;
+
+#[test]
+fn gltf_layout() {
+    let nodes = vec![
+        ("Curve".to_string(), vec![(0, square()), (2, square())]),
+        ("Surface".to_string(), vec![(1, square())]),
+    ];
+    let camera = Camera::default();
+    let scene_camera = gltf::SceneCamera {
+        camera: &camera,
+        ortho: false,
+        aspect_ratio: 1.5,
+    };
+    let path = std::env::temp_dir().join("franzplot_gltf_layout.glb");
+    gltf::save(&path, &nodes, &[[1.0, 0.0, 0.0, 1.0]; 3], &scene_camera).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let read_u32 = |offset: usize| u32::from_le_bytes([contents[offset], contents[offset + 1], contents[offset + 2], contents[offset + 3]]) as usize;
+
+    // 12 bytes header: magic, version and total length
+    assert_eq!(&contents[0..4], b"glTF");
+    assert_eq!(read_u32(4), 2);
+    assert_eq!(read_u32(8), contents.len());
+
+    // the json chunk comes first, followed by the binary chunk, and both are 4 bytes aligned
+    let json_length = read_u32(12);
+    assert_eq!(&contents[16..20], b"JSON");
+    assert_eq!(json_length % 4, 0);
+    let binary_start = 20 + json_length;
+    let binary_length = read_u32(binary_start);
+    assert_eq!(&contents[binary_start + 4 .. binary_start + 8], b"BIN\0");
+    assert_eq!(binary_length % 4, 0);
+    assert_eq!(binary_start + 8 + binary_length, contents.len());
+
+    let document: serde_json::Value = serde_json::from_slice(&contents[20..binary_start]).unwrap();
+    assert_eq!(document["buffers"][0]["byteLength"].as_u64(), Some(binary_length as u64));
+    // a root node, a node with a mesh for each Rendering entry and the camera node
+    let gltf_nodes = document["nodes"].as_array().unwrap();
+    assert_eq!(gltf_nodes.len(), 1 + nodes.len() + 1);
+    assert_eq!(gltf_nodes[0]["children"].as_array().unwrap().len(), nodes.len() + 1);
+    for (i, (name, node_meshes)) in nodes.iter().enumerate() {
+        let mesh = &document["meshes"][gltf_nodes[i + 1]["mesh"].as_u64().unwrap() as usize];
+        assert_eq!(gltf_nodes[i + 1]["name"], name.as_str());
+        assert_eq!(mesh["primitives"].as_array().unwrap().len(), node_meshes.len());
+    }
+    assert_eq!(gltf_nodes[nodes.len() + 1]["camera"], 0);
+    assert_eq!(document["materials"].as_array().unwrap().len(), 3);
+}
@@ -87,11 +87,31 @@ pub fn load_materials<P: AsRef<std::path::Path>>(manager: &device_manager::Manag
         .collect()
 }
 
+// the base color of each material is the color at the center of its matcap,
+// which is the color of the surfaces that face the camera. It is converted to linear rgb.
+pub fn load_material_colors<P: AsRef<std::path::Path>>(files: &[P]) -> Vec<[f32; 4]> {
+    files.iter()
+        .map(|path| {
+            let image = image::open(path).unwrap().to_rgba8();
+            let pixel = image.get_pixel(image.width() / 2, image.height() / 2);
+            let to_linear = |value: u8| {
+                let srgb = value as f32 / 255.0;
+                if srgb <= 0.04045 {
+                    srgb / 12.92
+                } else {
+                    ((srgb + 0.055) / 1.055).powf(2.4)
+                }
+            };
+            [to_linear(pixel[0]), to_linear(pixel[1]), to_linear(pixel[2]), 1.0]
+        })
+        .collect()
+}
+
 pub fn load_models<P: AsRef<std::path::Path>>(device: &wgpu::Device, files: &[P]) -> Vec<model::Model> {
     files.iter()
         .map(|path| {
             let obj_contents = obj::Obj::load(path).unwrap();
-            model::Model::from_obj(device, &obj_contents.data)
+            model::Model::from_obj(device, &obj_contents.data).unwrap()
         })
         .collect()
 }
@@ -163,6 +183,12 @@ impl FourBytes for i32 {
     }
 }
 
+impl FourBytes for u32 {
+    fn from_bytes(bytes: [u8; 4]) -> Self {
+        u32::from_ne_bytes(bytes)
+    }
+}
+
 // maps a buffer, waits for it to be available, and copies its contents into a new Vec<T>
 #[allow(unused)]
 pub fn copy_buffer_as<T: FourBytes>(buffer: &wgpu::Buffer, device: &wgpu::Device) -> Vec<T> {